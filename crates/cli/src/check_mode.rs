@@ -0,0 +1,121 @@
+use std::{path::PathBuf, str::FromStr};
+
+use anyhow::{bail, Context};
+use client::{
+    types::lang::{CommandArg, Statement},
+    CommandType,
+};
+use executor::exec::ExecScope;
+use parser::{line_col, script_recovering};
+
+/// Parses and type-checks a script without running it, printing all the errors found.
+pub async fn run(script_file: PathBuf) -> anyhow::Result<()> {
+    let script = tokio::fs::read_to_string(&script_file)
+        .await
+        .context("failed to read script file")?;
+
+    let path = script_file.display();
+    let mut errors_count = 0;
+
+    let parsed = script_recovering(&script);
+
+    for err in &parsed.errors {
+        println!(
+            "{path}:{loc}: syntax error: expected {expected}",
+            loc = err.location,
+            expected = err.expected
+        );
+        errors_count += 1;
+    }
+
+    let mut scope = ExecScope::new();
+    for stmt in parsed.statements {
+        let loc = line_col(&script, stmt.span.start);
+
+        let res = match stmt.value {
+            Statement::Definition(def) => scope
+                .check_definition(def)
+                .map(|warnings| {
+                    for warning in warnings {
+                        println!("{path}:{loc}: warning: {warning}");
+                    }
+                })
+                .map_err(anyhow::Error::from),
+            Statement::Command(cmd) => CommandType::from_str(&cmd.name.0).and_then(|cmd_type| {
+                match cmd_type {
+                    CommandType::Clear => scope.clear(),
+
+                    // Removed items can be defined again
+                    CommandType::Rm => {
+                        if let [CommandArg::Ident(name)] = &cmd.args[..] {
+                            let is_layer = scope
+                                .get_all_layers()
+                                .iter()
+                                .any(|layer| &layer.name == name);
+                            if is_layer {
+                                scope.rm_layer(name)?;
+                            } else {
+                                scope.rm(name.clone())?;
+                            }
+                            return Ok(());
+                        }
+                    }
+
+                    // Layers are needed to remove them; their flags are not applied
+                    CommandType::Layer => {
+                        if let Some(CommandArg::Ident(name)) = cmd.args.first() {
+                            let items = cmd.args[1..]
+                                .iter()
+                                .map_while(|arg| match arg {
+                                    CommandArg::Ident(item) => Some(item.clone()),
+                                    CommandArg::Expr(_) => None,
+                                })
+                                .collect();
+                            scope.add_to_layer(name.clone(), items)?;
+                        }
+                    }
+
+                    CommandType::Exact => {
+                        if let [CommandArg::Ident(mode)] = &cmd.args[..] {
+                            match mode.0.as_str() {
+                                "on" => scope.set_exact(true)?,
+                                "off" => scope.set_exact(false)?,
+                                _ => bail!("expected `on` or `off`, got {mode}"),
+                            }
+                            return Ok(());
+                        }
+                    }
+
+                    // Imported functions are needed to check the rest of the script
+                    CommandType::Import => {
+                        for warning in scope.import_command(cmd.args)? {
+                            println!("{path}:{loc}: warning: {warning}");
+                        }
+                        return Ok(());
+                    }
+
+                    _ => {}
+                }
+
+                for arg in cmd.args {
+                    if let CommandArg::Expr(expr) = arg {
+                        scope.check_expr(expr)?;
+                    }
+                }
+
+                Ok(())
+            }),
+        };
+
+        if let Err(err) = res {
+            println!("{path}:{loc}: error: {err:#}");
+            errors_count += 1;
+        }
+    }
+
+    if errors_count > 0 {
+        bail!("{errors_count} error(s) found");
+    }
+
+    Ok(())
+}
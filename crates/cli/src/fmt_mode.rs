@@ -0,0 +1,45 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Context};
+
+/// Formats scripts in place.
+///
+/// With `check` files are not modified; fails if any of them is not formatted.
+pub async fn run(script_files: Vec<PathBuf>, check: bool) -> anyhow::Result<()> {
+    let mut unformatted_count = 0;
+
+    for script_file in script_files {
+        let path = script_file.display();
+
+        let script = tokio::fs::read_to_string(&script_file)
+            .await
+            .with_context(|| format!("failed to read script file {path}"))?;
+
+        let formatted = parser::format(&script).map_err(|err| {
+            anyhow::anyhow!(
+                "{path}:{loc}: syntax error: expected {expected}",
+                loc = err.location,
+                expected = err.expected
+            )
+        })?;
+
+        if formatted == script {
+            continue;
+        }
+
+        if check {
+            println!("{path}: not formatted");
+            unformatted_count += 1;
+        } else {
+            tokio::fs::write(&script_file, formatted)
+                .await
+                .with_context(|| format!("failed to write script file {path}"))?;
+        }
+    }
+
+    if unformatted_count > 0 {
+        bail!("{unformatted_count} file(s) not formatted");
+    }
+
+    Ok(())
+}
@@ -6,14 +6,20 @@ use client::{Client, ClientSettings};
 use printing::ScriptResultPrinter;
 use url::Url;
 
+mod check_mode;
+mod fmt_mode;
 mod printing;
 mod script_file_mode;
 mod stdin_mode;
+mod test_mode;
 mod tty_mode;
 
 #[derive(clap::Parser)]
-#[command(version, about, long_about = None)]
+#[command(version, about, long_about = None, args_conflicts_with_subcommands = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<CliCommand>,
+
     script_file: Option<PathBuf>,
 
     #[arg(long, default_value_t = Url::parse("http://127.0.0.1:4242").unwrap())]
@@ -21,19 +27,59 @@ struct Cli {
 
     #[arg(long, default_value_t = true)]
     do_init_server: bool,
+
+    /// Execute scripts in-process, without a server
+    #[arg(long)]
+    embedded: bool,
     // TODO: server args
 }
 
+#[derive(clap::Subcommand)]
+enum CliCommand {
+    /// Parse and type-check a script without running it; no server is needed
+    Check { script_file: PathBuf },
+
+    /// Format scripts in place; no server is needed
+    Fmt {
+        #[arg(required = true)]
+        script_files: Vec<PathBuf>,
+
+        /// Don't write files, fail if any of them is not formatted
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Run every .geom file in a directory against a fresh scope; no server is needed
+    ///
+    /// Prints pass or fail for every file and exits with a non-zero code,
+    /// if any of them fails
+    Test { dir: PathBuf },
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
 
-    let client = Client::from(ClientSettings {
-        server_url: cli.server_url,
-        try_spawn_server: cli.do_init_server,
-    })
-    .await
-    .context("failed to connect to server")?;
+    match cli.command {
+        Some(CliCommand::Check { script_file }) => return check_mode::run(script_file).await,
+        Some(CliCommand::Fmt {
+            script_files,
+            check,
+        }) => return fmt_mode::run(script_files, check).await,
+        Some(CliCommand::Test { dir }) => return test_mode::run(dir).await,
+        None => {}
+    }
+
+    let client = if cli.embedded {
+        Client::new_embedded()
+    } else {
+        Client::from(ClientSettings {
+            server_url: cli.server_url,
+            try_spawn_server: cli.do_init_server,
+        })
+        .await
+        .context("failed to connect to server")?
+    };
 
     if let Some(script_file) = cli.script_file {
         script_file_mode::run(client, script_file).await?;
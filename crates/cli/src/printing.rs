@@ -19,6 +19,10 @@ impl Display for ScriptResultPrinter<'_> {
             writeln!(f, "{}\n", TablePrinter(table))?;
         }
 
+        for warning in &res.warnings {
+            writeln!(f, "warning: {warning}")?;
+        }
+
         if let Some(err) = &res.error {
             writeln!(f, "error: {:?}", err)?;
         }
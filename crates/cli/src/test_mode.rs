@@ -0,0 +1,59 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+use client::Client;
+
+/// Runs every `.geom` file in the directory, each against a fresh scope.
+///
+/// Scripts are executed in-process; a file passes, if none of it's statements fail,
+/// e.g. `assert!` or `assert_eq!`. Fails if any file fails.
+pub async fn run(dir: PathBuf) -> anyhow::Result<()> {
+    let mut script_files = Vec::new();
+    collect_script_files(&dir, &mut script_files)
+        .with_context(|| format!("failed to read directory {}", dir.display()))?;
+    script_files.sort();
+
+    if script_files.is_empty() {
+        bail!("no .geom files found in {}", dir.display());
+    }
+
+    let mut failed_count = 0;
+    for script_file in &script_files {
+        let path = script_file.display();
+
+        let res = match tokio::fs::read_to_string(script_file).await {
+            Ok(script) => Client::new_embedded().exec(script).await.error,
+            Err(err) => Some(anyhow::Error::from(err).context("failed to read script file")),
+        };
+
+        match res {
+            None => println!("{path}: ok"),
+            Some(err) => {
+                println!("{path}: FAILED: {err:#}");
+                failed_count += 1;
+            }
+        }
+    }
+
+    let passed_count = script_files.len() - failed_count;
+    println!("{passed_count} passed, {failed_count} failed");
+
+    if failed_count > 0 {
+        bail!("{failed_count} file(s) failed");
+    }
+
+    Ok(())
+}
+
+/// Finds `.geom` files in the directory and it's subdirectories.
+fn collect_script_files(dir: &Path, script_files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_script_files(&path, script_files)?;
+        } else if path.extension().is_some_and(|ext| ext == "geom") {
+            script_files.push(path);
+        }
+    }
+    Ok(())
+}
@@ -1,20 +1,52 @@
 use std::{collections::HashMap, path::Path};
 
-use anyhow::Context;
+use anyhow::{bail, Context};
 use parser::ParseInto;
 use reqwest::Url;
 use types::{
-    api::{self, FunctionList},
-    core::{Ident, Value},
-    lang::{Definition, Expr, Statement},
+    api::{self, FunctionList, LibrarySource},
+    check::{CheckOptions, CheckReport},
+    core::{Ident, PtOn, Value},
+    export::{
+        AnimationOptions, DrawingFormat, ExportOptions, FrameFormat, ImportOptions, PdfOptions,
+        Sweep, TikzOptions,
+    },
+    lang::{Definition, Expr, Statement, Warning},
+    layer::Layer,
+    slider::Slider,
+    style::Style,
 };
 
 use crate::ScriptResult;
 
 #[derive(Debug, Clone)]
 pub struct Client {
-    pub(crate) server_url: Url,
-    pub(crate) client: reqwest::Client,
+    pub(crate) backend: Backend,
+}
+
+#[derive(Clone)]
+pub(crate) enum Backend {
+    Server {
+        server_url: Url,
+        client: reqwest::Client,
+    },
+
+    /// Requests are handled in-process by the [executor]
+    #[cfg(feature = "embedded")]
+    Embedded(std::sync::Arc<tokio::sync::Mutex<executor::exec::ExecScope>>),
+}
+
+impl std::fmt::Debug for Backend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Backend::Server { server_url, .. } => f
+                .debug_struct("Server")
+                .field("server_url", server_url)
+                .finish(),
+            #[cfg(feature = "embedded")]
+            Backend::Embedded(_) => f.debug_struct("Embedded").finish(),
+        }
+    }
 }
 
 impl Client {
@@ -26,24 +58,32 @@ impl Client {
         Ok(())
     }
 
-    pub async fn define_one(&self, def: impl ParseInto<Definition>) -> anyhow::Result<()> {
-        self.req(api::exec::Request {
-            defs: vec![def.parse_into().context("failed to parse definition")?],
-        })
-        .await
-        .context("define failed")?;
+    pub async fn define_one(
+        &self,
+        def: impl ParseInto<Definition>,
+    ) -> anyhow::Result<Vec<Warning>> {
+        let resp = self
+            .req(api::exec::Request {
+                defs: vec![def.parse_into().context("failed to parse definition")?],
+            })
+            .await
+            .context("define failed")?;
 
-        Ok(())
+        Ok(resp.warnings)
     }
 
-    pub async fn define(&self, defs: impl ParseInto<Vec<Definition>>) -> anyhow::Result<()> {
-        self.req(api::exec::Request {
-            defs: defs.parse_into().context("failed to parse definitions")?,
-        })
-        .await
-        .context("define failed")?;
+    pub async fn define(
+        &self,
+        defs: impl ParseInto<Vec<Definition>>,
+    ) -> anyhow::Result<Vec<Warning>> {
+        let resp = self
+            .req(api::exec::Request {
+                defs: defs.parse_into().context("failed to parse definitions")?,
+            })
+            .await
+            .context("define failed")?;
 
-        Ok(())
+        Ok(resp.warnings)
     }
 
     pub async fn eval_one(&self, expr: impl ParseInto<Expr>) -> anyhow::Result<Value> {
@@ -92,17 +132,19 @@ impl Client {
         };
 
         let mut ans = Vec::new();
+        let mut warnings = Vec::new();
 
         for stmt in script {
             let res = self.exec_one(stmt).await;
             ans.extend(res.results);
+            warnings.extend(res.warnings);
 
             if let Some(err) = res.error {
-                return ScriptResult::partail_error(ans, err);
+                return ScriptResult::partail_error(ans, err).with_warnings(warnings);
             }
         }
 
-        ScriptResult::ok(ans)
+        ScriptResult::ok(ans).with_warnings(warnings)
     }
 
     /// Parses and executes the statement.
@@ -115,7 +157,7 @@ impl Client {
         match stmt {
             Statement::Definition(def) => {
                 match self.define_one(def).await.context("define failed") {
-                    Ok(()) => ScriptResult::ok_none(),
+                    Ok(warnings) => ScriptResult::ok_none().with_warnings(warnings),
                     Err(err) => ScriptResult::error(err),
                 }
             }
@@ -177,6 +219,197 @@ impl Client {
         Ok(())
     }
 
+    /// Imports function definitions from a library, see [api::import].
+    pub async fn import(
+        &self,
+        source: LibrarySource,
+        namespace: Option<Ident>,
+    ) -> anyhow::Result<Vec<Warning>> {
+        let resp = self
+            .req(api::import::Request { source, namespace })
+            .await
+            .context("failed to import")?;
+
+        Ok(resp.warnings)
+    }
+
+    /// Defines items for primitives of a vector drawing.
+    ///
+    /// Returns names of the new items.
+    pub async fn import_drawing(
+        &self,
+        format: DrawingFormat,
+        content: String,
+        options: ImportOptions,
+    ) -> anyhow::Result<Vec<Ident>> {
+        let resp = self
+            .req(api::drawing::import::Request {
+                format,
+                content,
+                options,
+            })
+            .await
+            .context("failed to import drawing")?;
+
+        Ok(resp.items)
+    }
+
+    pub async fn load_drawing(
+        &self,
+        file: &Path,
+        format: DrawingFormat,
+        options: ImportOptions,
+    ) -> anyhow::Result<Vec<Ident>> {
+        let content = tokio::fs::read_to_string(file)
+            .await
+            .context("failed to read file")?;
+        self.import_drawing(format, content, options)
+            .await
+            .context("import_drawing failed")
+    }
+
+    pub async fn get_style(&self, name: impl Into<Ident>) -> anyhow::Result<Style> {
+        let name = name.into();
+        let resp = self
+            .req(api::style::get::Request { name: name.clone() })
+            .await
+            .context(format!("failed to get style of '{name}'"))?;
+
+        Ok(resp.style)
+    }
+
+    /// Returns styles of items, that are not drawn in the default way.
+    pub async fn get_all_styles(&self) -> anyhow::Result<HashMap<Ident, Style>> {
+        let resp = self
+            .req(api::style::get_all::Request {})
+            .await
+            .context("failed to get all styles")?;
+
+        Ok(resp.styles)
+    }
+
+    pub async fn set_style(&self, name: impl Into<Ident>, style: Style) -> anyhow::Result<()> {
+        let name = name.into();
+        self.req(api::style::set::Request {
+            name: name.clone(),
+            style,
+        })
+        .await
+        .context(format!("failed to set style of '{name}'"))?;
+
+        Ok(())
+    }
+
+    pub async fn get_all_sliders(&self) -> anyhow::Result<HashMap<Ident, Slider>> {
+        let resp = self
+            .req(api::slider::get_all::Request {})
+            .await
+            .context("failed to get all sliders")?;
+
+        Ok(resp.sliders)
+    }
+
+    /// Puts a slider on a free numeric item or removes it with `None`.
+    pub async fn set_slider(
+        &self,
+        name: impl Into<Ident>,
+        slider: Option<Slider>,
+    ) -> anyhow::Result<()> {
+        let name = name.into();
+        self.req(api::slider::set::Request {
+            name: name.clone(),
+            slider,
+        })
+        .await
+        .context(format!("failed to set slider of '{name}'"))?;
+
+        Ok(())
+    }
+
+    /// Returns points, that are defined with `pt_on` and can be moved by their parameter.
+    pub async fn get_all_pt_ons(&self) -> anyhow::Result<HashMap<Ident, PtOn>> {
+        let resp = self
+            .req(api::pt_on::get_all::Request {})
+            .await
+            .context("failed to get all constrained points")?;
+
+        Ok(resp.pt_ons)
+    }
+
+    /// Returns labels of items, that have them shown.
+    pub async fn get_all_labels(&self) -> anyhow::Result<HashMap<Ident, String>> {
+        let resp = self
+            .req(api::labels::get_all::Request {})
+            .await
+            .context("failed to get all labels")?;
+
+        Ok(resp.labels)
+    }
+
+    /// Shows or hides labels of items, that don't override it in their styles.
+    pub async fn show_labels(&self, show: bool) -> anyhow::Result<()> {
+        self.req(api::labels::show::Request { show })
+            .await
+            .context("failed to show labels")?;
+
+        Ok(())
+    }
+
+    pub async fn get_all_layers(&self) -> anyhow::Result<Vec<Layer>> {
+        let resp = self
+            .req(api::layer::get_all::Request {})
+            .await
+            .context("failed to get all layers")?;
+
+        Ok(resp.layers)
+    }
+
+    /// Moves items to the layer, creating it if needed.
+    pub async fn add_to_layer(
+        &self,
+        name: impl Into<Ident>,
+        items: Vec<Ident>,
+    ) -> anyhow::Result<()> {
+        let name = name.into();
+        self.req(api::layer::add::Request {
+            name: name.clone(),
+            items,
+        })
+        .await
+        .context(format!("failed to add items to layer '{name}'"))?;
+
+        Ok(())
+    }
+
+    /// Changes the given flags of the layer; `None` keeps a flag as is.
+    pub async fn update_layer(
+        &self,
+        name: impl Into<Ident>,
+        hidden: Option<bool>,
+        locked: Option<bool>,
+    ) -> anyhow::Result<()> {
+        let name = name.into();
+        self.req(api::layer::update::Request {
+            name: name.clone(),
+            hidden,
+            locked,
+        })
+        .await
+        .context(format!("failed to update layer '{name}'"))?;
+
+        Ok(())
+    }
+
+    /// Removes the layer along with it's items.
+    pub async fn rm_layer(&self, name: impl Into<Ident>) -> anyhow::Result<()> {
+        let name = name.into();
+        self.req(api::layer::rm::Request { name: name.clone() })
+            .await
+            .context(format!("failed to rm layer '{name}'"))?;
+
+        Ok(())
+    }
+
     pub async fn load_json(&self, json: impl ToString) -> anyhow::Result<()> {
         self.req(api::json::load::Request {
             json: json.to_string(),
@@ -211,19 +444,193 @@ impl Client {
         Ok(())
     }
 
-    pub async fn dump_svg(&self) -> anyhow::Result<String> {
+    pub async fn dump_svg(&self, options: ExportOptions) -> anyhow::Result<String> {
         let resp = self
-            .req(api::svg::dump::Request {})
+            .req(api::svg::dump::Request { options })
             .await
             .context("failed to dump to svg")?;
         Ok(resp.svg)
     }
 
-    pub async fn save_svg(&self, file: &Path) -> anyhow::Result<()> {
-        let svg = self.dump_svg().await.context("dump_svg failed")?;
+    pub async fn save_svg(&self, file: &Path, options: ExportOptions) -> anyhow::Result<()> {
+        let svg = self.dump_svg(options).await.context("dump_svg failed")?;
         tokio::fs::write(file, svg.as_bytes())
             .await
             .context("failed to write to file")?;
         Ok(())
     }
+
+    pub async fn dump_png(&self, options: ExportOptions) -> anyhow::Result<Vec<u8>> {
+        let resp = self
+            .req(api::png::dump::Request { options })
+            .await
+            .context("failed to dump to png")?;
+        Ok(resp.png)
+    }
+
+    pub async fn save_png(&self, file: &Path, options: ExportOptions) -> anyhow::Result<()> {
+        let png = self.dump_png(options).await.context("dump_png failed")?;
+        tokio::fs::write(file, png)
+            .await
+            .context("failed to write to file")?;
+        Ok(())
+    }
+
+    pub async fn dump_tikz(&self, options: TikzOptions) -> anyhow::Result<String> {
+        let resp = self
+            .req(api::tikz::dump::Request { options })
+            .await
+            .context("failed to dump to tikz")?;
+        Ok(resp.tikz)
+    }
+
+    pub async fn save_tikz(&self, file: &Path, options: TikzOptions) -> anyhow::Result<()> {
+        let tikz = self.dump_tikz(options).await.context("dump_tikz failed")?;
+        tokio::fs::write(file, tikz.as_bytes())
+            .await
+            .context("failed to write to file")?;
+        Ok(())
+    }
+
+    pub async fn dump_pdf(&self, options: PdfOptions) -> anyhow::Result<Vec<u8>> {
+        let resp = self
+            .req(api::pdf::dump::Request { options })
+            .await
+            .context("failed to dump to pdf")?;
+        Ok(resp.pdf)
+    }
+
+    pub async fn save_pdf(&self, file: &Path, options: PdfOptions) -> anyhow::Result<()> {
+        let pdf = self.dump_pdf(options).await.context("dump_pdf failed")?;
+        tokio::fs::write(file, pdf)
+            .await
+            .context("failed to write to file")?;
+        Ok(())
+    }
+
+    pub async fn dump_animation_svg(
+        &self,
+        name: impl Into<Ident>,
+        sweep: Sweep,
+        options: AnimationOptions,
+    ) -> anyhow::Result<String> {
+        let resp = self
+            .req(api::animation::svg::Request {
+                name: name.into(),
+                sweep,
+                options,
+            })
+            .await
+            .context("failed to dump animation to svg")?;
+        Ok(resp.svg)
+    }
+
+    pub async fn dump_animation_frames(
+        &self,
+        name: impl Into<Ident>,
+        sweep: Sweep,
+        format: FrameFormat,
+        options: AnimationOptions,
+    ) -> anyhow::Result<Vec<Vec<u8>>> {
+        let resp = self
+            .req(api::animation::frames::Request {
+                name: name.into(),
+                sweep,
+                format,
+                options,
+            })
+            .await
+            .context("failed to dump animation frames")?;
+        Ok(resp.frames)
+    }
+
+    /// Saves an animation, that sweeps the item, to svg or png by the file's extension.
+    ///
+    /// Frames are saved next to the file with their numbers, e.g. `anim_007.png`,
+    /// unless they are put into a single animated svg.
+    pub async fn save_animation(
+        &self,
+        file: &Path,
+        name: impl Into<Ident>,
+        sweep: Sweep,
+        options: AnimationOptions,
+    ) -> anyhow::Result<()> {
+        let name = name.into();
+
+        let format = match file.extension().and_then(|ext| ext.to_str()) {
+            Some("svg") if options.animated => {
+                let svg = self
+                    .dump_animation_svg(name, sweep, options)
+                    .await
+                    .context("dump_animation_svg failed")?;
+                tokio::fs::write(file, svg.as_bytes())
+                    .await
+                    .context("failed to write to file")?;
+                return Ok(());
+            }
+            Some("svg") => FrameFormat::Svg,
+            Some("png") => FrameFormat::Png,
+            _ => bail!("animation can only be saved to svg or png"),
+        };
+
+        let frames = self
+            .dump_animation_frames(name, sweep, format, options)
+            .await
+            .context("dump_animation_frames failed")?;
+
+        let width = frames.len().saturating_sub(1).to_string().len().max(3);
+        let stem = file.file_stem().unwrap_or_default().to_string_lossy();
+        let ext = file.extension().unwrap_or_default().to_string_lossy();
+        for (idx, frame) in frames.into_iter().enumerate() {
+            let frame_file = file.with_file_name(format!("{stem}_{idx:0width$}.{ext}"));
+            tokio::fs::write(&frame_file, frame)
+                .await
+                .with_context(|| format!("failed to write to file {}", frame_file.display()))?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn check(
+        &self,
+        expr: impl ParseInto<Expr>,
+        options: CheckOptions,
+    ) -> anyhow::Result<CheckReport> {
+        let expr = expr.parse_into().context("failed to parse expr")?;
+        let resp = self
+            .req(api::check::Request { expr, options })
+            .await
+            .context("failed to check expr")?;
+        Ok(resp.report)
+    }
+
+    pub async fn get_tolerance(&self) -> anyhow::Result<f64> {
+        let resp = self
+            .req(api::tolerance::get::Request {})
+            .await
+            .context("failed to get tolerance")?;
+        Ok(resp.tolerance)
+    }
+
+    pub async fn set_tolerance(&self, tolerance: f64) -> anyhow::Result<()> {
+        self.req(api::tolerance::set::Request { tolerance })
+            .await
+            .context("failed to set tolerance")?;
+        Ok(())
+    }
+
+    pub async fn is_exact(&self) -> anyhow::Result<bool> {
+        let resp = self
+            .req(api::exact::get::Request {})
+            .await
+            .context("failed to get arithmetic")?;
+        Ok(resp.exact)
+    }
+
+    pub async fn set_exact(&self, exact: bool) -> anyhow::Result<()> {
+        self.req(api::exact::set::Request { exact })
+            .await
+            .context("failed to set arithmetic")?;
+        Ok(())
+    }
 }
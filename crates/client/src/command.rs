@@ -1,14 +1,23 @@
 use indoc::indoc;
-use std::{path::PathBuf, str::FromStr};
+use std::{
+    path::{self, PathBuf},
+    str::FromStr,
+};
 
 use anyhow::{anyhow, bail, Context};
 use enum_iterator::Sequence;
 use itertools::Itertools;
 use parser::ParseInto;
 use types::{
-    api::FunctionList,
+    api::{FunctionList, LibrarySource},
+    check::CheckOptions,
     core::Value,
+    export::{
+        AnimationOptions, DrawingFormat, ExportOptions, ExportOptionsError, ImportOptions,
+        PdfOptions, Sweep, TikzOptions,
+    },
     lang::{Command, CommandArg, Expr},
+    slider::Slider,
 };
 
 use crate::{table::Table, Client, ScriptResult};
@@ -46,6 +55,22 @@ pub enum CommandType {
     Save,
     Load,
     SaveSvg,
+    SavePng,
+    SaveTikz,
+    SavePdf,
+    Animate,
+    Check,
+    Assert,
+    AssertEq,
+    Tolerance,
+    Exact,
+    LoadSvg,
+    LoadDxf,
+    Import,
+    Style,
+    Slider,
+    Labels,
+    Layer,
 }
 
 impl FromStr for CommandType {
@@ -75,7 +100,14 @@ impl CommandType {
             CommandType::GetAll => ("get_all", "-", "get all items' values"),
             CommandType::Eval => ("eval", "expr+", "evaluate some expressions"),
             CommandType::Set => ("set", "ident expr", "set item's value to expr's value"),
-            CommandType::Rm => ("rm", "ident+", "remove some items"),
+            CommandType::Rm => (
+                "rm",
+                "ident+",
+                indoc!(
+                    "remove some items
+                    a layer name removes the layer along with it's items"
+                ),
+            ),
             CommandType::ListFunc => ("list_func", "-", "list all functions"),
             CommandType::ListCmd => ("list_cmd", "-", "list all commands"),
             CommandType::Clear => ("clear", "-", "clear all items and user-defined functions"),
@@ -97,13 +129,197 @@ impl CommandType {
             ),
             CommandType::SaveSvg => (
                 "save_svg",
-                "expr",
+                "expr expr?",
                 indoc!(
                     "writes svg image to a file
-                    expr should evaluate to a file path (str)
+                    the first expr should evaluate to a file path (str)
+                    the second one should evaluate to options (str) like
+                    \"viewport: -1 -1 5 5; width: 800; height: 600; y-axis: down;
+                    background: white; lines: segments\"
+                    as on the canvas, y axis points up and lines are infinite by default
+                    note: can NOT be imported"
+                ),
+            ),
+            CommandType::SavePng => (
+                "save_png",
+                "expr expr?",
+                indoc!(
+                    "writes png image to a file
+                    arguments are the same as for save_svg
+                    options also accept pixel density, e.g. \"dpi: 300\",
+                    which scales the image without changing it's proportions
+                    note: can NOT be imported"
+                ),
+            ),
+            CommandType::SaveTikz => (
+                "save_tikz",
+                "expr expr?",
+                indoc!(
+                    "writes tikzpicture for LaTeX to a file
+                    the first expr should evaluate to a file path (str)
+                    the second one should evaluate to options (str) like
+                    \"scale: 0.5; viewport: -1 -1 5 5; lines: segments\"
+                    scale is the length of a unit in cm, coordinates are written as is
+                    note: can NOT be imported"
+                ),
+            ),
+            CommandType::SavePdf => (
+                "save_pdf",
+                "expr expr?",
+                indoc!(
+                    "writes single page pdf to a file
+                    arguments are the same as for save_svg
+                    options also accept page size and margin in mm, e.g.
+                    \"page: a4 landscape; margin: 10\" or \"page: 100 150\"
+                    the drawing is fit to the page, unless there is \"fit: none\"
+                    note: can NOT be imported"
+                ),
+            ),
+            CommandType::Animate => (
+                "animate",
+                "ident expr expr expr expr expr?",
+                indoc!(
+                    "sweeps a free int or real item and writes the frames to files
+                    the exprs are the first and the last values, the number of frames,
+                    the file path (str) and options (str) as for save_svg with
+                    \"duration: 2; output: animated\"
+                    svg is a single animated file, unless there is \"output: frames\",
+                    frames are written next to it as path_000.svg, path_001.svg, ...
+                    png frames are always separate files
+                    the item gets it's value back afterwards"
+                ),
+            ),
+            CommandType::Check => (
+                "check",
+                "expr expr?",
+                indoc!(
+                    "checks a bool expr, while free items, that it depends on, are moved randomly
+                    the second expr should evaluate to options (str) like
+                    \"trials: 100; spread: 0.1; max_fail_rate: 0; eps: 1e-6; seed: 42\"
+                    spread is relative to the largest coordinate of the items,
+                    max_fail_rate is the part of trials, that may fail,
+                    eps is the tolerance of comparisons during the trials
+                    fails with a counterexample, unless the expr holds
+                    trials, where the expr is none, are skipped"
+                ),
+            ),
+            CommandType::Assert => (
+                "assert",
+                "expr expr?",
+                indoc!(
+                    "fails unless a bool expr is true
+                    the second expr should evaluate to a message (str)"
+                ),
+            ),
+            CommandType::AssertEq => (
+                "assert_eq",
+                "expr expr expr?",
+                indoc!(
+                    "fails unless two exprs have the same value
+                    reals, points and other objects are compared approximately,
+                    up to the third expr (real) or the tolerance by default,
+                    lines are the same regardless of their points,
+                    values of different types, e.g. 2 and 2.0, are never equal"
+                ),
+            ),
+            CommandType::Tolerance => (
+                "tolerance",
+                "expr?",
+                indoc!(
+                    "show or set the tolerance of comparisons, 1e-9 by default
+                    expr should evaluate to a non-negative real
+                    reals, points, lines and circles are equal (==), if they are
+                    at most the tolerance apart; `on`, `parallel?` and other predicates
+                    use it as well
+                    items are evaluated again with the new tolerance"
+                ),
+            ),
+            CommandType::Exact => (
+                "exact",
+                "ident?",
+                indoc!(
+                    "show or switch exact arithmetic of reals, it's off by default
+                    ident is either `on` or `off`; it can only be switched in an empty scope
+                    with it real literals and ints cast to reals are fractions and
+                    +, -, *, /, % and ^ with an int exponent keep them exact;
+                    coordinates of points and radii of circles are kept exact too,
+                    other operations are floating point"
+                ),
+            ),
+            CommandType::LoadSvg => (
+                "load_svg",
+                "expr expr?",
+                indoc!(
+                    "defines items for lines, circles, polylines and points of svg
+                    the first expr should evaluate to a file path (str)
+                    the second one should evaluate to options (str) like
+                    \"scale: 100; y-axis: keep\"
+                    scale is drawing units per unit, y-axis is flip or keep;
+                    by default svg saved with save_svg is loaded in it's coordinates
+                    note: can NOT be imported"
+                ),
+            ),
+            CommandType::LoadDxf => (
+                "load_dxf",
+                "expr expr?",
+                indoc!(
+                    "defines items for LINE, CIRCLE, POINT and polylines of ascii dxf
+                    arguments are the same as for load_svg
                     note: can NOT be imported"
                 ),
             ),
+            CommandType::Import => (
+                "import",
+                "(ident | expr) ident?",
+                indoc!(
+                    "imports functions from a library
+                    ident is a library name in the server's library directory
+                    expr should evaluate to a file path (str)
+                    the last ident is a namespace, e.g. `geo` for `geo::perp`"
+                ),
+            ),
+            CommandType::Style => (
+                "style",
+                "ident+ expr?",
+                indoc!(
+                    "show or update items' styles
+                    expr should evaluate to declarations (str) like
+                    \"color: gray; width: 2; dash: 4 2; fill: #ff000080; hidden\"
+                    use \"visible\" to show an item and \"default\" to reset it's style
+                    labels are set with \"label: show | hide | auto; label-text: A'\"
+                    a layer name restyles all of it's items"
+                ),
+            ),
+            CommandType::Slider => (
+                "slider",
+                "(ident expr?)?",
+                indoc!(
+                    "list sliders or show or update one of them
+                    ident is a free int or real item
+                    expr should evaluate to declarations (str) like
+                    \"min: 0; max: 10; step: 0.5\"
+                    use \"none\" to remove the slider"
+                ),
+            ),
+            CommandType::Layer => (
+                "layer",
+                "(ident ident* expr?)?",
+                indoc!(
+                    "list layers or update one of them
+                    the first ident is a layer name, the rest are items to move into it
+                    expr should evaluate to flags (str) like \"hidden; locked\"
+                    use \"visible\" and \"unlocked\" to clear the flags"
+                ),
+            ),
+            CommandType::Labels => (
+                "labels",
+                "ident?",
+                indoc!(
+                    "show labels or toggle them for all items
+                    ident is either `show` or `hide`
+                    items' own styles take precedence"
+                ),
+            ),
         }
     }
 
@@ -132,6 +348,22 @@ impl CommandType {
             CommandType::Save => Self::save_cmd(client, args).await,
             CommandType::Load => Self::load_cmd(client, args).await,
             CommandType::SaveSvg => Self::save_svg_cmd(client, args).await,
+            CommandType::SavePng => Self::save_png_cmd(client, args).await,
+            CommandType::SaveTikz => Self::save_tikz_cmd(client, args).await,
+            CommandType::SavePdf => Self::save_pdf_cmd(client, args).await,
+            CommandType::Animate => Self::animate_cmd(client, args).await,
+            CommandType::Check => Self::check_cmd(client, args).await,
+            CommandType::Assert => Self::assert_cmd(client, args).await,
+            CommandType::AssertEq => Self::assert_eq_cmd(client, args).await,
+            CommandType::Tolerance => Self::tolerance_cmd(client, args).await,
+            CommandType::Exact => Self::exact_cmd(client, args).await,
+            CommandType::LoadSvg => Self::load_drawing_cmd(client, args, DrawingFormat::Svg).await,
+            CommandType::LoadDxf => Self::load_drawing_cmd(client, args, DrawingFormat::Dxf).await,
+            CommandType::Import => Self::import_cmd(client, args).await,
+            CommandType::Style => Self::style_cmd(client, args).await,
+            CommandType::Slider => Self::slider_cmd(client, args).await,
+            CommandType::Labels => Self::labels_cmd(client, args).await,
+            CommandType::Layer => Self::layer_cmd(client, args).await,
         }
     }
 
@@ -223,7 +455,18 @@ impl CommandType {
         unwrap_cmd_arg!(IDENT name FROM args);
         unwrap_cmd_arg!(END FROM args);
 
-        if let Err(err) = client.rm(name).await {
+        let layers = match client.get_all_layers().await {
+            Ok(layers) => layers,
+            Err(err) => return ScriptResult::error(err.context("get_all_layers failed")),
+        };
+
+        let res = if layers.iter().any(|layer| layer.name == name) {
+            client.rm_layer(name).await
+        } else {
+            client.rm(name).await
+        };
+
+        if let Err(err) = res {
             return ScriptResult::error(err.context("rm failed"));
         }
 
@@ -351,22 +594,693 @@ impl CommandType {
         ScriptResult::ok_none()
     }
 
+    async fn load_drawing_cmd(
+        client: &Client,
+        args: Vec<CommandArg>,
+        format: DrawingFormat,
+    ) -> ScriptResult {
+        let mut args = args.into_iter().peekable();
+        unwrap_cmd_arg!(EXPR expr FROM args);
+
+        let mut options_expr = None;
+        if args.peek().is_some() {
+            unwrap_cmd_arg!(EXPR expr FROM args);
+            options_expr = Some(expr);
+        }
+        unwrap_cmd_arg!(END FROM args);
+
+        let options = match Self::eval_options(client, options_expr, ImportOptions::apply).await {
+            Ok(options) => options,
+            Err(err) => return ScriptResult::error(err.context("eval_options failed")),
+        };
+
+        let path = match Self::eval_file_path(client, expr).await {
+            Ok(path) => path,
+            Err(err) => return ScriptResult::error(err.context("eval_file_path failed")),
+        };
+
+        if let Err(err) = client.load_drawing(&path, format, options).await {
+            return ScriptResult::error(err.context("load_drawing failed"));
+        }
+
+        ScriptResult::ok_none()
+    }
+
+    /// Evaluates optional options, e.g. for `save_svg!` or `load_svg!`.
+    ///
+    /// `apply` updates the options with declarations.
+    async fn eval_options<T: Default>(
+        client: &Client,
+        expr: Option<Expr>,
+        apply: impl FnOnce(&mut T, &str) -> Result<(), ExportOptionsError>,
+    ) -> anyhow::Result<T> {
+        let mut options = T::default();
+        let Some(expr) = expr else {
+            return Ok(options);
+        };
+
+        let decls = match client.eval_one(expr).await {
+            Ok(Value::Str(Some(decls))) => decls,
+            Ok(v) => {
+                bail!("options must be a str, got {}", v.value_type())
+            }
+            Err(err) => return Err(err.context("failed to evaluate options expr")),
+        };
+
+        apply(&mut options, &decls)
+            .map_err(|err| anyhow::Error::from(err).context("bad options"))?;
+        Ok(options)
+    }
+
     async fn save_svg_cmd(client: &Client, args: Vec<CommandArg>) -> ScriptResult {
-        let mut args = args.into_iter();
+        let mut args = args.into_iter().peekable();
         unwrap_cmd_arg!(EXPR expr FROM args);
+
+        let mut options_expr = None;
+        if args.peek().is_some() {
+            unwrap_cmd_arg!(EXPR expr FROM args);
+            options_expr = Some(expr);
+        }
         unwrap_cmd_arg!(END FROM args);
 
+        let options = match Self::eval_options(client, options_expr, ExportOptions::apply).await {
+            Ok(options) => options,
+            Err(err) => return ScriptResult::error(err.context("eval_options failed")),
+        };
+
         let path = match Self::eval_file_path(client, expr).await {
             Ok(path) => path,
             Err(err) => return ScriptResult::error(err.context("eval_file_path failed")),
         };
 
-        if let Err(err) = client.save_svg(&path).await {
+        if let Err(err) = client.save_svg(&path, options).await {
             return ScriptResult::error(err.context("save_svg failed"));
         }
 
         ScriptResult::ok_none()
     }
+
+    async fn save_png_cmd(client: &Client, args: Vec<CommandArg>) -> ScriptResult {
+        let mut args = args.into_iter().peekable();
+        unwrap_cmd_arg!(EXPR expr FROM args);
+
+        let mut options_expr = None;
+        if args.peek().is_some() {
+            unwrap_cmd_arg!(EXPR expr FROM args);
+            options_expr = Some(expr);
+        }
+        unwrap_cmd_arg!(END FROM args);
+
+        let options = match Self::eval_options(client, options_expr, ExportOptions::apply).await {
+            Ok(options) => options,
+            Err(err) => return ScriptResult::error(err.context("eval_options failed")),
+        };
+
+        let path = match Self::eval_file_path(client, expr).await {
+            Ok(path) => path,
+            Err(err) => return ScriptResult::error(err.context("eval_file_path failed")),
+        };
+
+        if let Err(err) = client.save_png(&path, options).await {
+            return ScriptResult::error(err.context("save_png failed"));
+        }
+
+        ScriptResult::ok_none()
+    }
+
+    async fn save_tikz_cmd(client: &Client, args: Vec<CommandArg>) -> ScriptResult {
+        let mut args = args.into_iter().peekable();
+        unwrap_cmd_arg!(EXPR expr FROM args);
+
+        let mut options_expr = None;
+        if args.peek().is_some() {
+            unwrap_cmd_arg!(EXPR expr FROM args);
+            options_expr = Some(expr);
+        }
+        unwrap_cmd_arg!(END FROM args);
+
+        let options = match Self::eval_options(client, options_expr, TikzOptions::apply).await {
+            Ok(options) => options,
+            Err(err) => return ScriptResult::error(err.context("eval_options failed")),
+        };
+
+        let path = match Self::eval_file_path(client, expr).await {
+            Ok(path) => path,
+            Err(err) => return ScriptResult::error(err.context("eval_file_path failed")),
+        };
+
+        if let Err(err) = client.save_tikz(&path, options).await {
+            return ScriptResult::error(err.context("save_tikz failed"));
+        }
+
+        ScriptResult::ok_none()
+    }
+
+    async fn save_pdf_cmd(client: &Client, args: Vec<CommandArg>) -> ScriptResult {
+        let mut args = args.into_iter().peekable();
+        unwrap_cmd_arg!(EXPR expr FROM args);
+
+        let mut options_expr = None;
+        if args.peek().is_some() {
+            unwrap_cmd_arg!(EXPR expr FROM args);
+            options_expr = Some(expr);
+        }
+        unwrap_cmd_arg!(END FROM args);
+
+        let options = match Self::eval_options(client, options_expr, PdfOptions::apply).await {
+            Ok(options) => options,
+            Err(err) => return ScriptResult::error(err.context("eval_options failed")),
+        };
+
+        let path = match Self::eval_file_path(client, expr).await {
+            Ok(path) => path,
+            Err(err) => return ScriptResult::error(err.context("eval_file_path failed")),
+        };
+
+        if let Err(err) = client.save_pdf(&path, options).await {
+            return ScriptResult::error(err.context("save_pdf failed"));
+        }
+
+        ScriptResult::ok_none()
+    }
+
+    async fn animate_cmd(client: &Client, args: Vec<CommandArg>) -> ScriptResult {
+        let mut args = args.into_iter().peekable();
+        unwrap_cmd_arg!(IDENT name FROM args);
+        unwrap_cmd_arg!(EXPR from_expr FROM args);
+        unwrap_cmd_arg!(EXPR to_expr FROM args);
+        unwrap_cmd_arg!(EXPR frames_expr FROM args);
+        unwrap_cmd_arg!(EXPR expr FROM args);
+
+        let mut options_expr = None;
+        if args.peek().is_some() {
+            unwrap_cmd_arg!(EXPR expr FROM args);
+            options_expr = Some(expr);
+        }
+        unwrap_cmd_arg!(END FROM args);
+
+        let mut range = [0.0; 2];
+        for (value, expr) in range.iter_mut().zip([from_expr, to_expr]) {
+            *value = match client.eval_one(expr).await {
+                Ok(Value::Real(Some(v))) => v.to_f64(),
+                Ok(Value::Int(Some(v))) => v as f64,
+                Ok(v) => return ScriptResult::error(anyhow!("bound must be a number, got {v}")),
+                Err(err) => return ScriptResult::error(err.context("failed to evaluate bound")),
+            };
+        }
+        let [from, to] = range;
+
+        let frames = match client.eval_one(frames_expr).await {
+            Ok(Value::Int(Some(frames))) if frames > 0 => match u32::try_from(frames) {
+                Ok(frames) => frames,
+                Err(_) => return ScriptResult::error(anyhow!("too many frames: {frames}")),
+            },
+            Ok(v) => return ScriptResult::error(anyhow!("frames must be a positive int, got {v}")),
+            Err(err) => return ScriptResult::error(err.context("failed to evaluate frames")),
+        };
+
+        let options = match Self::eval_options(client, options_expr, AnimationOptions::apply).await
+        {
+            Ok(options) => options,
+            Err(err) => return ScriptResult::error(err.context("eval_options failed")),
+        };
+
+        let path = match Self::eval_file_path(client, expr).await {
+            Ok(path) => path,
+            Err(err) => return ScriptResult::error(err.context("eval_file_path failed")),
+        };
+
+        let sweep = Sweep { from, to, frames };
+        if let Err(err) = client.save_animation(&path, name, sweep, options).await {
+            return ScriptResult::error(err.context("save_animation failed"));
+        }
+
+        ScriptResult::ok_none()
+    }
+
+    async fn check_cmd(client: &Client, args: Vec<CommandArg>) -> ScriptResult {
+        let mut args = args.into_iter().peekable();
+        unwrap_cmd_arg!(EXPR expr FROM args);
+
+        let mut options = CheckOptions::default();
+        if args.peek().is_some() {
+            unwrap_cmd_arg!(EXPR options_expr FROM args);
+            let decls = match client.eval_one(options_expr).await {
+                Ok(Value::Str(Some(decls))) => decls,
+                Ok(v) => {
+                    return ScriptResult::error(anyhow!(
+                        "options must be a str, got {}",
+                        v.value_type()
+                    ))
+                }
+                Err(err) => return ScriptResult::error(err.context("failed to evaluate options")),
+            };
+            if let Err(err) = options.apply(&decls) {
+                return ScriptResult::error(anyhow::Error::from(err).context("bad options"));
+            }
+        }
+        unwrap_cmd_arg!(END FROM args);
+
+        let max_fail_rate = options.max_fail_rate;
+        let report = match client.check(expr, options).await {
+            Ok(report) => report,
+            Err(err) => return ScriptResult::error(err.context("check failed")),
+        };
+
+        if !report.holds(max_fail_rate) {
+            let counterexample = report
+                .counterexample
+                .iter()
+                .flatten()
+                .map(|(name, value)| format!("{name} = {value}"))
+                .join(", ");
+            return ScriptResult::error(anyhow!(
+                "check failed in {} of {} trials ({} skipped); counterexample: {}",
+                report.failed,
+                report.passed + report.failed,
+                report.skipped,
+                if counterexample.is_empty() {
+                    "none"
+                } else {
+                    &counterexample
+                }
+            ));
+        }
+
+        ScriptResult::ok_one(Table::new_with_rows(
+            ["Passed", "Failed", "Skipped"],
+            [[
+                report.passed.to_string(),
+                report.failed.to_string(),
+                report.skipped.to_string(),
+            ]],
+        ))
+    }
+
+    async fn assert_cmd(client: &Client, args: Vec<CommandArg>) -> ScriptResult {
+        let mut args = args.into_iter().map(arg_to_expr).peekable();
+        unwrap_cmd_arg!(EXPR expr FROM args);
+
+        let mut msg_expr = None;
+        if args.peek().is_some() {
+            unwrap_cmd_arg!(EXPR expr FROM args);
+            msg_expr = Some(expr);
+        }
+        unwrap_cmd_arg!(END FROM args);
+
+        match client.eval_one(expr.clone()).await {
+            Ok(Value::Bool(Some(true))) => return ScriptResult::ok_none(),
+            Ok(Value::Bool(_)) => {}
+            Ok(v) => {
+                return ScriptResult::error(anyhow!(
+                    "assertion must be a bool, got {}",
+                    v.value_type()
+                ))
+            }
+            Err(err) => return ScriptResult::error(err.context("failed to evaluate assertion")),
+        };
+
+        let Some(msg_expr) = msg_expr else {
+            return ScriptResult::error(anyhow!("assertion failed: {expr}"));
+        };
+        match client.eval_one(msg_expr).await {
+            Ok(Value::Str(Some(msg))) => ScriptResult::error(anyhow!("assertion failed: {msg}")),
+            Ok(v) => ScriptResult::error(anyhow!("message must be a str, got {}", v.value_type())),
+            Err(err) => ScriptResult::error(err.context("failed to evaluate message")),
+        }
+    }
+
+    async fn assert_eq_cmd(client: &Client, args: Vec<CommandArg>) -> ScriptResult {
+        let mut args = args.into_iter().map(arg_to_expr).peekable();
+        unwrap_cmd_arg!(EXPR lhs_expr FROM args);
+        unwrap_cmd_arg!(EXPR rhs_expr FROM args);
+
+        let eps = if args.peek().is_some() {
+            unwrap_cmd_arg!(EXPR eps_expr FROM args);
+            match client.eval_one(eps_expr).await {
+                Ok(Value::Real(Some(eps))) if eps.to_f64() >= 0.0 => eps.to_f64(),
+                Ok(v) => {
+                    return ScriptResult::error(anyhow!(
+                        "epsilon must be a non-negative real, got {v}"
+                    ))
+                }
+                Err(err) => return ScriptResult::error(err.context("failed to evaluate epsilon")),
+            }
+        } else {
+            match client.get_tolerance().await {
+                Ok(eps) => eps,
+                Err(err) => return ScriptResult::error(err.context("get_tolerance failed")),
+            }
+        };
+        unwrap_cmd_arg!(END FROM args);
+
+        let mut values = Vec::with_capacity(2);
+        for expr in [lhs_expr, rhs_expr] {
+            match client.eval_one(expr).await {
+                Ok(value) => values.push(value),
+                Err(err) => return ScriptResult::error(err.context("failed to evaluate expr")),
+            }
+        }
+        let (lhs, rhs) = (&values[0], &values[1]);
+
+        if lhs.approx_eq(rhs, eps) {
+            ScriptResult::ok_none()
+        } else {
+            ScriptResult::error(anyhow!("assertion failed: {lhs} != {rhs}"))
+        }
+    }
+
+    async fn tolerance_cmd(client: &Client, args: Vec<CommandArg>) -> ScriptResult {
+        let mut args = args.into_iter().peekable();
+
+        if args.peek().is_none() {
+            return match client.get_tolerance().await {
+                Ok(tolerance) => ScriptResult::ok_one(Table::new_with_rows(
+                    ["Tolerance"],
+                    [[tolerance.to_string()]],
+                )),
+                Err(err) => ScriptResult::error(err.context("get_tolerance failed")),
+            };
+        }
+
+        unwrap_cmd_arg!(EXPR expr FROM args);
+        unwrap_cmd_arg!(END FROM args);
+
+        let tolerance = match client.eval_one(expr).await {
+            Ok(Value::Real(Some(tolerance))) => tolerance.to_f64(),
+            Ok(v) => return ScriptResult::error(anyhow!("tolerance must be a real, got {v}")),
+            Err(err) => return ScriptResult::error(err.context("failed to evaluate tolerance")),
+        };
+
+        match client.set_tolerance(tolerance).await {
+            Ok(()) => ScriptResult::ok_none(),
+            Err(err) => ScriptResult::error(err.context("set_tolerance failed")),
+        }
+    }
+
+    async fn exact_cmd(client: &Client, args: Vec<CommandArg>) -> ScriptResult {
+        let mut args = args.into_iter().peekable();
+
+        if args.peek().is_none() {
+            return match client.is_exact().await {
+                Ok(exact) => ScriptResult::ok_one(Table::new_with_rows(
+                    ["Arithmetic"],
+                    [[if exact { "exact" } else { "float" }]],
+                )),
+                Err(err) => ScriptResult::error(err.context("is_exact failed")),
+            };
+        }
+
+        unwrap_cmd_arg!(IDENT mode FROM args);
+        unwrap_cmd_arg!(END FROM args);
+
+        let exact = match mode.0.as_str() {
+            "on" => true,
+            "off" => false,
+            _ => return ScriptResult::error(anyhow!("expected `on` or `off`, got {mode}")),
+        };
+
+        match client.set_exact(exact).await {
+            Ok(()) => ScriptResult::ok_none(),
+            Err(err) => ScriptResult::error(err.context("set_exact failed")),
+        }
+    }
+
+    async fn import_cmd(client: &Client, args: Vec<CommandArg>) -> ScriptResult {
+        let mut args = args.into_iter();
+
+        let source = match args.next() {
+            Some(CommandArg::Ident(name)) => LibrarySource::Lib(name),
+            Some(CommandArg::Expr(expr)) => {
+                let path = match Self::eval_file_path(client, expr).await {
+                    Ok(path) => path,
+                    Err(err) => return ScriptResult::error(err.context("eval_file_path failed")),
+                };
+
+                // The server may be running in another directory
+                match path::absolute(&path) {
+                    Ok(path) => LibrarySource::File(path),
+                    Err(err) => {
+                        return ScriptResult::error(
+                            anyhow::Error::from(err).context("failed to make path absolute"),
+                        )
+                    }
+                }
+            }
+            None => return ScriptResult::error(anyhow!("expected ident or expr, got nothing")),
+        };
+
+        let namespace = match args.next() {
+            Some(CommandArg::Ident(namespace)) => Some(namespace),
+            Some(CommandArg::Expr(_)) => {
+                return ScriptResult::error(anyhow!("got expr, ident expected"))
+            }
+            None => None,
+        };
+        unwrap_cmd_arg!(END FROM args);
+
+        match client.import(source, namespace).await {
+            Ok(warnings) => ScriptResult::ok_none().with_warnings(warnings),
+            Err(err) => ScriptResult::error(err.context("import failed")),
+        }
+    }
+
+    async fn style_cmd(client: &Client, args: Vec<CommandArg>) -> ScriptResult {
+        let mut names = Vec::new();
+        let mut args = args.into_iter().peekable();
+        while let Some(CommandArg::Ident(_)) = args.peek() {
+            unwrap_cmd_arg!(IDENT name FROM args);
+            names.push(name);
+        }
+        if names.is_empty() {
+            return ScriptResult::error(anyhow!("expected ident, got nothing"));
+        }
+
+        // Layers are replaced with their items
+        let layers = match client.get_all_layers().await {
+            Ok(layers) => layers,
+            Err(err) => return ScriptResult::error(err.context("get_all_layers failed")),
+        };
+        let names: Vec<_> = names
+            .into_iter()
+            .flat_map(
+                |name| match layers.iter().find(|layer| layer.name == name) {
+                    Some(layer) => layer.items.clone(),
+                    None => vec![name],
+                },
+            )
+            .collect();
+
+        let decls = match args.next() {
+            Some(CommandArg::Expr(expr)) => match client.eval_one(expr).await {
+                Ok(Value::Str(Some(decls))) => Some(decls),
+                Ok(v) => {
+                    return ScriptResult::error(anyhow!(
+                        "style must be a str, got {}",
+                        v.value_type()
+                    ))
+                }
+                Err(err) => {
+                    return ScriptResult::error(err.context("failed to evaluate style expr"))
+                }
+            },
+            _ => None,
+        };
+        unwrap_cmd_arg!(END FROM args);
+
+        let mut rows = Vec::new();
+        for name in names {
+            let mut style = match client.get_style(name.clone()).await {
+                Ok(style) => style,
+                Err(err) => return ScriptResult::error(err.context("get_style failed")),
+            };
+
+            let Some(decls) = &decls else {
+                rows.push([name.to_string(), style.to_string()]);
+                continue;
+            };
+
+            if let Err(err) = style.apply(decls) {
+                return ScriptResult::error(anyhow::Error::from(err).context("bad style"));
+            }
+
+            if let Err(err) = client.set_style(name, style).await {
+                return ScriptResult::error(err.context("set_style failed"));
+            }
+        }
+
+        if decls.is_some() {
+            ScriptResult::ok_none()
+        } else {
+            ScriptResult::ok_one(Table::new_with_rows(["Name", "Style"], rows))
+        }
+    }
+
+    async fn slider_cmd(client: &Client, args: Vec<CommandArg>) -> ScriptResult {
+        let mut args = args.into_iter().peekable();
+
+        let sliders = match client.get_all_sliders().await {
+            Ok(sliders) => sliders,
+            Err(err) => return ScriptResult::error(err.context("get_all_sliders failed")),
+        };
+
+        if args.peek().is_none() {
+            return ScriptResult::ok_one(Table::new_with_rows(
+                ["Name", "Slider"],
+                sliders
+                    .into_iter()
+                    .sorted_by(|(a, _), (b, _)| a.0.cmp(&b.0))
+                    .map(|(name, slider)| [name.to_string(), slider.to_string()]),
+            ));
+        }
+
+        unwrap_cmd_arg!(IDENT name FROM args);
+
+        let Some(CommandArg::Expr(expr)) = args.next() else {
+            let slider = sliders
+                .get(&name)
+                .map_or("none".to_string(), Slider::to_string);
+            return ScriptResult::ok_one(Table::new_with_rows(
+                ["Name", "Slider"],
+                [[name.to_string(), slider]],
+            ));
+        };
+        unwrap_cmd_arg!(END FROM args);
+
+        let decls = match client.eval_one(expr).await {
+            Ok(Value::Str(Some(decls))) => decls,
+            Ok(v) => {
+                return ScriptResult::error(anyhow!("slider must be a str, got {}", v.value_type()))
+            }
+            Err(err) => return ScriptResult::error(err.context("failed to evaluate slider expr")),
+        };
+
+        let slider = if decls.trim() == "none" {
+            None
+        } else {
+            let mut slider = sliders.get(&name).copied().unwrap_or_default();
+            if let Err(err) = slider.apply(&decls) {
+                return ScriptResult::error(anyhow::Error::from(err).context("bad slider"));
+            }
+            Some(slider)
+        };
+
+        match client.set_slider(name, slider).await {
+            Ok(()) => ScriptResult::ok_none(),
+            Err(err) => ScriptResult::error(err.context("set_slider failed")),
+        }
+    }
+
+    async fn layer_cmd(client: &Client, args: Vec<CommandArg>) -> ScriptResult {
+        let mut args = args.into_iter().peekable();
+
+        if args.peek().is_none() {
+            let layers = match client.get_all_layers().await {
+                Ok(layers) => layers,
+                Err(err) => return ScriptResult::error(err.context("get_all_layers failed")),
+            };
+
+            return ScriptResult::ok_one(Table::new_with_rows(
+                ["Name", "Items", "Flags"],
+                layers.into_iter().map(|layer| {
+                    let flags = [(layer.hidden, "hidden"), (layer.locked, "locked")]
+                        .into_iter()
+                        .filter_map(|(set, flag)| set.then_some(flag))
+                        .join("; ");
+                    [
+                        layer.name.to_string(),
+                        fit_to_len(layer.items.iter().join(" "), 100),
+                        flags,
+                    ]
+                }),
+            ));
+        }
+
+        unwrap_cmd_arg!(IDENT name FROM args);
+
+        let mut items = Vec::new();
+        while let Some(CommandArg::Ident(_)) = args.peek() {
+            unwrap_cmd_arg!(IDENT item FROM args);
+            items.push(item);
+        }
+
+        let flags = match args.next() {
+            Some(CommandArg::Expr(expr)) => match client.eval_one(expr).await {
+                Ok(Value::Str(Some(flags))) => Some(flags),
+                Ok(v) => {
+                    return ScriptResult::error(anyhow!(
+                        "flags must be a str, got {}",
+                        v.value_type()
+                    ))
+                }
+                Err(err) => {
+                    return ScriptResult::error(err.context("failed to evaluate flags expr"))
+                }
+            },
+            _ => None,
+        };
+        unwrap_cmd_arg!(END FROM args);
+
+        if flags.is_none() || !items.is_empty() {
+            if let Err(err) = client.add_to_layer(name.clone(), items).await {
+                return ScriptResult::error(err.context("add_to_layer failed"));
+            }
+        }
+
+        let Some(flags) = flags else {
+            return ScriptResult::ok_none();
+        };
+
+        let (mut hidden, mut locked) = (None, None);
+        for flag in flags.split(';').map(str::trim).filter(|f| !f.is_empty()) {
+            match flag {
+                "hidden" => hidden = Some(true),
+                "visible" => hidden = Some(false),
+                "locked" => locked = Some(true),
+                "unlocked" => locked = Some(false),
+                _ => return ScriptResult::error(anyhow!("unknown layer flag '{flag}'")),
+            }
+        }
+
+        if let Err(err) = client.update_layer(name, hidden, locked).await {
+            return ScriptResult::error(err.context("update_layer failed"));
+        }
+
+        ScriptResult::ok_none()
+    }
+
+    async fn labels_cmd(client: &Client, args: Vec<CommandArg>) -> ScriptResult {
+        let mut args = args.into_iter().peekable();
+
+        if args.peek().is_some() {
+            unwrap_cmd_arg!(IDENT mode FROM args);
+            unwrap_cmd_arg!(END FROM args);
+
+            let show = match mode.0.as_str() {
+                "show" => true,
+                "hide" => false,
+                _ => return ScriptResult::error(anyhow!("expected `show` or `hide`, got {mode}")),
+            };
+
+            return match client.show_labels(show).await {
+                Ok(()) => ScriptResult::ok_none(),
+                Err(err) => ScriptResult::error(err.context("show_labels failed")),
+            };
+        }
+
+        let mut labels: Vec<_> = match client.get_all_labels().await {
+            Ok(labels) => labels.into_iter().collect(),
+            Err(err) => return ScriptResult::error(err.context("get_all_labels failed")),
+        };
+        labels.sort_by(|(a, _), (b, _)| a.0.cmp(&b.0));
+
+        ScriptResult::ok_one(Table::new_with_rows(
+            ["Name", "Label"],
+            labels
+                .into_iter()
+                .map(|(name, label)| [name.to_string(), label]),
+        ))
+    }
 }
 
 fn fit_to_len(text: impl ToString, len: usize) -> String {
@@ -388,6 +1302,18 @@ fn fit_to_len(text: impl ToString, len: usize) -> String {
         .collect()
 }
 
+/// Treats an ident as a variable or a bool literal, where only exprs are expected.
+fn arg_to_expr(arg: CommandArg) -> CommandArg {
+    match arg {
+        CommandArg::Ident(name) => CommandArg::Expr(match name.0.as_str() {
+            "true" => Expr::Value(Value::from(true)),
+            "false" => Expr::Value(Value::from(false)),
+            _ => Expr::Variable(name),
+        }),
+        arg => arg,
+    }
+}
+
 macro_rules! unwrap_cmd_arg {
     (END FROM $args:ident) => {
         if $args.next().is_some() {
@@ -1,4 +1,5 @@
 pub use client::Client;
+pub use command::CommandType;
 pub use new::ClientSettings;
 pub use script_result::ScriptResult;
 pub use table::Table;
@@ -5,7 +5,7 @@ use reqwest::Url;
 use smart_default::SmartDefault;
 use url::Host;
 
-use crate::Client;
+use crate::{client::Backend, Client};
 
 static SERVER_BINARY_NAME: &str = "server";
 
@@ -32,6 +32,20 @@ impl Client {
         Self::from(Default::default()).await
     }
 
+    /// Creates a client, that executes everything in-process, without a server.
+    #[cfg(feature = "embedded")]
+    pub fn new_embedded() -> Self {
+        Client {
+            backend: Backend::Embedded(Default::default()),
+        }
+    }
+
+    fn from_server(server_url: Url, client: reqwest::Client) -> Self {
+        Client {
+            backend: Backend::Server { server_url, client },
+        }
+    }
+
     /// Same as [Self::from_with_child] but won't return [Child].
     pub async fn from(settings: ClientSettings) -> anyhow::Result<Self> {
         Self::from_with_child(settings)
@@ -52,7 +66,7 @@ impl Client {
         }
 
         if Self::ping_server_with_url(client.clone(), server_url.clone()).await {
-            return Ok((Client { server_url, client }, None));
+            return Ok((Client::from_server(server_url, client), None));
         }
 
         let server_to_spawn_port =
@@ -67,7 +81,7 @@ impl Client {
         let server_url = Url::parse(&format!("http://127.0.0.1:{server_port}"))
             .expect("failed to parse server url");
 
-        Ok((Client { server_url, client }, Some(server_child)))
+        Ok((Client::from_server(server_url, client), Some(server_child)))
     }
 
     /// Return Err if NOT spawnable and a port for server to listen on otherwise.
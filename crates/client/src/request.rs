@@ -1,13 +1,47 @@
 use anyhow::{anyhow, Context};
+use reqwest::Url;
 use types::api::{self, Request};
 
-use crate::Client;
+use crate::{client::Backend, Client};
+
+/// A request, that every backend can handle.
+///
+/// The embedded backend passes requests to [executor::handle::Handle] directly.
+#[cfg(feature = "embedded")]
+pub(crate) trait ClientRequest: executor::handle::Handle {}
+
+#[cfg(feature = "embedded")]
+impl<REQ: executor::handle::Handle> ClientRequest for REQ {}
+
+/// A request, that every backend can handle.
+#[cfg(not(feature = "embedded"))]
+pub(crate) trait ClientRequest: Request {}
+
+#[cfg(not(feature = "embedded"))]
+impl<REQ: Request> ClientRequest for REQ {}
 
 impl Client {
-    pub(crate) async fn req<REQ: Request>(&self, req: REQ) -> anyhow::Result<REQ::Response> {
-        let resp = self
-            .client
-            .post(self.server_url.join(REQ::ROUTE).unwrap())
+    pub(crate) async fn req<REQ: ClientRequest>(&self, req: REQ) -> anyhow::Result<REQ::Response> {
+        match &self.backend {
+            Backend::Server { server_url, client } => {
+                Self::req_server(server_url, client, req).await
+            }
+
+            #[cfg(feature = "embedded")]
+            Backend::Embedded(scope) => req.handle(&mut *scope.lock().await).map_err(|err| {
+                let err: anyhow::Error = err.into();
+                err.context("got error from embedded executor")
+            }),
+        }
+    }
+
+    async fn req_server<REQ: Request>(
+        server_url: &Url,
+        client: &reqwest::Client,
+        req: REQ,
+    ) -> anyhow::Result<REQ::Response> {
+        let resp = client
+            .post(server_url.join(REQ::ROUTE).unwrap())
             .json(&req)
             .send()
             .await
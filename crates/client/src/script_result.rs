@@ -1,5 +1,7 @@
 use std::fmt::Display;
 
+use types::lang::Warning;
+
 use crate::Table;
 
 /// Result of script ([`Vec<Statement>`]) execution.
@@ -11,12 +13,15 @@ use crate::Table;
 ///     hold the results of the commands that were executed.
 /// * Execution succeeded. Then `error` will be `None`.
 ///
+/// In any case `warnings` hold the warnings of the definitions that were executed.
+///
 /// Note: the "Result" in the name has noting to do with [`Result`] or [`anyhow::Result`].
 #[derive(Debug)]
 pub struct ScriptResult {
     // TODO (?): Vec<Table> -> Vec<StatementResult>
     pub results: Vec<Table>,
     pub error: Option<anyhow::Error>,
+    pub warnings: Vec<Warning>,
 }
 
 impl ScriptResult {
@@ -24,6 +29,7 @@ impl ScriptResult {
         Self {
             results: results.into_iter().collect(),
             error: None,
+            warnings: Vec::new(),
         }
     }
 
@@ -31,6 +37,7 @@ impl ScriptResult {
         Self {
             results: vec![result],
             error: None,
+            warnings: Vec::new(),
         }
     }
 
@@ -38,6 +45,7 @@ impl ScriptResult {
         Self {
             results: Vec::new(),
             error: None,
+            warnings: Vec::new(),
         }
     }
 
@@ -45,6 +53,7 @@ impl ScriptResult {
         Self {
             results: Vec::new(),
             error: Some(error.into()),
+            warnings: Vec::new(),
         }
     }
 
@@ -55,9 +64,15 @@ impl ScriptResult {
         Self {
             results: results.into_iter().collect(),
             error: Some(error.into()),
+            warnings: Vec::new(),
         }
     }
 
+    pub fn with_warnings(mut self, warnings: impl IntoIterator<Item = Warning>) -> Self {
+        self.warnings.extend(warnings);
+        self
+    }
+
     pub fn is_ok(&self) -> bool {
         self.error.is_none()
     }
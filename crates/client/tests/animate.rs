@@ -0,0 +1,70 @@
+use std::fs;
+
+use tempfile::TempDir;
+use test_client::TestClient;
+use types::core::Value;
+
+mod test_client;
+
+#[tokio::test]
+async fn animate() {
+    let client = TestClient::new().await;
+
+    let res = client
+        .exec(
+            r#"
+            r = 1.0
+            n = 1
+            c = circ (pt 0.0 0.0) r
+            "#,
+        )
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+
+    let dir = TempDir::new().unwrap();
+
+    let file = dir.path().join("anim.svg");
+    let res = client
+        .exec(format!(r#"animate! r 1 2.0 4 {file:?} "duration: 3""#))
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+    let svg = fs::read_to_string(&file).unwrap();
+    assert_eq!(svg.matches("<animate").count(), 4);
+    assert!(svg.contains(r#"dur="3s""#));
+
+    // The item gets it's value back
+    assert_eq!(client.get_item("r").await.unwrap(), Value::from(1.0));
+
+    let file = dir.path().join("frame.svg");
+    let res = client
+        .exec(format!(r#"animate! r 1.0 2.0 3 {file:?} "output: frames""#))
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+    assert!(!file.exists());
+    for idx in 0..3 {
+        let frame = fs::read_to_string(dir.path().join(format!("frame_00{idx}.svg"))).unwrap();
+        assert!(!frame.contains("<animate"));
+    }
+
+    let file = dir.path().join("frame.png");
+    let res = client
+        .exec(format!(r#"animate! n 1 3 3 {file:?} "width: 50""#))
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+    for idx in 0..3 {
+        let png = fs::read(dir.path().join(format!("frame_00{idx}.png"))).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+    }
+
+    for cmd in [
+        format!("animate! c 1.0 2.0 3 {file:?}"),
+        format!("animate! r 1.0 2.0 0 {file:?}"),
+        format!("animate! r 1.0 2.0 100001 {file:?}"),
+        format!("animate! r 1.0 2.0 4294967297 {file:?}"),
+        format!("animate! r 1.0 2.0 3 {:?}", dir.path().join("anim.gif")),
+        format!(r#"animate! r 1.0 2.0 3 {file:?} "duration: 0""#),
+    ] {
+        let res = client.exec(cmd.as_str()).await;
+        assert!(res.error.is_some(), "{cmd}");
+    }
+}
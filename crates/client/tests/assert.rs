@@ -0,0 +1,50 @@
+use test_client::TestClient;
+
+mod test_client;
+
+#[tokio::test]
+async fn assert() {
+    let client = TestClient::new().await;
+
+    let res = client
+        .exec(
+            r#"
+            a = 0.1 + 0.2
+            n = 3
+            A = pt 1.0 2.0
+            B = pt (a * 10.0 - 2.0) 2.0
+            assert! (n == 3)
+            assert! true "holds"
+            assert_eq! a 0.3
+            assert_eq! n 3
+            assert_eq! A B
+            assert_eq! A (pt 1.0 2.05) 0.1
+            assert_eq! "abc" "abc"
+            "#,
+        )
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+
+    let res = client.exec(r#"assert! (n > 3) "n is small""#).await;
+    let err = format!("{:#}", res.error.unwrap());
+    assert!(err.ends_with("assertion failed: n is small"), "{err}");
+
+    let res = client.exec("assert_eq! A (pt 1.0 2.1)").await;
+    let err = format!("{:#}", res.error.unwrap());
+    assert!(err.contains("!="), "{err}");
+
+    for cmd in [
+        "assert! false",
+        "assert! n",
+        "assert! false 1",
+        "assert_eq! a",
+        "assert_eq! a 0.31",
+        "assert_eq! A a",
+        "assert_eq! n 3.0",
+        "assert_eq! a 0.3 (-1.0)",
+        "assert_eq! a 0.3 1e-9 1",
+    ] {
+        let res = client.exec(cmd).await;
+        assert!(res.error.is_some(), "{cmd}");
+    }
+}
@@ -0,0 +1,93 @@
+use std::{fs, path::Path};
+
+use test_client::TestClient;
+use types::core::{Pt, Value};
+
+mod test_client;
+
+#[tokio::test]
+async fn check() {
+    let client = TestClient::new().await;
+
+    let res = client
+        .exec(
+            r#"
+            A = pt 0.0 0.0
+            B = pt 4.0 0.0
+            C = pt 1.0 3.0
+            M = (A + B) / 2.0
+            "#,
+        )
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+
+    // The midpoint is between the ends
+    let res = client
+        .exec(r#"check! ((M.x - A.x) * (M.x - B.x) <= 0.0) "trials: 50; seed: 1""#)
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+
+    // C is above AB only by chance
+    let res = client.exec(r#"check! (C.y > 3.0) "seed: 1""#).await;
+    let err = format!("{:?}", res.error.unwrap());
+    assert!(err.contains("counterexample: C = "), "{err}");
+
+    // Unless most trials may fail
+    let res = client
+        .exec(r#"check! (C.y > 3.0) "seed: 1; max_fail_rate: 0.9""#)
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+
+    // Comparisons are up to the eps during the trials
+    let res = client
+        .exec(r#"check! (C == C + pt 0.001 0.0) "seed: 1""#)
+        .await;
+    assert!(res.error.is_some());
+    let res = client
+        .exec(r#"check! (C == C + pt 0.001 0.0) "seed: 1; eps: 0.01""#)
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+    assert_eq!(client.get_tolerance().await.unwrap(), 1e-9);
+
+    // Items get their values back
+    assert_eq!(
+        client.get_item("M").await.unwrap(),
+        Value::from(Pt::new(2.0, 0.0))
+    );
+
+    for cmd in [
+        "check! (M)",
+        r#"check! (C.y > 0.0) "trials: 0""#,
+        r#"check! (C.y > 0.0) "eps: -1""#,
+        r#"check! (C.y > 0.0) 1"#,
+    ] {
+        let res = client.exec(cmd).await;
+        assert!(res.error.is_some(), "{cmd}");
+    }
+}
+
+/// Altitudes of a triangle meet in one point, wherever it's vertices are.
+#[tokio::test]
+async fn altitudes() {
+    let client = TestClient::new().await;
+
+    let script =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../../examples/geom/altitudes_theorem.geom");
+    let res = client.exec(fs::read_to_string(script).unwrap()).await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+
+    let res = client.exec("H = inter_point_ A_alt B_alt").await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+
+    let res = client
+        .exec(r#"check! (on H C_alt) "seed: 1; eps: 1e-6""#)
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+
+    // Unlike the intersection of other lines
+    let res = client
+        .exec(r#"check! (on H (line A B)) "seed: 1; eps: 1e-6""#)
+        .await;
+    let err = format!("{:?}", res.error.unwrap());
+    assert!(err.contains("counterexample: A = "), "{err}");
+}
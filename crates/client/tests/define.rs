@@ -1,5 +1,5 @@
 use test_client::TestClient;
-use types::core::Ident;
+use types::{core::Ident, lang::Warning};
 
 mod test_client;
 
@@ -85,3 +85,32 @@ async fn multiple_requests() {
     assert!(items[&Ident::from("b")] == 2.into());
     assert!(items[&Ident::from("c")] == 5.into());
 }
+
+#[tokio::test]
+async fn warnings() {
+    let client = TestClient::new().await;
+
+    let warnings = client
+        .define(
+            r#"
+            x = 1
+            y = let x = 2, in x
+        "#,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        warnings,
+        vec![Warning::ShadowedLetBinding(Ident::from("x"))]
+    );
+
+    let res = client.exec("z = if true then 1, else 2").await;
+    assert!(res.is_ok());
+    assert_eq!(
+        res.warnings,
+        vec![
+            Warning::ConstantIfCondition(true),
+            Warning::UnreachableIfCase
+        ]
+    );
+}
@@ -0,0 +1,94 @@
+use std::fs;
+
+use tempfile::TempDir;
+use test_client::TestClient;
+use types::{
+    core::{Ident, Line, Pt, Value},
+    export::{DrawingFormat, ImportOptions},
+};
+
+mod test_client;
+
+#[tokio::test]
+async fn load_svg() {
+    let client = TestClient::new().await;
+
+    let res = client
+        .exec(
+            r#"
+            A = pt 1.0 2.0
+            l = line A (pt 3.0 -1.0)
+            "#,
+        )
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+
+    let dir = TempDir::new().unwrap();
+    let file = dir.path().join("drawing.svg");
+
+    let res = client
+        .exec(format!(r#"save_svg! {file:?} "lines: segments""#))
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+
+    let res = client.exec(format!("clear!\nload_svg! {file:?}")).await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+
+    // Saved svg is loaded in the original coordinates
+    let items = client.get_all_items().await.unwrap();
+    assert_eq!(items.len(), 2);
+    let is_a = |value: &Value| match value {
+        Value::Pt(Some(pt)) => {
+            let (x, y) = pt.to_f64();
+            (x - 1.0).abs() < 1e-9 && (y - 2.0).abs() < 1e-9
+        }
+        _ => false,
+    };
+    assert!(items.values().any(is_a));
+
+    let res = client
+        .exec(format!(r#"load_svg! {file:?} "scale: 0""#))
+        .await;
+    assert!(res.error.is_some());
+}
+
+#[tokio::test]
+async fn load_dxf() {
+    let client = TestClient::new().await;
+
+    let dir = TempDir::new().unwrap();
+    let file = dir.path().join("drawing.dxf");
+    fs::write(
+        &file,
+        "0\nSECTION\n2\nENTITIES\n0\nLINE\n10\n0.0\n20\n0.0\n11\n10.0\n21\n20.0\n0\nENDSEC\n0\nEOF\n",
+    )
+    .unwrap();
+
+    let res = client
+        .exec(format!(r#"load_dxf! {file:?} "scale: 10""#))
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+
+    assert_eq!(
+        client.get_all_items().await.unwrap()[&Ident::from("a")],
+        Line {
+            p1: Pt::new(0.0, 0.0),
+            p2: Pt::new(1.0, 2.0),
+        }
+        .into()
+    );
+
+    let names = client
+        .import_drawing(
+            DrawingFormat::Dxf,
+            "0\nSECTION\n2\nENTITIES\n0\nPOINT\n10\n1.0\n20\n1.0\n0\nENDSEC\n".to_string(),
+            ImportOptions::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(names, vec![Ident::from("A")]);
+
+    fs::write(&file, "0\nLINE\n").unwrap();
+    let res = client.exec(format!("load_dxf! {file:?}")).await;
+    assert!(res.error.is_some());
+}
@@ -0,0 +1,26 @@
+#![cfg(feature = "embedded")]
+
+use client::Client;
+
+#[tokio::test]
+async fn embedded() {
+    let client = Client::new_embedded();
+
+    client
+        .define(
+            r#"
+        x = 2
+        y = x^2 + 1
+    "#,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(client.get_item("y").await.unwrap(), 5.into());
+
+    client.set("x", "3").await.unwrap();
+    assert_eq!(client.get_item("y").await.unwrap(), 10.into());
+
+    assert_eq!(client.eval_one("y + 1").await.unwrap(), 11.into());
+    assert!(client.get_item("z").await.is_err());
+}
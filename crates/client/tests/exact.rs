@@ -0,0 +1,57 @@
+use test_client::TestClient;
+use types::core::{Rational, Real, Value};
+
+mod test_client;
+
+#[tokio::test]
+async fn exact() {
+    let client = TestClient::new().await;
+    assert!(!client.is_exact().await.unwrap());
+
+    let res = client
+        .exec(
+            r#"
+            exact! on
+            third = 1.0 / 3.0
+            assert! (0.1 + 0.2 == 0.3)
+            assert_eq! (third * 3.0) 1.0 0.0
+            assert! ((2 as real) ^ 2.0 == 4.0)
+            "#,
+        )
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+    assert!(client.is_exact().await.unwrap());
+
+    let third = client.get_item("third").await.unwrap();
+    assert_eq!(
+        third,
+        Value::from(Real::Exact(Rational::new(1, 3).unwrap()))
+    );
+    assert!(matches!(third, Value::Real(Some(Real::Exact(_)))));
+    assert_eq!(third.to_string(), "(1.0 / 3.0)");
+
+    // Shown values can be set back, e.g. when they are edited in the gui
+    let res = client.exec("P = pt (1.0 / 3.0) -2.0").await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+    let p = client.get_item("P").await.unwrap();
+    for (name, value) in [("third", &third), ("P", &p)] {
+        client.set(name, value.to_string()).await.unwrap();
+        assert_eq!(&client.get_item(name).await.unwrap(), value);
+    }
+
+    // Only in an empty scope
+    let res = client.exec("exact! off").await;
+    assert!(res.error.is_some());
+
+    let res = client.exec("clear!\nexact! off\nthird = 1.0 / 3.0").await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+    assert!(matches!(
+        client.get_item("third").await.unwrap(),
+        Value::Real(Some(Real::Float(_)))
+    ));
+
+    for cmd in ["exact! yes", "exact! on off"] {
+        let res = client.exec(cmd).await;
+        assert!(res.error.is_some(), "{cmd}");
+    }
+}
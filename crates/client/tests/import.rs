@@ -0,0 +1,50 @@
+use std::fs;
+
+use tempfile::TempDir;
+use test_client::TestClient;
+
+mod test_client;
+
+#[tokio::test]
+async fn import() {
+    let client = TestClient::new().await;
+
+    let dir = TempDir::new().unwrap();
+    let lib = dir.path().join("geo.geom");
+    fs::write(
+        &lib,
+        r#"
+        sq x:real -> real = x * x
+        len_sq p:pt -> real = p.x.sq + p.y.sq
+        "#,
+    )
+    .unwrap();
+
+    let res = client
+        .exec(format!(
+            r#"
+            import! {lib:?} geo
+            l = geo::len_sq (pt 3.0 4.0)
+            "#
+        ))
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+    assert_eq!(client.get_item("l").await.unwrap(), 25.0.into());
+
+    // Functions and libraries are stored along with items
+    let json = client.dump_json().await.unwrap();
+    client.clear().await.unwrap();
+    assert!(client.eval_one("geo::sq 2.0").await.is_err());
+
+    client.load_json(json).await.unwrap();
+    assert_eq!(client.eval_one("geo::sq 2.0").await.unwrap(), 4.0.into());
+
+    // Name clash
+    let res = client.exec(format!("import! {lib:?} geo")).await;
+    assert!(res.error.is_none(), "importing twice should be a no-op");
+    let res = client
+        .exec(format!("sq x:real -> real = x\nimport! {lib:?}"))
+        .await;
+    let err = format!("{:#}", res.error.unwrap());
+    assert!(err.contains("function redefinition"), "{err}");
+}
@@ -0,0 +1,69 @@
+use test_client::TestClient;
+
+mod test_client;
+
+#[tokio::test]
+async fn labels() {
+    let client = TestClient::new().await;
+
+    let res = client
+        .exec(
+            r#"
+            A = pt 0.0 0.0
+            B = pt 1.0 0.0
+            l = line A B
+            "#,
+        )
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+
+    // Labels are hidden by default
+    assert!(client.get_all_labels().await.unwrap().is_empty());
+
+    let res = client
+        .exec(
+            r#"
+            style! A "label: show; label-text: O"
+            style! l "label: show"
+            "#,
+        )
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+
+    let labels = client.get_all_labels().await.unwrap();
+    assert_eq!(labels.len(), 2);
+    assert_eq!(labels[&"A".into()], "O");
+    assert_eq!(labels[&"l".into()], "l");
+
+    let res = client
+        .exec(
+            r#"
+            labels! show
+            style! l "label: hide"
+            "#,
+        )
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+
+    let labels = client.get_all_labels().await.unwrap();
+    assert_eq!(labels.len(), 2);
+    assert_eq!(labels[&"B".into()], "B");
+    assert!(!labels.contains_key(&"l".into()));
+
+    // Hidden items have no labels
+    client.exec(r#"style! B "hidden""#).await;
+    assert!(!client
+        .get_all_labels()
+        .await
+        .unwrap()
+        .contains_key(&"B".into()));
+
+    // The flag is stored along with items
+    let json = client.dump_json().await.unwrap();
+    client.exec("labels! hide").await;
+    assert_eq!(client.get_all_labels().await.unwrap().len(), 1);
+    client.load_json(json).await.unwrap();
+    assert_eq!(client.get_all_labels().await.unwrap().len(), 1);
+
+    assert!(client.exec("labels! maybe").await.error.is_some());
+}
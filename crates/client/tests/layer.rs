@@ -0,0 +1,69 @@
+use test_client::TestClient;
+use types::{core::Ident, style::Color};
+
+mod test_client;
+
+#[tokio::test]
+async fn layer() {
+    let client = TestClient::new().await;
+
+    let res = client
+        .exec(
+            r#"
+            A = pt 0.0 0.0
+            B = pt 1.0 0.0
+            C = pt 0.0 1.0
+            l = line A B
+            layer! base A B
+            layer! lines l
+            layer! base "hidden; locked"
+            style! lines C "color: red"
+            "#,
+        )
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+
+    let layers = client.get_all_layers().await.unwrap();
+    assert_eq!(layers.len(), 2);
+    assert_eq!(layers[0].name, Ident::from("base"));
+    assert_eq!(layers[0].items, vec![Ident::from("A"), Ident::from("B")]);
+    assert!(layers[0].hidden && layers[0].locked);
+    assert!(!layers[1].hidden && !layers[1].locked);
+
+    // Restyling a layer restyles it's items
+    let styles = client.get_all_styles().await.unwrap();
+    assert_eq!(styles.len(), 2);
+    assert_eq!(styles[&"l".into()].color, Some(Color::rgb(255, 0, 0)));
+
+    // Locked items can be neither changed nor removed
+    assert!(client.exec("set! A (pt 1.0 1.0)").await.error.is_some());
+    assert!(client.exec("rm! base").await.error.is_some());
+    assert!(client
+        .exec(r#"layer! base "unlocked""#)
+        .await
+        .error
+        .is_none());
+    assert!(client.exec("set! A (pt 1.0 1.0)").await.error.is_none());
+
+    // Layers are stored along with items
+    let json = client.dump_json().await.unwrap();
+    client.clear().await.unwrap();
+    assert!(client.get_all_layers().await.unwrap().is_empty());
+    client.load_json(json).await.unwrap();
+    assert_eq!(client.get_all_layers().await.unwrap().len(), 2);
+
+    // Removing a layer removes it's items and items, that depend on them
+    assert!(client.exec("rm! base").await.error.is_none());
+    let items = client.get_all_items().await.unwrap();
+    assert_eq!(items.len(), 1);
+    assert!(items.contains_key(&"C".into()));
+    assert_eq!(client.get_all_layers().await.unwrap().len(), 1);
+
+    assert!(client.exec(r#"layer! C "hidden""#).await.error.is_some());
+    assert!(client
+        .exec(r#"layer! lines "dimmed""#)
+        .await
+        .error
+        .is_some());
+    assert!(client.exec("layer! other D").await.error.is_some());
+}
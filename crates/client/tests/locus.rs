@@ -0,0 +1,39 @@
+use test_client::TestClient;
+use types::core::{Pt, Value};
+
+mod test_client;
+
+#[tokio::test]
+async fn locus() {
+    let client = TestClient::new().await;
+
+    client
+        .define(
+            r#"
+        t = 0.0
+        k = 1.0
+        l = line (pt 0.0 0.0) (pt 1.0 0.0)
+        P = pt_on l t
+        Q = pt (x P) (k * (x P) * (x P))
+        L = locus Q P 3
+    "#,
+        )
+        .await
+        .unwrap();
+
+    let Value::Polyline(Some(locus)) = client.get_item("L").await.unwrap() else {
+        panic!("locus should be a polyline");
+    };
+    assert_eq!(
+        locus.pts,
+        vec![Pt::new(0.0, 0.0), Pt::new(0.5, 0.25), Pt::new(1.0, 1.0)]
+    );
+
+    client.set("k", "2.0").await.unwrap();
+    let Value::Polyline(Some(locus)) = client.get_item("L").await.unwrap() else {
+        panic!("locus should be a polyline");
+    };
+    assert_eq!(locus.pts[2], Pt::new(1.0, 2.0));
+
+    assert!(client.define("M = locus Q l").await.is_err());
+}
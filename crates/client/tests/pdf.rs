@@ -0,0 +1,43 @@
+use std::fs;
+
+use tempfile::TempDir;
+use test_client::TestClient;
+
+mod test_client;
+
+#[tokio::test]
+async fn pdf() {
+    let client = TestClient::new().await;
+
+    let res = client
+        .exec(
+            r#"
+            A = pt 0.0 0.0
+            B = pt 1.0 2.0
+            l = line A B
+            "#,
+        )
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+
+    let dir = TempDir::new().unwrap();
+    let file = dir.path().join("drawing.pdf");
+
+    let res = client
+        .exec(format!(
+            r#"save_pdf! {file:?} "page: a5 landscape; margin: 10; background: white""#
+        ))
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+
+    let pdf = fs::read(&file).unwrap();
+    let pdf = String::from_utf8_lossy(&pdf);
+    assert!(pdf.starts_with("%PDF-"));
+    assert!(pdf.contains("/MediaBox [0 0 595.2756 419.5276]"));
+    assert!(pdf.contains("1 1 1 rg"));
+
+    let res = client
+        .exec(format!(r#"save_pdf! {file:?} "page: a4; margin: 150""#))
+        .await;
+    assert!(res.error.is_some());
+}
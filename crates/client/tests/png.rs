@@ -0,0 +1,135 @@
+use std::{env, fs, path::Path};
+
+use tempfile::TempDir;
+use test_client::TestClient;
+use types::export::ExportOptions;
+
+mod test_client;
+
+/// Set it to regenerate golden images after intended changes in rendering.
+const BLESS_VAR: &str = "GEOMETRICA_BLESS_GOLDEN";
+
+/// Channels may differ by this much because of antialiasing.
+const CHANNEL_TOLERANCE: u8 = 16;
+
+/// Fraction of pixels, that may differ more than [CHANNEL_TOLERANCE].
+const PIXEL_TOLERANCE: f64 = 0.001;
+
+#[tokio::test]
+async fn png() {
+    let client = TestClient::new().await;
+
+    let res = client
+        .exec(
+            r#"
+            A = pt 0.0 0.0
+            B = pt 1.0 2.0
+            "#,
+        )
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+
+    let dir = TempDir::new().unwrap();
+    let file = dir.path().join("drawing.png");
+
+    let res = client
+        .exec(format!(
+            r#"save_png! {file:?} "viewport: 0 0 2 2; width: 200; dpi: 192""#
+        ))
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+
+    let (width, height, _pixels) = decode(&fs::read(&file).unwrap());
+    assert_eq!((width, height), (400, 400));
+
+    let res = client.exec(format!(r#"save_png! {file:?} "dpi: 0""#)).await;
+    assert!(res.error.is_some());
+}
+
+/// Compares renders of `examples/geom` with golden images in `tests/golden`.
+///
+/// Missing golden images are only created, if `GEOMETRICA_BLESS_GOLDEN` is set.
+#[tokio::test]
+async fn golden() {
+    let client = TestClient::new().await;
+
+    let examples = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../examples/geom");
+    let golden_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+    let bless = env::var_os(BLESS_VAR).is_some();
+
+    let mut options = ExportOptions::default();
+    options
+        .apply("width: 400; background: white; lines: segments")
+        .unwrap();
+
+    let mut scripts: Vec<_> = fs::read_dir(&examples)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "geom"))
+        .collect();
+    scripts.sort();
+    assert!(!scripts.is_empty());
+
+    for script in scripts {
+        let name = script.file_stem().unwrap().to_str().unwrap();
+
+        // Examples save themselves to the working directory
+        let script: String = fs::read_to_string(&script)
+            .unwrap()
+            .lines()
+            .filter(|line| !line.trim_start().starts_with("save_"))
+            .map(|line| format!("{line}\n"))
+            .collect();
+
+        let res = client.exec(script).await;
+        assert!(res.error.is_none(), "{name}: {:?}", res.error);
+
+        let png = client.dump_png(options.clone()).await.unwrap();
+
+        let golden_file = golden_dir.join(format!("{name}.png"));
+        if bless {
+            fs::write(&golden_file, &png).unwrap();
+            continue;
+        }
+        assert!(
+            golden_file.exists(),
+            "{name}: golden image is missing, set {BLESS_VAR}=1 to create it"
+        );
+
+        let (width, height, pixels) = decode(&png);
+        let (golden_width, golden_height, golden_pixels) = decode(&fs::read(&golden_file).unwrap());
+        assert_eq!(
+            (width, height),
+            (golden_width, golden_height),
+            "{name}: size differs from golden image"
+        );
+
+        let differing = pixels
+            .chunks(4)
+            .zip(golden_pixels.chunks(4))
+            .filter(|(pixel, golden)| {
+                pixel
+                    .iter()
+                    .zip(golden.iter())
+                    .any(|(a, b)| a.abs_diff(*b) > CHANNEL_TOLERANCE)
+            })
+            .count();
+        let total = (width * height) as f64;
+        assert!(
+            differing as f64 <= total * PIXEL_TOLERANCE,
+            "{name}: {differing} pixels differ from golden image, set {BLESS_VAR}=1 to update it"
+        );
+    }
+}
+
+/// Returns width, height and rgba pixels.
+fn decode(png: &[u8]) -> (u32, u32, Vec<u8>) {
+    let mut decoder = png::Decoder::new(png);
+    decoder.set_transformations(png::Transformations::EXPAND);
+    let mut reader = decoder.read_info().unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut pixels).unwrap();
+    assert_eq!(info.color_type, png::ColorType::Rgba);
+    pixels.truncate(info.buffer_size());
+    (info.width, info.height, pixels)
+}
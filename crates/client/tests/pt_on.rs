@@ -0,0 +1,50 @@
+use test_client::TestClient;
+use types::core::{Ident, Line, Pt, PtOn, Value};
+
+mod test_client;
+
+#[tokio::test]
+async fn pt_on() {
+    let client = TestClient::new().await;
+
+    client
+        .define(
+            r#"
+        t = 0.5
+        l = line (pt 0.0 0.0) (pt 4.0 0.0)
+        P = pt_on l t
+    "#,
+        )
+        .await
+        .unwrap();
+
+    let pt_ons = client.get_all_pt_ons().await.unwrap();
+    assert_eq!(
+        pt_ons[&Ident::from("P")],
+        PtOn {
+            object: Line {
+                p1: Pt::new(0.0, 0.0),
+                p2: Pt::new(4.0, 0.0),
+            }
+            .into(),
+            param: Ident::from("t"),
+        }
+    );
+
+    // Point is moved by its parameter
+    client.set("t", "0.25").await.unwrap();
+    assert_eq!(
+        client.get_item("P").await.unwrap(),
+        Value::from(Pt::new(1.0, 0.0))
+    );
+
+    // Constrained points survive saving and loading
+    let json = client.dump_json().await.unwrap();
+    client.clear().await.unwrap();
+    client.load_json(json).await.unwrap();
+    assert_eq!(client.get_all_pt_ons().await.unwrap(), pt_ons);
+    assert_eq!(
+        client.get_item("P").await.unwrap(),
+        Value::from(Pt::new(1.0, 0.0))
+    );
+}
@@ -0,0 +1,66 @@
+use test_client::TestClient;
+use types::{core::Ident, slider::Slider};
+
+mod test_client;
+
+#[tokio::test]
+async fn slider() {
+    let client = TestClient::new().await;
+
+    let res = client
+        .exec(
+            r#"
+            r = 1.0
+            n = 3
+            c = r * 2.0
+            slider! r "min: 0; max: 10; step: 0.5"
+            slider! n "min: 1; max: 12"
+            "#,
+        )
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+
+    let sliders = client.get_all_sliders().await.unwrap();
+    assert_eq!(
+        sliders[&Ident::from("r")],
+        Slider {
+            min: 0.0,
+            max: 10.0,
+            step: Some(0.5),
+        }
+    );
+    assert_eq!(
+        sliders[&Ident::from("n")],
+        Slider {
+            min: 1.0,
+            max: 12.0,
+            step: None,
+        }
+    );
+
+    // Only free numeric items can have sliders
+    let res = client.exec(r#"slider! c "max: 5""#).await;
+    assert!(res.error.is_some());
+    let res = client.exec(r#"slider! n "step: 0.5""#).await;
+    assert!(res.error.is_some());
+    let res = client.exec(r#"slider! r "min: 20""#).await;
+    assert!(res.error.is_some());
+
+    // Sliders survive saving and loading
+    let json = client.dump_json().await.unwrap();
+    client.clear().await.unwrap();
+    client.load_json(json).await.unwrap();
+    assert_eq!(client.get_all_sliders().await.unwrap(), sliders);
+
+    let res = client.exec(r#"slider! n "none""#).await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+    assert!(!client
+        .get_all_sliders()
+        .await
+        .unwrap()
+        .contains_key(&Ident::from("n")));
+
+    // Removing an item removes its slider
+    client.rm("r").await.unwrap();
+    assert!(client.get_all_sliders().await.unwrap().is_empty());
+}
@@ -0,0 +1,46 @@
+use test_client::TestClient;
+use types::style::{Color, Style};
+
+mod test_client;
+
+#[tokio::test]
+async fn style() {
+    let client = TestClient::new().await;
+
+    let res = client
+        .exec(
+            r#"
+            A = pt 0.0 0.0
+            B = pt 1.0 0.0
+            l = line A B
+            style! A l "color: gray; dash: 4 2"
+            style! l "hidden"
+            "#,
+        )
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+
+    let style = client.get_style("l").await.unwrap();
+    assert_eq!(style.color, Some(Color::rgb(128, 128, 128)));
+    assert_eq!(style.dash, Some(vec![4.0, 2.0]));
+    assert!(style.hidden);
+
+    let styles = client.get_all_styles().await.unwrap();
+    assert_eq!(styles.len(), 2);
+    assert!(!styles.contains_key(&"B".into()));
+
+    // Styles are stored along with items
+    let json = client.dump_json().await.unwrap();
+    client.clear().await.unwrap();
+    assert!(client.get_style("l").await.is_err());
+    client.load_json(json).await.unwrap();
+    assert_eq!(client.get_style("l").await.unwrap(), style);
+
+    // Resetting a style and removing an item forget it
+    client.set_style("A", Style::default()).await.unwrap();
+    client.rm("B").await.unwrap();
+    assert!(client.get_all_styles().await.unwrap().is_empty());
+
+    assert!(client.exec(r#"style! A "size: 2""#).await.error.is_some());
+    assert!(client.exec(r#"style! C "hidden""#).await.error.is_some());
+}
@@ -0,0 +1,45 @@
+use std::fs;
+
+use tempfile::TempDir;
+use test_client::TestClient;
+use types::export::ExportOptions;
+
+mod test_client;
+
+#[tokio::test]
+async fn svg() {
+    let client = TestClient::new().await;
+
+    let res = client
+        .exec(
+            r#"
+            A = pt 0.0 0.0
+            B = pt 1.0 2.0
+            "#,
+        )
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+
+    // Y axis points up by default
+    let svg = client.dump_svg(ExportOptions::default()).await.unwrap();
+    assert!(svg.contains(r#"y1="-2000""#));
+
+    let dir = TempDir::new().unwrap();
+    let file = dir.path().join("drawing.svg");
+
+    let res = client
+        .exec(format!(
+            r#"save_svg! {file:?} "viewport: 0 0 2 2; width: 200; y-axis: down""#
+        ))
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+
+    let svg = fs::read_to_string(&file).unwrap();
+    assert!(svg.contains(r#"viewBox="0 0 200 200""#));
+    assert!(svg.contains(r#"y1="200""#));
+
+    let res = client
+        .exec(format!(r#"save_svg! {file:?} "width: 0""#))
+        .await;
+    assert!(res.error.is_some());
+}
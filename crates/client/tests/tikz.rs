@@ -0,0 +1,96 @@
+use std::{env, fs, path::Path};
+
+use tempfile::TempDir;
+use test_client::TestClient;
+use types::export::TikzOptions;
+
+mod test_client;
+
+/// Set it to regenerate snapshots after intended changes in the output.
+const BLESS_VAR: &str = "GEOMETRICA_BLESS_GOLDEN";
+
+#[tokio::test]
+async fn tikz() {
+    let client = TestClient::new().await;
+
+    let res = client
+        .exec(
+            r#"
+            A = pt 0.0 0.0
+            B = pt 1.0 2.0
+            "#,
+        )
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+
+    let dir = TempDir::new().unwrap();
+    let file = dir.path().join("drawing.tex");
+
+    let res = client
+        .exec(format!(r#"save_tikz! {file:?} "scale: 2""#))
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+
+    let tikz = fs::read_to_string(&file).unwrap();
+    assert!(tikz.starts_with(r"\begin{tikzpicture}[scale=2]"));
+    // Coordinates are not scaled
+    assert!(tikz.contains(r"\coordinate (B) at (1, 2);"));
+
+    let res = client
+        .exec(format!(r#"save_tikz! {file:?} "width: 100""#))
+        .await;
+    assert!(res.error.is_some());
+}
+
+/// Compares output for `examples/geom` with snapshots in `tests/golden`.
+///
+/// Missing snapshots are only created, if `GEOMETRICA_BLESS_GOLDEN` is set.
+#[tokio::test]
+async fn snapshots() {
+    let client = TestClient::new().await;
+
+    let examples = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../examples/geom");
+    let golden_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+    let bless = env::var_os(BLESS_VAR).is_some();
+
+    let mut scripts: Vec<_> = fs::read_dir(&examples)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "geom"))
+        .collect();
+    scripts.sort();
+    assert!(!scripts.is_empty());
+
+    for script in scripts {
+        let name = script.file_stem().unwrap().to_str().unwrap();
+
+        // Examples save themselves to the working directory
+        let script: String = fs::read_to_string(&script)
+            .unwrap()
+            .lines()
+            .filter(|line| !line.trim_start().starts_with("save_"))
+            .map(|line| format!("{line}\n"))
+            .collect();
+
+        let res = client.exec(script).await;
+        assert!(res.error.is_none(), "{name}: {:?}", res.error);
+
+        let tikz = client.dump_tikz(TikzOptions::default()).await.unwrap();
+
+        let golden_file = golden_dir.join(format!("{name}.tex"));
+        if bless {
+            fs::write(&golden_file, &tikz).unwrap();
+            continue;
+        }
+        assert!(
+            golden_file.exists(),
+            "{name}: snapshot is missing, set {BLESS_VAR}=1 to create it"
+        );
+
+        let golden = fs::read_to_string(&golden_file).unwrap();
+        assert!(
+            tikz == golden,
+            "{name}: output differs from snapshot, set {BLESS_VAR}=1 to update it\n{tikz}"
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use test_client::TestClient;
+use types::core::Value;
+
+mod test_client;
+
+#[tokio::test]
+async fn tolerance() {
+    let client = TestClient::new().await;
+
+    let res = client
+        .exec(
+            r#"
+            d = 0.001
+            A = pt 0.0 0.0
+            B = pt d 0.0
+            C = pt 1.0 1.0
+            same = A == B
+            assert! (line A C == line C (pt 2.0 2.0))
+            assert! (parallel? (line A B) (line C (pt 2.0 1.0)))
+            assert! (perpendicular? (line A C) (line (pt 1.0 0.0) (pt 0.0 1.0)))
+            assert! (collinear? A B (pt 2.0 0.0))
+            assert! (concyclic? (pt 1.0 0.0) (pt 0.0 1.0) (pt (-1.0) 0.0) (pt 0.0 (-1.0)))
+            assert! (on (pt 0.5 0.5) (line A C))
+            assert! (approx_eq A B 0.01)
+            "#,
+        )
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+    assert_eq!(client.get_item("same").await.unwrap(), Value::from(false));
+
+    let res = client.exec("tolerance! 0.01").await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+    assert_eq!(client.get_tolerance().await.unwrap(), 0.01);
+    assert_eq!(client.get_item("same").await.unwrap(), Value::from(true));
+
+    // assert_eq! uses the tolerance by default
+    let res = client.exec("assert_eq! A B").await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+    let res = client.exec("assert_eq! A B 0.0001").await;
+    assert!(res.error.is_some());
+
+    for cmd in ["tolerance! (-1.0)", "tolerance! 1", "tolerance! 0.1 0.2"] {
+        let res = client.exec(cmd).await;
+        assert!(res.error.is_some(), "{cmd}");
+    }
+}
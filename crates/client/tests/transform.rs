@@ -0,0 +1,68 @@
+use test_client::TestClient;
+use types::core::{Pt, Rational, Real, Value, ValueType};
+
+mod test_client;
+
+#[tokio::test]
+async fn transform() {
+    let client = TestClient::new().await;
+
+    let res = client
+        .exec(
+            r#"
+            A = pt 0.0 0.0
+            B = pt 2.0 0.0
+            l = line A (pt 0.0 1.0)
+            t = (translate (pt 1.0 1.0)) * (reflect l)
+            B2 = apply t B
+            assert_eq! B2 (pt (-1.0) 1.0)
+            assert_eq! (apply (inverse t) B2) B
+            assert_eq! (apply (scale A 2.0) (circ B 1.0)) (circ (pt 4.0 0.0) 2.0)
+            assert_eq! (apply (rotate A (3.14159265358979 / 2.0)) l) (line A (pt (-1.0) 0.0)) 1e-6
+            "#,
+        )
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+
+    let Value::Transform(Some(t)) = client.get_item("t").await.unwrap() else {
+        panic!("t should be a transform");
+    };
+    assert_eq!(t.apply_pt(Pt::new(0.0, 3.0)), Pt::new(1.0, 4.0));
+    assert_eq!(
+        Value::from(t).to_string(),
+        "transform [-1.000 0.000 1.000; 0.000 1.000 1.000]"
+    );
+
+    let res = client.exec("n = inverse (scale A 0.0)").await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+    assert_eq!(
+        client.get_item("n").await.unwrap(),
+        Value::none(ValueType::Transform)
+    );
+
+    // Exact points are transformed exactly
+    let res = client
+        .exec(
+            r#"
+            clear!
+            exact! on
+            l = line (pt 0.0 1.0) (pt 1.0 3.0)
+            t = (translate (pt 1.0 2.0)) * (scale (pt 1.0 0.0) (1.0 / 3.0)) * (reflect l)
+            P = apply (inverse t) (apply t (pt 2.0 0.0))
+            Q = apply t (pt 2.0 0.0)
+            "#,
+        )
+        .await;
+    assert!(res.error.is_none(), "{:?}", res.error);
+    let exact = |num, den| Real::Exact(Rational::new(num, den).unwrap());
+    for (name, pt) in [
+        ("P", Pt::new(exact(2, 1), exact(0, 1))),
+        ("Q", Pt::new(exact(1, 1), exact(8, 3))),
+    ] {
+        let Value::Pt(Some(p)) = client.get_item(name).await.unwrap() else {
+            panic!("{name} should be a pt");
+        };
+        assert!(p.x.is_exact() && p.y.is_exact(), "{name} = {p}");
+        assert_eq!(p, pt);
+    }
+}
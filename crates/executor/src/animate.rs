@@ -0,0 +1,236 @@
+//! This module implements exporting animations, that sweep a free variable.
+//!
+//! Every frame is the scene of the scope with the variable set to the frame's value,
+//! and all frames share the same viewport.
+
+use svg::{
+    node::element::{Animate, Group},
+    Node,
+};
+use thiserror::Error;
+use types::{
+    core::{Ident, Value, ValueType},
+    export::{AnimationOptions, ExportOptions, FrameFormat, Sweep, MAX_SWEEP_FRAMES},
+};
+
+use crate::{
+    exec::{ExecError, ExecScope},
+    node::NodeInnerKind,
+    raster::{scene_to_png, RasterError},
+    svg::{build_empty_doc, populate_doc_with_scene, Frame, Item},
+};
+
+const FRAME_CLASS: &str = "frame";
+
+#[derive(Debug, Error)]
+pub enum AnimationError {
+    #[error("exec error")]
+    ExecError(#[from] ExecError),
+
+    #[error("raster error")]
+    RasterError(#[from] RasterError),
+
+    #[error("only free int and real items can be animated, but '{0}' is not one")]
+    NotFree(Ident),
+
+    #[error("animation should have at least one frame")]
+    NoFrames,
+
+    #[error("animation can have at most {MAX_SWEEP_FRAMES} frames, got {0}")]
+    TooManyFrames(u32),
+}
+
+impl ExecScope {
+    /// Exports every frame of the sweep to a separate image.
+    pub fn animation_to_frames(
+        &mut self,
+        name: &Ident,
+        sweep: Sweep,
+        format: FrameFormat,
+        options: &AnimationOptions,
+    ) -> Result<Vec<Vec<u8>>, AnimationError> {
+        let (frames, frame) = self.sweep_items(name, sweep, &options.export)?;
+
+        frames
+            .into_iter()
+            .map(|items| {
+                let scene = self.scene_in_frame(items, frame.clone(), &options.export);
+                Ok(match format {
+                    FrameFormat::Svg => {
+                        let doc = build_empty_doc(&scene.frame, &options.export);
+                        populate_doc_with_scene(doc, &scene, true)
+                            .to_string()
+                            .into_bytes()
+                    }
+                    FrameFormat::Png => scene_to_png(&scene, &options.export)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Exports the sweep to a single svg, that shows it's frames in turn with SMIL animation.
+    ///
+    /// Viewers without SMIL support show the first frame.
+    pub fn animation_to_svg(
+        &mut self,
+        name: &Ident,
+        sweep: Sweep,
+        options: &AnimationOptions,
+    ) -> Result<String, AnimationError> {
+        let (frames, frame) = self.sweep_items(name, sweep, &options.export)?;
+        let count = frames.len();
+
+        let mut doc = build_empty_doc(&frame, &options.export);
+        for (idx, items) in frames.into_iter().enumerate() {
+            let scene = self.scene_in_frame(items, frame.clone(), &options.export);
+
+            let mut group = Group::new().set("class", FRAME_CLASS);
+            if idx != 0 {
+                group = group.set("display", "none");
+            }
+            let group = populate_doc_with_scene(group, &scene, false);
+            doc.append(group.add(frame_visibility(idx, count, options.duration)));
+        }
+
+        Ok(doc.to_string())
+    }
+
+    /// Visible items of every frame along with the frame, that fits all of them.
+    ///
+    /// The variable gets it's value back afterwards.
+    fn sweep_items(
+        &mut self,
+        name: &Ident,
+        sweep: Sweep,
+        options: &ExportOptions,
+    ) -> Result<(Vec<Vec<Item>>, Frame), AnimationError> {
+        let node = self
+            .get_node(name)
+            .ok_or_else(|| ExecError::UndefinedVariable(name.clone()))?;
+
+        if !matches!(node.inner().kind, NodeInnerKind::Value(_)) {
+            return Err(AnimationError::NotFree(name.clone()));
+        }
+        let value_type = node.value_type();
+        let to_value = |v: f64| match value_type {
+            ValueType::Int => Ok(Value::from(v.round() as i64)),
+            ValueType::Real => Ok(Value::from(v)),
+            _ => Err(AnimationError::NotFree(name.clone())),
+        };
+
+        if sweep.frames == 0 {
+            return Err(AnimationError::NoFrames);
+        }
+        if sweep.frames > MAX_SWEEP_FRAMES {
+            return Err(AnimationError::TooManyFrames(sweep.frames));
+        }
+
+        let old_value = node.get_value();
+        let frames: Result<Vec<_>, AnimationError> = (0..sweep.frames)
+            .map(|frame| {
+                self.set(name, to_value(sweep.value(frame))?)?;
+                Ok(self.visible_items(options))
+            })
+            .collect();
+        self.set(name, old_value)?;
+        let frames = frames?;
+
+        let frame = self.get_frame(&frames.concat(), options);
+        Ok((frames, frame))
+    }
+}
+
+/// Shows the frame only during it's part of the animation.
+fn frame_visibility(idx: usize, count: usize, duration: f64) -> Animate {
+    let mut key_times = Vec::new();
+    let mut values = Vec::new();
+
+    if idx != 0 {
+        key_times.push(0.0);
+        values.push("none");
+    }
+    key_times.push(idx as f64 / count as f64);
+    values.push("inline");
+    if idx + 1 != count {
+        key_times.push((idx + 1) as f64 / count as f64);
+        values.push("none");
+    }
+
+    let key_times: Vec<_> = key_times.iter().map(f64::to_string).collect();
+    Animate::new()
+        .set("attributeName", "display")
+        .set("values", values.join(";"))
+        .set("keyTimes", key_times.join(";"))
+        .set("dur", format!("{duration}s"))
+        .set("calcMode", "discrete")
+        .set("repeatCount", "indefinite")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::exec::Exec;
+
+    use super::*;
+
+    #[test]
+    fn animate() {
+        let mut scope = ExecScope::new();
+        parser::definitions(
+            r#"
+            r = 1.0
+            n = 1
+            c = circ (pt 0.0 0.0) r
+            A = pt (n as real) 0.0
+            "#,
+        )
+        .unwrap()
+        .exec(&mut scope)
+        .unwrap();
+
+        let sweep = Sweep {
+            from: 1.0,
+            to: 2.0,
+            frames: 3,
+        };
+        let options = AnimationOptions::default();
+
+        let svg = scope
+            .animation_to_svg(&Ident::from("r"), sweep, &options)
+            .unwrap();
+        assert_eq!(svg.matches(r#"class="frame""#).count(), 3);
+        assert_eq!(svg.matches("<animate").count(), 3);
+        assert!(svg.contains(r#"keyTimes="0;0.3333333333333333;0.6666666666666666""#));
+        // All frames share the viewport, that fits the largest circle
+        assert!(svg.contains(r#"r="250""#));
+        assert!(svg.contains(r#"r="500""#));
+        assert_eq!(svg.matches("viewBox").count(), 1);
+
+        // The value is restored
+        assert_eq!(scope.get_item(&Ident::from("r")), Some(Value::from(1.0)));
+
+        let frames = scope
+            .animation_to_frames(&Ident::from("n"), sweep, FrameFormat::Svg, &options)
+            .unwrap();
+        assert_eq!(frames.len(), 3);
+        let first = String::from_utf8(frames[0].clone()).unwrap();
+        let last = String::from_utf8(frames[2].clone()).unwrap();
+        assert_eq!(
+            first.split('>').next().unwrap(),
+            last.split('>').next().unwrap()
+        );
+
+        assert!(matches!(
+            scope.animation_to_svg(&Ident::from("c"), sweep, &options),
+            Err(AnimationError::NotFree(_))
+        ));
+        assert!(matches!(
+            scope.animation_to_svg(&Ident::from("r"), Sweep { frames: 0, ..sweep }, &options),
+            Err(AnimationError::NoFrames)
+        ));
+        let frames = MAX_SWEEP_FRAMES + 1;
+        assert!(matches!(
+            scope.animation_to_svg(&Ident::from("r"), Sweep { frames, ..sweep }, &options),
+            Err(AnimationError::TooManyFrames(_))
+        ));
+    }
+}
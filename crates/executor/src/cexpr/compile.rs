@@ -1,4 +1,5 @@
 use std::{
+    cell::RefCell,
     collections::{hash_map, HashMap, HashSet},
     sync::Arc,
 };
@@ -8,12 +9,12 @@ use types::{
     core::{Ident, Value, ValueType},
     lang::{
         AsExpr, DotExpr, Expr, FuncCallExpr, FunctionSignature, IfExpr, InfixExpr, InfixOp,
-        LetExpr, LetExprDefinition, UnaryExpr, UnaryOp,
+        LetExpr, LetExprDefinition, UnaryExpr, UnaryOp, Warning,
     },
 };
 
 use crate::{
-    cexpr::{CExpr, CExprInner, CExprInnerKind, FuncCallCExpr, IfCExpr, IfCExprCase},
+    cexpr::{locus, CExpr, CExprInner, CExprInnerKind, FuncCallCExpr, IfCExpr, IfCExprCase},
     exec::ExecScope,
     function::Function,
 };
@@ -24,6 +25,9 @@ pub(crate) struct CScope<'a, 'b> {
     bindings: HashMap<Ident, CExpr>,
     var_types: HashMap<Ident, ValueType>,
     parent: Option<&'b CScope<'a, 'b>>,
+
+    /// Warnings are only collected in the root scope
+    warnings: RefCell<Vec<Warning>>,
 }
 
 impl<'a> CScope<'a, '_> {
@@ -33,6 +37,7 @@ impl<'a> CScope<'a, '_> {
             bindings: HashMap::new(),
             var_types: HashMap::new(),
             parent: None,
+            warnings: RefCell::new(Vec::new()),
         }
     }
 
@@ -42,7 +47,21 @@ impl<'a> CScope<'a, '_> {
             bindings: HashMap::new(),
             var_types: HashMap::new(),
             parent: Some(self),
+            warnings: RefCell::new(Vec::new()),
+        }
+    }
+
+    fn warn(&self, warning: Warning) {
+        let mut scope = self;
+        while let Some(parent) = scope.parent {
+            scope = parent;
         }
+        scope.warnings.borrow_mut().push(warning);
+    }
+
+    /// Returns warnings, collected while compiling in this scope and it's children.
+    pub(crate) fn take_warnings(&self) -> Vec<Warning> {
+        self.warnings.take()
     }
 
     fn insert_binding(&mut self, name: Ident, value: CExpr) -> Result<(), CError> {
@@ -96,6 +115,10 @@ impl<'a> CScope<'a, '_> {
     fn get_func(&self, sign: &FunctionSignature) -> Option<Function> {
         self.exec_scope.get_func(sign)
     }
+
+    pub(super) fn exec_scope(&self) -> &'a ExecScope {
+        self.exec_scope
+    }
 }
 
 /// Compile error
@@ -118,6 +141,15 @@ pub enum CError {
 
     #[error("if condition should be bool")]
     IfConditionNotBool,
+
+    #[error("locus expects a traced point, a moving point and optionally the number of samples")]
+    LocusArgs,
+
+    #[error("{0} should be defined as pt_on with a free real parameter to trace a locus")]
+    LocusNotPtOn(Ident),
+
+    #[error("{traced} doesn't depend on {moving}, so there is no locus")]
+    LocusIndependent { traced: Ident, moving: Ident },
 }
 
 /// Compile Result
@@ -150,11 +182,12 @@ impl Compile for Expr {
 }
 
 impl Compile for Value {
-    fn compile(self, _cscope: &CScope) -> CResult {
+    fn compile(self, cscope: &CScope) -> CResult {
+        let value = cscope.exec_scope.arithmetic.convert(self);
         Ok(CExpr::from_inner(CExprInner {
             required_vars: HashSet::new(),
-            value_type: self.value_type(),
-            kind: CExprInnerKind::Value(self),
+            value_type: value.value_type(),
+            kind: CExprInnerKind::Value(value),
         }))
     }
 }
@@ -180,6 +213,12 @@ impl Compile for Ident {
 impl Compile for FuncCallExpr {
     fn compile(self, cscope: &CScope) -> CResult {
         let FuncCallExpr { name, args } = self;
+
+        // Locus is compiled from definitions of it's arguments, so it's not a function
+        if name.0 == locus::LOCUS {
+            return locus::compile(args.into_iter().map(|arg| *arg).collect(), cscope);
+        }
+
         let args = args
             .clone()
             .into_iter()
@@ -223,6 +262,23 @@ impl Compile for IfExpr {
             })
             .collect::<Result<Vec<_>, _>>()?;
 
+        // Cases after an always true one, including the default one, can never be reached
+        let mut always_true = false;
+        let mut has_unreachable = false;
+        for case in &cases {
+            if always_true {
+                has_unreachable = true;
+                break;
+            }
+            if let CExprInnerKind::Value(Value::Bool(Some(cond))) = case.cond.0.kind {
+                cscope.warn(Warning::ConstantIfCondition(cond));
+                always_true = cond;
+            }
+        }
+        if has_unreachable || (always_true && default_case_value.is_some()) {
+            cscope.warn(Warning::UnreachableIfCase);
+        }
+
         assert!(!cases.is_empty());
         let value_type = cases[0].value.0.value_type.clone();
         let mut vars = HashSet::new();
@@ -286,6 +342,9 @@ impl Compile for LetExpr {
                     });
                 }
             }
+            if cscope.get_binding(&name).is_some() || cscope.get_var_type(&name).is_some() {
+                cscope.warn(Warning::ShadowedLetBinding(name.clone()));
+            }
             new_cscope.insert_binding(name, body)?;
         }
         body.compile(&new_cscope)
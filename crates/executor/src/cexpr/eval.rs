@@ -1,9 +1,11 @@
 use std::collections::HashMap;
 
 use thiserror::Error;
-use types::{core::Value, core::Ident};
+use types::{core::Ident, core::Polyline, core::Value};
 
-use crate::cexpr::{CExpr, CExprInnerKind, FuncCallCExpr, IfCExpr};
+use crate::cexpr::{
+    locus::MAX_LOCUS_SAMPLES, CExpr, CExprInnerKind, FuncCallCExpr, IfCExpr, LocusCExpr,
+};
 
 #[derive(Debug, Error)]
 pub enum EvalError {
@@ -12,6 +14,9 @@ pub enum EvalError {
 
     #[error("none of if cases matched")]
     NotingMatched,
+
+    #[error("locus needs from 2 to {MAX_LOCUS_SAMPLES} samples, got {0}")]
+    LocusSamples(i64),
 }
 
 pub type EvalResult = Result<Value, EvalError>;
@@ -33,6 +38,7 @@ impl Eval for CExpr {
             CExprInnerKind::Variable(e) => e.eval(vars),
             CExprInnerKind::FuncCall(e) => e.eval(vars),
             CExprInnerKind::If(e) => e.eval(vars),
+            CExprInnerKind::Locus(e) => e.eval(vars),
         }
     }
 }
@@ -85,6 +91,36 @@ impl Eval for IfCExpr {
     }
 }
 
+impl Eval for LocusCExpr {
+    fn eval(&self, vars: &VarsMap) -> EvalResult {
+        let samples = match self.samples.eval(vars)? {
+            Value::Int(Some(samples)) => samples,
+            Value::Int(None) => return Err(EvalError::UnexpectedNone),
+            _ => unreachable!("type should have been checked when compiling"),
+        };
+        if !(2..=MAX_LOCUS_SAMPLES).contains(&samples) {
+            return Err(EvalError::LocusSamples(samples));
+        }
+
+        let mut vars = vars.clone();
+        let mut pts = Vec::new();
+        for i in 0..samples {
+            let t = self.start + (self.end - self.start) * i as f64 / (samples - 1) as f64;
+            vars.insert(self.param.clone(), t.into());
+
+            match self.body.eval(&vars) {
+                Ok(Value::Pt(Some(pt))) => pts.push(pt),
+                // Traced point may be undefined at some positions, e.g. for parallel lines
+                Ok(Value::Pt(None)) | Err(EvalError::UnexpectedNone) => {}
+                Ok(_) => unreachable!("type should have been checked when compiling"),
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(Polyline::new(pts).into())
+    }
+}
+
 #[cfg(test)]
 pub(crate) fn eval(expr: &str) -> Value {
     use crate::{
@@ -113,7 +149,7 @@ mod test {
     #[test]
     fn func_call() {
         assert_eq!(eval("1 + 1"), 2.into());
-        assert_eq!(eval("pt 1.0 2.0"), Pt { x: 1.0, y: 2.0 }.into());
+        assert_eq!(eval("pt 1.0 2.0"), Pt::new(1.0, 2.0).into());
     }
 
     #[test]
@@ -0,0 +1,332 @@
+//! Loci, i.e. paths of points, that are traced while other points move along their objects.
+//!
+//! `locus Q P [samples]` is compiled from the definitions of the items:
+//! the moving point `P` should be defined as `pt_on <object> <param>` with a free parameter,
+//! and the chain of definitions from the parameter to the traced point `Q` is inlined
+//! into a single expression. It is evaluated at evenly spaced parameter values,
+//! so the items themselves are never changed.
+
+use std::{
+    collections::{HashMap, HashSet},
+    f64::consts::PI,
+};
+
+use types::{
+    core::{Ident, ValueType},
+    lang::Expr,
+};
+
+use crate::{
+    cexpr::{
+        compile::{CError, CScope, Compile},
+        CExpr, CExprInner, CExprInnerKind, FuncCallCExpr, IfCExpr, IfCExprCase, LocusCExpr,
+    },
+    node::{CExprNode, Node, NodeInnerKind},
+};
+
+pub(super) const LOCUS: &str = "locus";
+
+const DEFAULT_SAMPLES: i64 = 100;
+
+/// Limits the memory used by a single locus.
+pub(super) const MAX_LOCUS_SAMPLES: i64 = 100_000;
+
+pub(super) fn compile(args: Vec<Expr>, cscope: &CScope) -> Result<CExpr, CError> {
+    let exec_scope = cscope.exec_scope();
+
+    let (traced, moving, samples) = match <[Expr; 2]>::try_from(args) {
+        Ok([Expr::Variable(traced), Expr::Variable(moving)]) => (traced, moving, None),
+        Ok(_) => return Err(CError::LocusArgs),
+        Err(args) => match <[Expr; 3]>::try_from(args) {
+            Ok([Expr::Variable(traced), Expr::Variable(moving), samples]) => {
+                (traced, moving, Some(samples))
+            }
+            _ => return Err(CError::LocusArgs),
+        },
+    };
+
+    let samples = samples
+        .unwrap_or(Expr::Value(DEFAULT_SAMPLES.into()))
+        .compile(cscope)?;
+    if samples.value_type() != ValueType::Int {
+        return Err(CError::LocusArgs);
+    }
+
+    let traced_node = exec_scope
+        .get_node(&traced)
+        .ok_or_else(|| CError::UndefinedVariable(traced.clone()))?;
+    if traced_node.value_type() != ValueType::Pt {
+        return Err(CError::LocusArgs);
+    }
+
+    let moving_node = exec_scope
+        .get_node(&moving)
+        .ok_or_else(|| CError::UndefinedVariable(moving.clone()))?;
+    let Some((object, param)) = exec_scope.pt_on_parts(&moving_node) else {
+        return Err(CError::LocusNotPtOn(moving));
+    };
+
+    // Lines are traced between their defining points, circles are traced once
+    let (start, end) = match object.value_type() {
+        ValueType::Line => (0.0, 1.0),
+        _ => (0.0, 2.0 * PI),
+    };
+
+    let param = param.clone();
+    let param_node = exec_scope
+        .get_node(&param)
+        .expect("parameter of pt_on should be defined");
+
+    let Some(body) = inline(&traced_node, &param_node, &param, &mut HashMap::new()) else {
+        return Err(CError::LocusIndependent { traced, moving });
+    };
+
+    // Traced and moving points are required, so that removing them removes the locus
+    let required_vars = body
+        .required_vars()
+        .iter()
+        .filter(|var| **var != param)
+        .chain(samples.required_vars())
+        .cloned()
+        .chain([traced, moving])
+        .collect();
+
+    Ok(CExpr::from(CExprInner {
+        required_vars,
+        value_type: ValueType::Polyline,
+        kind: CExprInnerKind::Locus(LocusCExpr {
+            body,
+            param,
+            start,
+            end,
+            samples,
+        }),
+    }))
+}
+
+/// Returns the node's value as an expression of the parameter
+/// or `None` if it doesn't depend on the parameter.
+///
+/// Results are cached by node addresses, as the same node may be required many times.
+fn inline(
+    node: &Node,
+    param_node: &Node,
+    param: &Ident,
+    cache: &mut HashMap<usize, Option<CExpr>>,
+) -> Option<CExpr> {
+    if node == param_node {
+        return Some(CExpr::from(CExprInner {
+            required_vars: HashSet::from([param.clone()]),
+            value_type: ValueType::Real,
+            kind: CExprInnerKind::Variable(param.clone()),
+        }));
+    }
+
+    if let Some(cexpr) = cache.get(&node.address()) {
+        return cexpr.clone();
+    }
+
+    let cexpr = match &node.inner().kind {
+        NodeInnerKind::Value(_) => None,
+        NodeInnerKind::CExpr(CExprNode { body, bindings, .. }) => {
+            let subs: HashMap<_, _> = bindings
+                .iter()
+                .filter_map(|(name, node)| {
+                    Some((name.clone(), inline(node, param_node, param, cache)?))
+                })
+                .collect();
+
+            (!subs.is_empty()).then(|| substitute(body, &subs))
+        }
+    };
+
+    cache.insert(node.address(), cexpr.clone());
+    cexpr
+}
+
+/// Replaces variables with expressions.
+fn substitute(cexpr: &CExpr, subs: &HashMap<Ident, CExpr>) -> CExpr {
+    if !cexpr
+        .required_vars()
+        .iter()
+        .any(|var| subs.contains_key(var))
+    {
+        return cexpr.clone();
+    }
+
+    let kind = match &cexpr.inner().kind {
+        CExprInnerKind::Variable(var) => return subs[var].clone(),
+        CExprInnerKind::Value(_) => unreachable!("values don't require variables"),
+        CExprInnerKind::FuncCall(FuncCallCExpr { func, args }) => {
+            CExprInnerKind::FuncCall(FuncCallCExpr {
+                func: func.clone(),
+                args: args.iter().map(|arg| substitute(arg, subs)).collect(),
+            })
+        }
+        CExprInnerKind::If(IfCExpr {
+            cases,
+            default_case_value,
+        }) => CExprInnerKind::If(IfCExpr {
+            cases: cases
+                .iter()
+                .map(|case| IfCExprCase {
+                    cond: substitute(&case.cond, subs),
+                    value: substitute(&case.value, subs),
+                })
+                .collect(),
+            default_case_value: default_case_value
+                .as_ref()
+                .map(|value| substitute(value, subs)),
+        }),
+        CExprInnerKind::Locus(locus) => {
+            // Nested locus binds it's own parameter
+            let mut body_subs = subs.clone();
+            body_subs.remove(&locus.param);
+
+            CExprInnerKind::Locus(LocusCExpr {
+                body: substitute(&locus.body, &body_subs),
+                samples: substitute(&locus.samples, subs),
+                ..locus.clone()
+            })
+        }
+    };
+
+    let required_vars = cexpr
+        .required_vars()
+        .iter()
+        .flat_map(|var| match subs.get(var) {
+            Some(sub) => sub.required_vars().clone(),
+            None => HashSet::from([var.clone()]),
+        })
+        .collect();
+
+    CExpr::from(CExprInner {
+        required_vars,
+        value_type: cexpr.value_type(),
+        kind,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use types::core::{Polyline, Pt, Value};
+
+    use crate::{
+        cexpr::eval::EvalError,
+        exec::{Exec, ExecError, ExecScope},
+    };
+
+    use super::*;
+
+    fn scope() -> ExecScope {
+        let mut scope = ExecScope::new();
+        parser::definitions(
+            r#"
+            t = 0.0
+            s = 2.0
+            c = circ (pt 0.0 0.0) 1.0
+            P = pt_on c t
+            Q = pt (s * (x P)) (y P)
+            l = line (pt 0.0 0.0) (pt 1.0 0.0)
+            R = pt_on l t
+            A = pt 1.0 1.0
+            "#,
+        )
+        .unwrap()
+        .exec(&mut scope)
+        .unwrap();
+        scope
+    }
+
+    fn exec(scope: &mut ExecScope, src: &str) -> Result<(), ExecError> {
+        parser::definitions(src).unwrap().exec(scope).map(|_| ())
+    }
+
+    fn polyline(scope: &ExecScope, name: &str) -> Polyline {
+        match scope.get_item(&Ident::from(name)) {
+            Some(Value::Polyline(Some(polyline))) => polyline,
+            value => panic!("expected polyline, got {value:?}"),
+        }
+    }
+
+    #[test]
+    fn ellipse() {
+        let mut scope = scope();
+        exec(&mut scope, "L = locus Q P 5").unwrap();
+
+        let pts = polyline(&scope, "L").pts;
+        assert_eq!(pts.len(), 5);
+        let expected = [(2.0, 0.0), (0.0, 1.0), (-2.0, 0.0), (0.0, -1.0), (2.0, 0.0)];
+        for (pt, (x, y)) in pts.iter().zip(expected) {
+            assert!(pt.dist(Pt::new(x, y)) < 1e-9, "{pt:?} != ({x}, {y})");
+        }
+
+        // Other free inputs update the locus
+        scope.set(&Ident::from("s"), Value::from(3.0)).unwrap();
+        assert!(polyline(&scope, "L").pts[0].dist(Pt::new(3.0, 0.0)) < 1e-9);
+
+        // Moving point doesn't change the locus and is not changed by it
+        scope.set(&Ident::from("t"), Value::from(1.0)).unwrap();
+        assert!(polyline(&scope, "L").pts[0].dist(Pt::new(3.0, 0.0)) < 1e-9);
+        assert_eq!(scope.get_item(&Ident::from("t")), Some(Value::from(1.0)));
+
+        // Locus is removed with it's points
+        scope.rm(Ident::from("Q")).unwrap();
+        assert_eq!(scope.get_item(&Ident::from("L")), None);
+    }
+
+    #[test]
+    fn line() {
+        let mut scope = scope();
+        exec(&mut scope, "L = locus R R 3").unwrap();
+        assert_eq!(
+            polyline(&scope, "L").pts,
+            vec![Pt::new(0.0, 0.0), Pt::new(0.5, 0.0), Pt::new(1.0, 0.0)]
+        );
+
+        exec(&mut scope, "M = locus R R").unwrap();
+        assert_eq!(polyline(&scope, "M").pts.len(), DEFAULT_SAMPLES as usize);
+    }
+
+    #[test]
+    fn errors() {
+        let mut scope = scope();
+        assert!(matches!(
+            exec(&mut scope, "L = locus Q t"),
+            Err(ExecError::CompileError(CError::LocusNotPtOn(_)))
+        ));
+        assert!(matches!(
+            exec(&mut scope, "L = locus Q Q"),
+            Err(ExecError::CompileError(CError::LocusNotPtOn(_)))
+        ));
+        assert!(matches!(
+            exec(&mut scope, "L = locus (pt 1.0 1.0) P"),
+            Err(ExecError::CompileError(CError::LocusArgs))
+        ));
+        assert!(matches!(
+            exec(&mut scope, "L = locus s P"),
+            Err(ExecError::CompileError(CError::LocusArgs))
+        ));
+        assert!(matches!(
+            exec(&mut scope, "L = locus A P"),
+            Err(ExecError::CompileError(CError::LocusIndependent { .. }))
+        ));
+        for samples in [1, MAX_LOCUS_SAMPLES + 1, i64::MAX] {
+            assert!(matches!(
+                exec(&mut scope, &format!("L = locus Q P {samples}")),
+                Err(ExecError::EvalError(EvalError::LocusSamples(n))) if n == samples
+            ));
+        }
+        exec(&mut scope, &format!("L = locus Q P {MAX_LOCUS_SAMPLES}")).unwrap();
+    }
+
+    #[test]
+    fn json() {
+        let mut scope = scope();
+        exec(&mut scope, "L = locus Q P 5").unwrap();
+
+        let mut scope = ExecScope::from_json(&scope.to_json()).unwrap();
+        scope.set(&Ident::from("s"), Value::from(3.0)).unwrap();
+        assert!(polyline(&scope, "L").pts[0].dist(Pt::new(3.0, 0.0)) < 1e-9);
+    }
+}
@@ -9,6 +9,7 @@ use crate::function::Function;
 
 pub mod compile;
 pub mod eval;
+mod locus;
 
 /// Compiled Expr
 #[derive(Clone, Debug)]
@@ -55,6 +56,7 @@ pub(crate) enum CExprInnerKind {
     Variable(Ident),
     FuncCall(FuncCallCExpr),
     If(IfCExpr),
+    Locus(LocusCExpr),
 }
 
 #[derive(Clone, Debug)]
@@ -74,3 +76,20 @@ pub(crate) struct IfCExprCase {
     pub(crate) cond: CExpr,
     pub(crate) value: CExpr,
 }
+
+/// Path of a point, that is traced while another point moves along it's object.
+#[derive(Clone, Debug)]
+pub(crate) struct LocusCExpr {
+    /// The traced point as an expression of [Self::param]
+    pub(crate) body: CExpr,
+
+    /// Parameter of the moving point, that is not bound by the locus' bindings
+    pub(crate) param: Ident,
+
+    /// The parameter goes from `start` to `end`
+    pub(crate) start: f64,
+    pub(crate) end: f64,
+
+    /// Number of parameter values
+    pub(crate) samples: CExpr,
+}
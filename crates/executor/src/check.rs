@@ -0,0 +1,305 @@
+//! This module implements checking statements numerically.
+//!
+//! A bool expression is evaluated, while free items, that it depends on,
+//! are randomly moved; it's dependencies are updated as by `set!`.
+
+use std::collections::{HashMap, HashSet};
+
+use types::{
+    check::{CheckOptions, CheckReport},
+    core::{Circ, Ident, Line, Pt, Value, ValueType},
+    lang::Expr,
+};
+
+use crate::{
+    cexpr::CExpr,
+    compile::{CScope, Compile},
+    eval::Eval,
+    exec::{ExecError, ExecScope},
+    node::{Node, NodeInnerKind},
+};
+
+impl ExecScope {
+    /// Evaluates the bool expression under random perturbations of free real,
+    /// pt, line and circ items, that it depends on.
+    ///
+    /// Comparisons use `options.eps` during the trials, if it is given.
+    /// The scope is restored afterwards.
+    pub fn check(&self, expr: Expr, options: &CheckOptions) -> Result<CheckReport, ExecError> {
+        let cexpr = expr.compile(&CScope::new(self))?;
+        if cexpr.value_type() != ValueType::Bool {
+            return Err(ExecError::UnexpectedType {
+                expected: ValueType::Bool,
+                got: cexpr.value_type(),
+            });
+        }
+
+        let Some(eps) = options.eps else {
+            return self.run_trials(&cexpr, options);
+        };
+        if !(eps.is_finite() && eps >= 0.0) {
+            return Err(ExecError::BadTolerance(eps));
+        }
+
+        // Items, that do not depend on the perturbed ones, are evaluated with the eps too
+        let scope_eps = self.tolerance.replace(eps);
+        let report = Node::reevaluate_all(self.nodes.values().cloned())
+            .map_err(ExecError::from)
+            .and_then(|()| self.run_trials(&cexpr, options));
+        self.tolerance.replace(scope_eps);
+        Node::reevaluate_all(self.nodes.values().cloned())?;
+
+        report
+    }
+
+    fn run_trials(&self, cexpr: &CExpr, options: &CheckOptions) -> Result<CheckReport, ExecError> {
+        let free = self.free_items_of(cexpr.required_vars());
+        let originals: Vec<_> = free.iter().map(|(_name, node)| node.get_value()).collect();
+        let scale = originals.iter().map(magnitude).fold(1.0, f64::max) * options.spread;
+
+        let mut rng = match options.seed {
+            Some(seed) => fastrand::Rng::with_seed(seed),
+            None => fastrand::Rng::new(),
+        };
+
+        let mut report = CheckReport {
+            passed: 0,
+            failed: 0,
+            skipped: 0,
+            counterexample: None,
+        };
+
+        for _ in 0..options.trials {
+            let mut perturbed = Vec::new();
+            let mut updated = true;
+            for ((name, node), value) in free.iter().zip(&originals) {
+                let value = perturb(value, scale, &mut rng);
+                updated &= node.set(value.clone()).is_ok();
+                perturbed.push((name.clone(), value));
+            }
+
+            let vars = cexpr
+                .required_vars()
+                .iter()
+                .filter_map(|var| Some((var.clone(), self.get_node(var)?.get_value())))
+                .collect();
+
+            match cexpr.eval(&vars) {
+                Ok(Value::Bool(Some(true))) if updated => report.passed += 1,
+                Ok(Value::Bool(Some(false))) if updated => {
+                    report.failed += 1;
+                    report.counterexample.get_or_insert(perturbed);
+                }
+                _ => report.skipped += 1,
+            }
+        }
+
+        for ((_name, node), value) in free.iter().zip(originals) {
+            node.set(value)?;
+        }
+
+        Ok(report)
+    }
+
+    /// Free items, that the variables depend on, sorted by name.
+    fn free_items_of(&self, vars: &HashSet<Ident>) -> Vec<(Ident, Node)> {
+        #[allow(clippy::mutable_key_type)]
+        fn inner(visited: &mut HashSet<Node>, node: Node) {
+            if !visited.insert(node.clone()) {
+                return;
+            }
+            if let NodeInnerKind::CExpr(cexpr_node) = &node.inner().kind {
+                for (_name, binding) in &cexpr_node.bindings {
+                    inner(visited, binding.clone());
+                }
+            }
+        }
+
+        #[allow(clippy::mutable_key_type)]
+        let mut visited = HashSet::new();
+        for var in vars {
+            if let Some(node) = self.get_node(var) {
+                inner(&mut visited, node);
+            }
+        }
+
+        #[allow(clippy::mutable_key_type)]
+        let names: HashMap<_, _> = self
+            .nodes
+            .iter()
+            .map(|(name, node)| (node.clone(), name.clone()))
+            .collect();
+
+        let mut free: Vec<_> = visited
+            .into_iter()
+            .filter(|node| matches!(node.inner().kind, NodeInnerKind::Value(_)))
+            .filter(|node| {
+                matches!(
+                    node.value_type(),
+                    ValueType::Real | ValueType::Pt | ValueType::Line | ValueType::Circ
+                )
+            })
+            .filter_map(|node| Some((names.get(&node)?.clone(), node)))
+            .collect();
+        free.sort_by(|(a, _), (b, _)| a.0.cmp(&b.0));
+        free
+    }
+}
+
+/// The largest absolute coordinate of the value.
+fn magnitude(value: &Value) -> f64 {
+    let coords = match value {
+        Value::Real(Some(v)) => vec![*v],
+        Value::Pt(Some(Pt { x, y })) => vec![*x, *y],
+        Value::Line(Some(Line { p1, p2 })) => vec![p1.x, p1.y, p2.x, p2.y],
+        Value::Circ(Some(Circ { o, r })) => vec![o.x, o.y, *r],
+        _ => vec![],
+    };
+    coords
+        .into_iter()
+        .map(|v| v.to_f64().abs())
+        .fold(0.0, f64::max)
+}
+
+/// Moves every coordinate by up to `scale`; radii are scaled up to twice.
+fn perturb(value: &Value, scale: f64, rng: &mut fastrand::Rng) -> Value {
+    let mut shift = || scale * (2.0 * rng.f64() - 1.0);
+    let mut shift_pt = |Pt { x, y }: Pt| Pt::new(x.to_f64() + shift(), y.to_f64() + shift());
+
+    match value {
+        Value::Real(Some(v)) => Value::from(v.to_f64() + shift()),
+        Value::Pt(Some(pt)) => Value::from(shift_pt(*pt)),
+        Value::Line(Some(Line { p1, p2 })) => Value::from(Line {
+            p1: shift_pt(*p1),
+            p2: shift_pt(*p2),
+        }),
+        Value::Circ(Some(Circ { o, r })) => {
+            let o = shift_pt(*o);
+            Value::from(Circ {
+                o,
+                r: (r.to_f64() * 2f64.powf(2.0 * rng.f64() - 1.0)).into(),
+            })
+        }
+        value => value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::exec::Exec;
+
+    use super::*;
+
+    fn scope() -> ExecScope {
+        let mut scope = ExecScope::new();
+        parser::definitions(
+            r#"
+            A = pt 0.0 0.0
+            B = pt 4.0 0.0
+            C = pt 1.0 3.0
+            M = (A + B) / 2.0
+            k = 2.0
+            n = 3
+            "#,
+        )
+        .unwrap()
+        .exec(&mut scope)
+        .unwrap();
+        scope
+    }
+
+    fn options() -> CheckOptions {
+        CheckOptions {
+            seed: Some(1),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn holds() {
+        let scope = scope();
+
+        // Strict inequalities are not affected by rounding
+        let report = scope
+            .check(parser::expr("M.x > A.x - 1.0").unwrap(), &options())
+            .unwrap();
+        assert_eq!(report.passed, 100);
+        assert!(report.holds(0.0));
+        assert_eq!(report.counterexample, None);
+
+        // The scope is restored
+        assert_eq!(
+            scope.get_item(&Ident::from("M")),
+            Some(Value::from(Pt::new(2.0, 0.0)))
+        );
+    }
+
+    #[test]
+    fn counterexample() {
+        let scope = scope();
+
+        let report = scope
+            .check(parser::expr("C.y > 3.0").unwrap(), &options())
+            .unwrap();
+        assert!(report.failed > 0);
+        assert!(!report.holds(0.0));
+
+        // Only items, that the expression depends on, are perturbed
+        let counterexample = report.counterexample.unwrap();
+        assert_eq!(counterexample.len(), 1);
+        assert_eq!(counterexample[0].0, Ident::from("C"));
+        let Value::Pt(Some(c)) = counterexample[0].1 else {
+            panic!("C should be a pt");
+        };
+        assert!(c.y.to_f64() <= 3.0);
+
+        // Ints are not perturbed
+        let report = scope
+            .check(parser::expr("n == 3 & k > 0.0").unwrap(), &options())
+            .unwrap();
+        assert!(report.holds(0.0));
+    }
+
+    #[test]
+    fn eps() {
+        let mut scope = scope();
+        parser::definitions("same = (n as real) == (n as real) + 0.001")
+            .unwrap()
+            .exec(&mut scope)
+            .unwrap();
+
+        // Items, that are not perturbed, are compared with the eps too
+        let expr = parser::expr("same & C.y == C.y + 0.001").unwrap();
+        let report = scope.check(expr.clone(), &options()).unwrap();
+        assert_eq!(report.failed, 100);
+
+        let options = CheckOptions {
+            eps: Some(0.01),
+            ..options()
+        };
+        let report = scope.check(expr, &options).unwrap();
+        assert_eq!(report.passed, 100);
+
+        // The scope's tolerance is restored
+        assert_eq!(scope.get_tolerance(), crate::tolerance::DEFAULT_TOLERANCE);
+        assert_eq!(scope.get_item(&Ident::from("same")), Some(false.into()));
+
+        let options = CheckOptions {
+            eps: Some(-1.0),
+            ..options
+        };
+        assert!(matches!(
+            scope.check(parser::expr("same").unwrap(), &options),
+            Err(ExecError::BadTolerance(_))
+        ));
+    }
+
+    #[test]
+    fn not_bool() {
+        let scope = scope();
+        assert!(matches!(
+            scope.check(parser::expr("M").unwrap(), &options()),
+            Err(ExecError::UnexpectedType { .. })
+        ));
+    }
+}
@@ -0,0 +1,708 @@
+//! Imports primitives of vector drawings (svg, dxf) as items.
+//!
+//! Every primitive becomes a value definition with a generated name,
+//! so the drawing can be built on, but does not depend on anything.
+//!
+//! Transformations (e.g. svg's `transform`) are not supported.
+
+use std::collections::HashSet;
+
+use svg::{
+    node::element::tag::{self, Type},
+    parser::Event,
+};
+use thiserror::Error;
+use types::{
+    core::{Circ, Ident, Line, Pt, Value, ValueType},
+    export::{DrawingFormat, ImportOptions},
+    lang::{Expr, ValueDefinition},
+};
+
+use crate::{
+    exec::{Exec, ExecResult, ExecScope},
+    svg::{PT_CLASS, SCALE_ATTR, Y_AXIS_ATTR},
+};
+
+#[derive(Debug, Error)]
+pub enum DrawingError {
+    #[error("failed to parse svg: {0}")]
+    Svg(String),
+
+    #[error("bad attribute {attr}=\"{value}\" of <{tag}>")]
+    SvgAttribute {
+        tag: String,
+        attr: String,
+        value: String,
+    },
+
+    #[error("failed to parse dxf at line {line}: {msg}")]
+    Dxf { line: usize, msg: String },
+}
+
+/// Coordinates of a drawing with it's own scale and y axis.
+#[derive(Debug, Default)]
+struct Primitives {
+    values: Vec<Value>,
+
+    /// Scale, that was used to export the drawing.
+    scale: Option<f64>,
+
+    /// Whether the drawing is flipped, if the options do not say otherwise.
+    flip_y: bool,
+}
+
+impl ExecScope {
+    /// Defines items for the drawing's primitives.
+    ///
+    /// Returns names of the new items in order of the primitives.
+    pub fn import_drawing(
+        &mut self,
+        format: DrawingFormat,
+        content: &str,
+        options: &ImportOptions,
+    ) -> ExecResult<Vec<Ident>> {
+        let primitives = match format {
+            DrawingFormat::Svg => parse_svg(content)?,
+            DrawingFormat::Dxf => parse_dxf(content)?,
+        };
+
+        let scale = options.scale.or(primitives.scale).unwrap_or(1.0);
+        let flip_y = options.flip_y.unwrap_or(primitives.flip_y);
+        let to_plane = |Pt { x, y }: Pt| {
+            let (x, y) = (x.to_f64(), y.to_f64());
+            Pt::new(x / scale, if flip_y { -y } else { y } / scale)
+        };
+
+        let mut taken: HashSet<Ident> = self
+            .nodes
+            .keys()
+            .cloned()
+            .chain(self.layers.iter().map(|layer| layer.name.clone()))
+            .collect();
+
+        let mut names = Vec::new();
+        for value in primitives.values {
+            let value = match value {
+                Value::Pt(Some(pt)) => to_plane(pt).into(),
+                Value::Line(Some(Line { p1, p2 })) => Line {
+                    p1: to_plane(p1),
+                    p2: to_plane(p2),
+                }
+                .into(),
+                Value::Circ(Some(Circ { o, r })) => Circ {
+                    o: to_plane(o),
+                    r: (r.to_f64() / scale).into(),
+                }
+                .into(),
+                value => value,
+            };
+
+            let value_type = value.value_type();
+            let name = new_item_name(&value_type, &taken);
+            taken.insert(name.clone());
+
+            ValueDefinition {
+                name: name.clone(),
+                value_type: Some(value_type),
+                body: Expr::Value(value),
+            }
+            .exec(self)?;
+
+            names.push(name);
+        }
+
+        Ok(names)
+    }
+}
+
+/// Names are picked as in the GUI: `A`..`Z`, `p1`, ... for points,
+/// `a`..`z`, `l1`, ... for lines and `a`..`z`, `c1`, ... for circles.
+fn new_item_name(value_type: &ValueType, taken: &HashSet<Ident>) -> Ident {
+    let (letters, prefix) = match value_type {
+        ValueType::Pt => ('A'..='Z', "p"),
+        ValueType::Line => ('a'..='z', "l"),
+        _ => ('a'..='z', "c"),
+    };
+
+    letters
+        .map(|c| Ident(c.to_string()))
+        .chain((1..).map(|n| Ident(format!("{prefix}{n}"))))
+        .find(|name| !taken.contains(name))
+        .expect("there are infinitely many names")
+}
+
+fn parse_svg(content: &str) -> Result<Primitives, DrawingError> {
+    let mut primitives = Primitives {
+        flip_y: true,
+        ..Default::default()
+    };
+
+    for event in svg::read(content).map_err(|err| DrawingError::Svg(err.to_string()))? {
+        let (name, attrs) = match event {
+            Event::Error(err) => return Err(DrawingError::Svg(err.to_string())),
+            Event::Tag(_, Type::End, _) => continue,
+            Event::Tag(name, _, attrs) => (name, attrs),
+            _ => continue,
+        };
+
+        let attr = |attr: &str| -> Result<f64, DrawingError> {
+            let Some(value) = attrs.get(attr) else {
+                // Missing coordinates are zero in svg
+                return Ok(0.0);
+            };
+            value
+                .trim()
+                .trim_end_matches("px")
+                .parse()
+                .ok()
+                .filter(|x: &f64| x.is_finite())
+                .ok_or_else(|| DrawingError::SvgAttribute {
+                    tag: name.to_string(),
+                    attr: attr.to_string(),
+                    value: value.to_string(),
+                })
+        };
+        let is_pt = attrs
+            .get("class")
+            .is_some_and(|class| class.split_whitespace().any(|class| class == PT_CLASS));
+
+        match name {
+            tag::SVG => {
+                if attrs.contains_key(SCALE_ATTR) {
+                    primitives.scale = Some(attr(SCALE_ATTR)?).filter(|scale| *scale > 0.0);
+                }
+                if attrs
+                    .get(Y_AXIS_ATTR)
+                    .is_some_and(|y_axis| &**y_axis == "down")
+                {
+                    primitives.flip_y = false;
+                }
+            }
+
+            tag::Line => {
+                let p1 = Pt::new(attr("x1")?, attr("y1")?);
+                let p2 = Pt::new(attr("x2")?, attr("y2")?);
+
+                // Points are exported as zero-length lines
+                primitives.values.push(if is_pt || p1 == p2 {
+                    p1.into()
+                } else {
+                    Line { p1, p2 }.into()
+                });
+            }
+
+            tag::Circle => {
+                let o = Pt::new(attr("cx")?, attr("cy")?);
+                let r = attr("r")?;
+
+                if is_pt {
+                    primitives.values.push(o.into());
+                } else if r > 0.0 {
+                    primitives.values.push(Circ::new(o, r).into());
+                }
+            }
+
+            tag::Polyline | tag::Polygon => {
+                let points = attrs.get("points").map_or("", |points| points);
+                let coords = points
+                    .split(|c: char| c == ',' || c.is_whitespace())
+                    .filter(|coord| !coord.is_empty())
+                    .map(|coord| coord.parse().ok().filter(|x: &f64| x.is_finite()))
+                    .collect::<Option<Vec<f64>>>()
+                    .filter(|coords| coords.len() % 2 == 0)
+                    .ok_or_else(|| DrawingError::SvgAttribute {
+                        tag: name.to_string(),
+                        attr: "points".to_string(),
+                        value: points.to_string(),
+                    })?;
+
+                let mut points: Vec<_> = coords.chunks(2).map(|xy| Pt::new(xy[0], xy[1])).collect();
+                if name == tag::Polygon {
+                    points.extend(points.first().copied());
+                }
+
+                push_polyline(&mut primitives.values, &points);
+            }
+
+            _ => {}
+        }
+    }
+
+    Ok(primitives)
+}
+
+/// Every segment becomes a line.
+fn push_polyline(values: &mut Vec<Value>, points: &[Pt]) {
+    values.extend(
+        points
+            .windows(2)
+            .filter(|segment| segment[0] != segment[1])
+            .map(|segment| {
+                Line {
+                    p1: segment[0],
+                    p2: segment[1],
+                }
+                .into()
+            }),
+    );
+}
+
+/// Parses entities of an ascii dxf.
+///
+/// Supported entities are `POINT`, `LINE`, `CIRCLE`, `LWPOLYLINE` and `POLYLINE`.
+fn parse_dxf(content: &str) -> Result<Primitives, DrawingError> {
+    // A dxf is a list of (group code, value) pairs, each on two lines
+    let lines: Vec<_> = content.lines().map(str::trim).collect();
+    if lines.len() % 2 != 0 {
+        return Err(DrawingError::Dxf {
+            line: lines.len(),
+            msg: "odd number of lines".to_string(),
+        });
+    }
+
+    let mut pairs = Vec::new();
+    for (idx, pair) in lines.chunks(2).enumerate() {
+        let code: i32 = pair[0].parse().map_err(|_| DrawingError::Dxf {
+            line: 2 * idx + 1,
+            msg: format!("bad group code '{}'", pair[0]),
+        })?;
+        pairs.push(DxfPair {
+            line: 2 * idx + 1,
+            code,
+            value: pair[1],
+        });
+    }
+
+    // Entities of blocks are not drawn by themselves, so only the ENTITIES section is read
+    let start = pairs
+        .windows(2)
+        .position(|pairs| {
+            (pairs[0].code, pairs[0].value) == (0, "SECTION")
+                && (pairs[1].code, pairs[1].value) == (2, "ENTITIES")
+        })
+        .ok_or(DrawingError::Dxf {
+            line: lines.len(),
+            msg: "no ENTITIES section".to_string(),
+        })?;
+    let entities = &pairs[start + 2..];
+    let end = entities
+        .iter()
+        .position(|pair| (pair.code, pair.value) == (0, "ENDSEC"))
+        .unwrap_or(entities.len());
+
+    // Entities start with group 0
+    let mut entities: Vec<&[DxfPair]> = Vec::new();
+    let mut rest = &pairs[start + 2..start + 2 + end];
+    while let Some(first) = rest.first() {
+        let len = 1 + rest[1..]
+            .iter()
+            .position(|pair| pair.code == 0)
+            .unwrap_or(rest.len() - 1);
+        if first.code == 0 {
+            entities.push(&rest[..len]);
+        }
+        rest = &rest[len..];
+    }
+
+    let mut primitives = Primitives::default();
+    let mut polyline: Option<(Vec<Pt>, bool)> = None;
+
+    for entity in entities {
+        let kind = entity[0].value;
+        let get = |code: i32| -> Result<f64, DrawingError> {
+            let Some(pair) = entity.iter().find(|pair| pair.code == code) else {
+                return Ok(0.0);
+            };
+            pair.number()
+        };
+        let flags = || -> Result<i64, DrawingError> { Ok(get(70)? as i64) };
+
+        match kind {
+            "POINT" => primitives.values.push(Pt::new(get(10)?, get(20)?).into()),
+
+            "LINE" => {
+                let p1 = Pt::new(get(10)?, get(20)?);
+                let p2 = Pt::new(get(11)?, get(21)?);
+                if p1 != p2 {
+                    primitives.values.push(Line { p1, p2 }.into());
+                }
+            }
+
+            "CIRCLE" => {
+                let r = get(40)?;
+                if r > 0.0 {
+                    let o = Pt::new(get(10)?, get(20)?);
+                    primitives.values.push(Circ::new(o, r).into());
+                }
+            }
+
+            "LWPOLYLINE" => {
+                let xs = entity.iter().filter(|pair| pair.code == 10);
+                let ys = entity.iter().filter(|pair| pair.code == 20);
+                let mut points = xs
+                    .zip(ys)
+                    .map(|(x, y)| Ok(Pt::new(x.number()?, y.number()?)))
+                    .collect::<Result<Vec<_>, DrawingError>>()?;
+
+                // Bit 1 means a closed polyline
+                if flags()? & 1 != 0 {
+                    points.extend(points.first().copied());
+                }
+                push_polyline(&mut primitives.values, &points);
+            }
+
+            // Old style polylines are followed by their vertices
+            "POLYLINE" => polyline = Some((Vec::new(), flags()? & 1 != 0)),
+            "VERTEX" => {
+                if let Some((points, _closed)) = &mut polyline {
+                    points.push(Pt::new(get(10)?, get(20)?));
+                }
+            }
+            "SEQEND" => {
+                if let Some((mut points, closed)) = polyline.take() {
+                    if closed {
+                        points.extend(points.first().copied());
+                    }
+                    push_polyline(&mut primitives.values, &points);
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    Ok(primitives)
+}
+
+struct DxfPair<'a> {
+    /// Line of the group code, for errors.
+    line: usize,
+    code: i32,
+    value: &'a str,
+}
+
+impl DxfPair<'_> {
+    fn number(&self) -> Result<f64, DrawingError> {
+        self.value
+            .parse()
+            .ok()
+            .filter(|x: &f64| x.is_finite())
+            .ok_or_else(|| DrawingError::Dxf {
+                line: self.line + 1,
+                msg: format!("bad number '{}'", self.value),
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+    use types::export::ExportOptions;
+
+    use super::*;
+
+    fn pt(x: f64, y: f64) -> Pt {
+        Pt::new(x, y)
+    }
+
+    fn is_close(lhs: &Value, rhs: &Value) -> bool {
+        let close = |a: Pt, b: Pt| a.dist(b) < 1e-9;
+        match (lhs, rhs) {
+            (Value::Pt(Some(a)), Value::Pt(Some(b))) => close(*a, *b),
+            (Value::Line(Some(a)), Value::Line(Some(b))) => close(a.p1, b.p1) && close(a.p2, b.p2),
+            (Value::Circ(Some(a)), Value::Circ(Some(b))) => {
+                close(a.o, b.o) && (a.r.to_f64() - b.r.to_f64()).abs() < 1e-9
+            }
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn svg() {
+        let mut scope = ExecScope::new();
+        scope
+            .import_drawing(
+                DrawingFormat::Svg,
+                r#"<line x1="0" y1="0" x2="0" y2="0"/>"#,
+                &ImportOptions::default(),
+            )
+            .unwrap();
+
+        let names = scope
+            .import_drawing(
+                DrawingFormat::Svg,
+                indoc! {r#"
+                    <svg xmlns="http://www.w3.org/2000/svg">
+                      <g>
+                        <line x1="0" y1="0" x2="10px" y2="20"/>
+                        <circle cx="1" cy="2" r="3"/>
+                        <circle class="pt" cx="4" cy="5" r="1"/>
+                        <polyline points="0,0 1,1, 2 0"/>
+                        <text x="1" y="1">ignored</text>
+                      </g>
+                    </svg>
+                "#},
+                &ImportOptions::default(),
+            )
+            .unwrap();
+
+        // Names, that are taken, are skipped
+        assert_eq!(names, ["a", "b", "B", "c", "d"].map(Ident::from).to_vec());
+
+        let items = scope.get_all_items();
+        // Y axis is flipped by default
+        assert_eq!(
+            items[&Ident::from("a")],
+            Line {
+                p1: pt(0.0, 0.0),
+                p2: pt(10.0, -20.0)
+            }
+            .into()
+        );
+        assert_eq!(
+            items[&Ident::from("b")],
+            Circ {
+                o: pt(1.0, -2.0),
+                r: 3.0.into()
+            }
+            .into()
+        );
+        assert_eq!(items[&Ident::from("B")], pt(4.0, -5.0).into());
+        assert_eq!(
+            items[&Ident::from("d")],
+            Line {
+                p1: pt(1.0, -1.0),
+                p2: pt(2.0, 0.0)
+            }
+            .into()
+        );
+
+        let mut options = ImportOptions::default();
+        options.apply("scale: 10; y-axis: keep").unwrap();
+        let names = scope
+            .import_drawing(
+                DrawingFormat::Svg,
+                r#"<circle cx="10" cy="20" r="30"/>"#,
+                &options,
+            )
+            .unwrap();
+        assert_eq!(
+            scope.get_all_items()[&names[0]],
+            Circ {
+                o: pt(1.0, 2.0),
+                r: 3.0.into()
+            }
+            .into()
+        );
+
+        for svg in [
+            r#"<line x1="a"/>"#,
+            r#"<polyline points="1 2 3"/>"#,
+            "<line",
+        ] {
+            assert!(
+                ExecScope::new()
+                    .import_drawing(DrawingFormat::Svg, svg, &ImportOptions::default())
+                    .is_err(),
+                "{svg}"
+            );
+        }
+    }
+
+    /// Exported svg is imported back in the plane's coordinates.
+    #[test]
+    fn svg_round_trip() {
+        let mut scope = ExecScope::new();
+        parser::definitions(
+            r#"
+            A = pt 1.0 2.0
+            l = line A (pt 3.0 -1.0)
+            c = circ A 0.5
+            "#,
+        )
+        .unwrap()
+        .exec(&mut scope)
+        .unwrap();
+
+        for decls in [
+            "lines: segments",
+            "lines: segments; y-axis: down; width: 300",
+        ] {
+            let mut options = ExportOptions::default();
+            options.apply(decls).unwrap();
+            let svg = scope.to_svg(&options);
+
+            let mut imported = ExecScope::new();
+            imported
+                .import_drawing(DrawingFormat::Svg, &svg, &ImportOptions::default())
+                .unwrap();
+
+            let mut values: Vec<_> = imported.get_all_items().into_values().collect();
+            values.sort_by_key(|value| format!("{value:?}"));
+            let mut expected: Vec<_> = scope.get_all_items().into_values().collect();
+            expected.sort_by_key(|value| format!("{value:?}"));
+
+            assert_eq!(values.len(), expected.len(), "{decls}");
+            for (value, expected) in values.iter().zip(&expected) {
+                assert!(
+                    is_close(value, expected),
+                    "{decls}: {value:?} != {expected:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn dxf() {
+        let dxf = indoc! {"
+            0
+            SECTION
+            2
+            BLOCKS
+            0
+            LINE
+            10
+            5.0
+            20
+            5.0
+            11
+            6.0
+            21
+            6.0
+            0
+            ENDSEC
+            0
+            SECTION
+            2
+            ENTITIES
+            0
+            POINT
+            8
+            0
+            10
+            1.0
+            20
+            2.0
+            30
+            0.0
+            0
+            LINE
+            10
+            0.0
+            20
+            0.0
+            11
+            3.0
+            21
+            4.0
+            0
+            CIRCLE
+            10
+            1.0
+            20
+            1.0
+            40
+            2.5
+            0
+            LWPOLYLINE
+            90
+            3
+            70
+            1
+            10
+            0.0
+            20
+            0.0
+            10
+            1.0
+            20
+            0.0
+            10
+            1.0
+            20
+            1.0
+            0
+            POLYLINE
+            70
+            0
+            0
+            VERTEX
+            10
+            0.0
+            20
+            5.0
+            0
+            VERTEX
+            10
+            5.0
+            20
+            5.0
+            0
+            SEQEND
+            0
+            ENDSEC
+            0
+            EOF
+        "};
+
+        let mut scope = ExecScope::new();
+        let names = scope
+            .import_drawing(DrawingFormat::Dxf, dxf, &ImportOptions::default())
+            .unwrap();
+        assert_eq!(names.len(), 7);
+
+        let items = scope.get_all_items();
+        let values: Vec<_> = names.iter().map(|name| items[name].clone()).collect();
+        assert_eq!(
+            values,
+            [
+                pt(1.0, 2.0).into(),
+                Line {
+                    p1: pt(0.0, 0.0),
+                    p2: pt(3.0, 4.0)
+                }
+                .into(),
+                Circ {
+                    o: pt(1.0, 1.0),
+                    r: 2.5.into()
+                }
+                .into(),
+                Line {
+                    p1: pt(0.0, 0.0),
+                    p2: pt(1.0, 0.0)
+                }
+                .into(),
+                Line {
+                    p1: pt(1.0, 0.0),
+                    p2: pt(1.0, 1.0)
+                }
+                .into(),
+                Line {
+                    p1: pt(1.0, 1.0),
+                    p2: pt(0.0, 0.0)
+                }
+                .into(),
+                Line {
+                    p1: pt(0.0, 5.0),
+                    p2: pt(5.0, 5.0)
+                }
+                .into(),
+            ]
+            .to_vec()
+        );
+
+        for dxf in [
+            "0\nSECTION\n2\nBLOCKS\n",
+            "x\nLINE\n",
+            "0\nSECTION\n2\nENTITIES\n0\nPOINT\n10\nabc\n",
+            "0\n",
+        ] {
+            assert!(
+                ExecScope::new()
+                    .import_drawing(DrawingFormat::Dxf, dxf, &ImportOptions::default())
+                    .is_err(),
+                "{dxf}"
+            );
+        }
+    }
+}
@@ -0,0 +1,273 @@
+//! This module implements the scope-wide choice of the arithmetic of reals.
+//!
+//! In scopes with exact arithmetic real literals and bools and ints cast to reals
+//! are exact rationals, so that rational operations on them stay exact
+//! (see [types::core::Real]). Coordinates of points and radii of circles are reals
+//! too, so e.g. midpoints, projections and intersections of lines through exact
+//! points are exact as well.
+//!
+//! Literals are converted, when they are compiled, so the arithmetic can only be
+//! changed in an empty scope.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, OnceLock,
+    },
+};
+
+use types::{
+    core::{Ident, Rational, Real, Value, ValueType},
+    lang::FunctionSignature,
+};
+
+use crate::{
+    exec::{ExecError, ExecScope},
+    function::{FuncMap, Function, FunctionInner, FunctionInnerKind},
+};
+
+/// Whether the arithmetic is exact along with the casts to reals, that depend on it.
+#[derive(Debug)]
+pub(crate) struct Arithmetic {
+    /// It's shared with the functions.
+    exact: Arc<AtomicBool>,
+
+    funcs: FuncMap,
+}
+
+impl Default for Arithmetic {
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+impl Arithmetic {
+    pub(crate) fn new(exact: bool) -> Self {
+        let exact = Arc::new(AtomicBool::new(exact));
+
+        let mut funcs = HashMap::new();
+        insert_cast(&mut funcs, &exact, ValueType::Int, |v| match v {
+            Value::Int(v) => v.map(Rational::from),
+            _ => unreachable!("type should be as specified in signature"),
+        });
+        insert_cast(&mut funcs, &exact, ValueType::Bool, |v| match v {
+            Value::Bool(v) => v.map(|v| Rational::from(v as i64)),
+            _ => unreachable!("type should be as specified in signature"),
+        });
+
+        Self { exact, funcs }
+    }
+
+    pub(crate) fn is_exact(&self) -> bool {
+        self.exact.load(Ordering::Relaxed)
+    }
+
+    /// Converts a real to the scope's arithmetic.
+    pub(crate) fn convert(&self, value: Value) -> Value {
+        match value {
+            Value::Real(Some(v)) if !self.is_exact() => Value::Real(Some(v.to_float())),
+            v => v,
+        }
+    }
+
+    pub(crate) fn get_func(&self, sign: &FunctionSignature) -> Option<Function> {
+        self.funcs.get(sign).cloned()
+    }
+
+    pub(crate) fn list_funcs(&self) -> impl Iterator<Item = &FunctionSignature> {
+        self.funcs.keys()
+    }
+}
+
+/// Inserts `#as_real` from `arg_type`, that returns an exact real,
+/// if the arithmetic is exact, and a float otherwise.
+fn insert_cast(
+    funcs: &mut FuncMap,
+    exact: &Arc<AtomicBool>,
+    arg_type: ValueType,
+    to_rational: fn(Value) -> Option<Rational>,
+) {
+    let exact = exact.clone();
+    let kind = OnceLock::new();
+    kind.set(FunctionInnerKind::BuiltIn(Box::new(move |mut args| {
+        assert_eq!(args.len(), 1);
+        let v = to_rational(args.remove(0)).map(Real::Exact);
+        Ok(Value::Real(if exact.load(Ordering::Relaxed) {
+            v
+        } else {
+            v.map(Real::to_float)
+        }))
+    })))
+    .expect("initialization");
+
+    let sign = FunctionSignature {
+        name: Ident::from("#as_real"),
+        arg_types: vec![arg_type],
+    };
+    let func = Function::from(FunctionInner {
+        sign: sign.clone(),
+        return_type: ValueType::Real,
+        kind,
+    });
+    assert!(funcs.insert(sign, func).is_none());
+}
+
+impl ExecScope {
+    pub fn is_exact(&self) -> bool {
+        self.arithmetic.is_exact()
+    }
+
+    pub fn set_exact(&mut self, exact: bool) -> Result<(), ExecError> {
+        if exact == self.is_exact() {
+            return Ok(());
+        }
+        if !(self.nodes.is_empty() && self.funcs.is_empty()) {
+            return Err(ExecError::ArithmeticOfNonEmptyScope);
+        }
+
+        self.arithmetic.exact.store(exact, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use crate::exec::Exec;
+
+    use super::*;
+
+    fn is_exact(value: Value) -> bool {
+        matches!(value, Value::Real(Some(v)) if v.is_exact())
+    }
+
+    #[test]
+    fn exact() {
+        let mut scope = ExecScope::new();
+        scope.set_exact(true).unwrap();
+        parser::definitions(
+            r#"
+            third = 1.0 / 3.0
+            sum = 0.1 + 0.2
+            eq = sum == 0.3
+            n = 3
+            from_int = (n as real) / 6.0
+            squared = third ^ 2.0
+            root = 2.0 ^ 0.5
+            mixed = x (pt 0.5 0.0) + third
+            A = pt 0.0 0.0
+            B = pt 1.0 0.0
+            C = pt 0.0 1.0
+            D = pt 1.0 3.0
+            mid = (A + D) / 2.0
+            l1 = line A D
+            l2 = line B C
+            det = l1.a * l2.b - l2.a * l1.b
+            P = pt ((l1.b * l2.c - l2.b * l1.c) / det) ((l1.c * l2.a - l2.c * l1.a) / det)
+            H = A + (dot (B - A) (D - A) / dot (D - A) (D - A)) * (D - A)
+            dist = (dot D D) ^ 0.5
+            inv x:real -> real = 1.0 / x
+            half = inv 2.0
+            "#,
+        )
+        .unwrap()
+        .exec(&mut scope)
+        .unwrap();
+
+        let get = |scope: &ExecScope, name: &str| scope.get_item(&Ident::from(name)).unwrap();
+        let exact = |num, den| Value::from(Real::Exact(Rational::new(num, den).unwrap()));
+
+        for (name, value) in [
+            ("third", exact(1, 3)),
+            ("sum", exact(3, 10)),
+            ("from_int", exact(1, 2)),
+            ("squared", exact(1, 9)),
+            ("half", exact(1, 2)),
+            ("mixed", exact(5, 6)),
+        ] {
+            assert_eq!(get(&scope, name), value);
+            assert!(is_exact(get(&scope, name)), "{name} should be exact");
+        }
+        assert_eq!(get(&scope, "eq"), true.into());
+        assert_eq!(get(&scope, "third").to_string(), "(1.0 / 3.0)");
+
+        // Rational operations on points are exact too
+        for (name, pt) in [
+            ("mid", "pt (1.0 / 2.0) (3.0 / 2.0)"),
+            ("P", "pt (1.0 / 4.0) (3.0 / 4.0)"),
+            ("H", "pt (1.0 / 10.0) (3.0 / 10.0)"),
+            ("D", "pt 1.0 3.0"),
+        ] {
+            assert_eq!(get(&scope, name).to_string(), pt);
+        }
+
+        // Values are shown as exprs, that evaluate to them
+        for name in [
+            "third", "sum", "from_int", "squared", "mixed", "P", "H", "D",
+        ] {
+            let value = get(&scope, name);
+            let parsed = scope
+                .eval_expr(parser::expr(&value.to_string()).unwrap(), HashMap::new())
+                .unwrap();
+            assert_eq!(parsed, value, "{name}");
+            assert_eq!(parsed.value_type(), value.value_type(), "{name}");
+        }
+        let neg = scope
+            .eval_expr(
+                parser::expr("pt (0.0 - third) -2.0").unwrap(),
+                HashMap::new(),
+            )
+            .unwrap();
+        assert_eq!(neg.to_string(), "pt (-1.0 / 3.0) -2.0");
+        assert_eq!(
+            scope
+                .eval_expr(parser::expr(&neg.to_string()).unwrap(), HashMap::new())
+                .unwrap(),
+            neg
+        );
+
+        // Irrational operations fall back to floats
+        assert!(!is_exact(get(&scope, "root")));
+        assert!(!is_exact(get(&scope, "dist")));
+
+        // The arithmetic is saved
+        let loaded = ExecScope::from_json(&scope.to_json()).unwrap();
+        assert!(loaded.is_exact());
+        assert!(is_exact(get(&loaded, "from_int")));
+        assert_eq!(get(&loaded, "P").to_string(), "pt (1.0 / 4.0) (3.0 / 4.0)");
+        assert!(is_exact(
+            loaded
+                .eval_expr(parser::expr("third + 1.0").unwrap(), HashMap::new())
+                .unwrap()
+        ));
+
+        // It can only be changed in an empty scope
+        assert!(matches!(
+            scope.set_exact(false),
+            Err(ExecError::ArithmeticOfNonEmptyScope)
+        ));
+        scope.clear();
+        scope.set_exact(false).unwrap();
+        assert!(!is_exact(
+            scope
+                .eval_expr(parser::expr("(1 as real) / 3.0").unwrap(), HashMap::new())
+                .unwrap()
+        ));
+    }
+
+    #[test]
+    fn float_by_default() {
+        let scope = ExecScope::new();
+        assert!(!scope.is_exact());
+        assert!(!is_exact(
+            scope
+                .eval_expr(
+                    parser::expr("0.1 + (true as real)").unwrap(),
+                    HashMap::new()
+                )
+                .unwrap()
+        ));
+    }
+}
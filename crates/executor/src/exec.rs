@@ -1,19 +1,29 @@
-use std::collections::{hash_map, HashMap};
+use std::{
+    collections::{hash_map, HashMap},
+    path::PathBuf,
+};
 
 use thiserror::Error;
 use types::{
     api::FunctionList,
     core::{Ident, Value, ValueType},
-    lang::{Definition, Expr, FunctionDefinition, FunctionSignature, ValueDefinition},
+    lang::{Definition, Expr, FunctionDefinition, FunctionSignature, ValueDefinition, Warning},
+    layer::Layer,
+    slider::Slider,
+    style::Style,
 };
 
 use crate::{
     cexpr::{compile::CError, eval::EvalError},
     compile::{CScope, Compile},
+    drawing::DrawingError,
     eval::Eval,
+    exact::Arithmetic,
     function::{FuncMap, Function},
+    import::{ImportError, Library},
     node::Node,
     store::LoadError,
+    tolerance::Tolerance,
 };
 
 #[derive(Debug, Error)]
@@ -28,6 +38,12 @@ pub enum ExecError {
     #[error("load error")]
     LoadError(#[from] LoadError),
 
+    #[error("import error")]
+    ImportError(#[from] ImportError),
+
+    #[error("drawing import error")]
+    DrawingError(#[from] DrawingError),
+
     #[error("undefined variable '{var}' in function '{func}'")]
     UndefinedVariableInFunction { var: Ident, func: FunctionSignature },
 
@@ -46,6 +62,30 @@ pub enum ExecError {
     #[error("variable '{0}' is not defined")]
     UndefinedVariable(Ident),
 
+    #[error("layer '{0}' is not defined")]
+    UndefinedLayer(Ident),
+
+    #[error("layer name '{0}' is already taken by an item")]
+    LayerNameTaken(Ident),
+
+    #[error("item '{0}' is locked")]
+    ItemLocked(Ident),
+
+    #[error("layer '{0}' is locked")]
+    LayerLocked(Ident),
+
+    #[error("sliders can only be put on free int and real items, but '{0}' is not one")]
+    SliderOnNonFree(Ident),
+
+    #[error("slider of int item '{0}' should have whole min, max and step")]
+    SliderNotWhole(Ident),
+
+    #[error("tolerance should be a non-negative number, got {0}")]
+    BadTolerance(f64),
+
+    #[error("arithmetic can only be changed in an empty scope")]
+    ArithmeticOfNonEmptyScope,
+
     #[error("cannot set! {old_type} variable '{name}' with {new_type} value")]
     SetDifferentType {
         name: Ident,
@@ -57,6 +97,32 @@ pub enum ExecError {
 pub struct ExecScope {
     pub(crate) funcs: FuncMap,
     pub(crate) nodes: HashMap<Ident, Node>,
+
+    /// Styles of items, that are not drawn in the default way.
+    pub(crate) styles: HashMap<Ident, Style>,
+
+    /// Whether labels are shown for items, whose style does not say otherwise.
+    pub(crate) show_labels: bool,
+
+    /// Tolerance of comparisons of reals, points, lines and circles.
+    pub(crate) tolerance: Tolerance,
+
+    /// Whether reals are exact rationals rather than floats.
+    pub(crate) arithmetic: Arithmetic,
+
+    /// Ranges of free numeric items, that are changed with sliders in the gui.
+    pub(crate) sliders: HashMap<Ident, Slider>,
+
+    /// Layers in order of creation; items may belong to none of them.
+    pub(crate) layers: Vec<Layer>,
+
+    /// Libraries in order of import.
+    pub(crate) libs: Vec<Library>,
+
+    /// Directory, where `import!` looks for libraries by name.
+    ///
+    /// It's a setting rather than a state, so it's neither cleared nor stored.
+    pub(crate) lib_dir: Option<PathBuf>,
 }
 
 impl Default for ExecScope {
@@ -70,12 +136,29 @@ impl ExecScope {
         Self {
             funcs: FuncMap::new(),
             nodes: HashMap::new(),
+            styles: HashMap::new(),
+            sliders: HashMap::new(),
+            show_labels: false,
+            tolerance: Tolerance::default(),
+            arithmetic: Arithmetic::default(),
+            layers: Vec::new(),
+            libs: Vec::new(),
+            lib_dir: None,
         }
     }
 
+    pub fn with_lib_dir(mut self, lib_dir: Option<PathBuf>) -> Self {
+        self.lib_dir = lib_dir;
+        self
+    }
+
     pub fn clear(&mut self) {
         self.funcs.clear();
         self.nodes.clear();
+        self.styles.clear();
+        self.sliders.clear();
+        self.layers.clear();
+        self.libs.clear();
     }
 
     pub fn rm(&mut self, name: Ident) -> Result<(), ExecError> {
@@ -86,8 +169,23 @@ impl ExecScope {
         #[allow(clippy::mutable_key_type)]
         let nodes_to_rm = node.get_nodes_to_rm();
 
+        if let Some(name) = self
+            .nodes
+            .iter()
+            .find(|(name, node)| nodes_to_rm.contains(*node) && self.is_locked(name))
+            .map(|(name, _node)| name)
+        {
+            return Err(ExecError::ItemLocked(name.clone()));
+        }
+
         self.nodes.retain(|_name, node| !nodes_to_rm.contains(node));
 
+        let nodes = &self.nodes;
+        self.styles.retain(|name, _style| nodes.contains_key(name));
+        self.sliders
+            .retain(|name, _slider| nodes.contains_key(name));
+        self.prune_layers();
+
         Ok(())
     }
 
@@ -96,6 +194,10 @@ impl ExecScope {
             .get_node(name)
             .ok_or(ExecError::UndefinedVariable(name.clone()))?;
 
+        if self.is_locked(name) {
+            return Err(ExecError::ItemLocked(name.clone()));
+        }
+
         if node.value_type() != value.value_type() {
             return Err(ExecError::SetDifferentType {
                 name: name.clone(),
@@ -109,6 +211,56 @@ impl ExecScope {
         Ok(())
     }
 
+    pub fn get_style(&self, name: &Ident) -> Result<Style, ExecError> {
+        if !self.nodes.contains_key(name) {
+            return Err(ExecError::UndefinedVariable(name.clone()));
+        }
+
+        Ok(self.styles.get(name).cloned().unwrap_or_default())
+    }
+
+    /// Returns styles of items, that are not drawn in the default way.
+    pub fn get_all_styles(&self) -> HashMap<Ident, Style> {
+        self.styles.clone()
+    }
+
+    pub fn set_style(&mut self, name: Ident, style: Style) -> Result<(), ExecError> {
+        if !self.nodes.contains_key(&name) {
+            return Err(ExecError::UndefinedVariable(name));
+        }
+
+        if style.is_default() {
+            self.styles.remove(&name);
+        } else {
+            self.styles.insert(name, style);
+        }
+
+        Ok(())
+    }
+
+    pub fn set_show_labels(&mut self, show_labels: bool) {
+        self.show_labels = show_labels;
+    }
+
+    /// Returns label texts of visible items, whose labels are shown.
+    pub fn get_all_labels(&self) -> HashMap<Ident, String> {
+        self.nodes
+            .keys()
+            .filter(|name| !self.is_hidden(name))
+            .filter_map(|name| {
+                let Some(style) = self.styles.get(name) else {
+                    return self.show_labels.then(|| (name.clone(), name.to_string()));
+                };
+
+                let show = style.show_label.unwrap_or(self.show_labels);
+                show.then(|| {
+                    let label = style.label.clone().unwrap_or_else(|| name.to_string());
+                    (name.clone(), label)
+                })
+            })
+            .collect()
+    }
+
     pub fn eval_expr(
         &self,
         expr: Expr,
@@ -131,6 +283,36 @@ impl ExecScope {
         Ok(cexpr.eval(&vars)?)
     }
 
+    /// Compiles a definition and adds it to the scope without evaluating anything.
+    ///
+    /// Items are defined as `none` values of the right type,
+    /// so the scope is only suitable for further checking.
+    /// Dependencies are kept, as some expressions (e.g. `locus`) are compiled from them.
+    pub fn check_definition(&mut self, def: Definition) -> ExecResult<Vec<Warning>> {
+        match def {
+            Definition::ValueDefinition(val_def) => {
+                let name = val_def.name.clone();
+                let (body, warnings) = Node::compile_value_definition(val_def, self)?;
+                let node = if body.required_vars().is_empty() {
+                    Node::from_value(Value::none(body.value_type()))
+                } else {
+                    let bindings = Node::bindings(&body, self);
+                    Node::from_cexpr_unevaluated(body, bindings)
+                };
+                self.insert_node(name, node)?;
+                Ok(warnings)
+            }
+            Definition::FunctionDefinition(func_def) => {
+                Function::push_from_definition(func_def, self)
+            }
+        }
+    }
+
+    /// Compiles an expression and returns it's type without evaluating it.
+    pub fn check_expr(&self, expr: Expr) -> Result<ValueType, ExecError> {
+        Ok(expr.compile(&CScope::new(self))?.value_type())
+    }
+
     pub fn get_all_items(&self) -> HashMap<Ident, Value> {
         self.nodes
             .iter()
@@ -143,19 +325,27 @@ impl ExecScope {
     }
 
     pub(crate) fn get_func(&self, sign: &FunctionSignature) -> Option<Function> {
-        let ans = Function::get_builtin(sign);
-        if ans.is_some() {
-            return ans;
-        }
+        self.get_builtin(sign)
+            .or_else(|| self.funcs.get(sign).cloned())
+    }
 
-        self.funcs.get(sign).cloned()
+    /// Returns a builtin, including the ones, that depend on the scope's settings.
+    fn get_builtin(&self, sign: &FunctionSignature) -> Option<Function> {
+        Function::get_builtin(sign)
+            .or_else(|| self.tolerance.get_func(sign))
+            .or_else(|| self.arithmetic.get_func(sign))
     }
 
     pub fn list_funcs(&self) -> FunctionList {
         let mut operators = Vec::new();
         let mut normal_builtins = Vec::new();
 
-        for builtin in Function::list_builtins() {
+        let scope_dependent = self
+            .tolerance
+            .list_funcs()
+            .chain(self.arithmetic.list_funcs())
+            .cloned();
+        for builtin in Function::list_builtins().into_iter().chain(scope_dependent) {
             if builtin.name.0.starts_with('#') {
                 operators.push(builtin);
             } else {
@@ -173,8 +363,9 @@ impl ExecScope {
     }
 
     pub(crate) fn insert_func(&mut self, func: Function) -> ExecResult {
-        if Function::get_builtin(&func.sign()).is_some() {
-            return Err(ExecError::BuiltinRedefinition(func.sign()));
+        let sign = func.sign();
+        if self.get_builtin(&sign).is_some() {
+            return Err(ExecError::BuiltinRedefinition(sign));
         }
 
         match self.funcs.entry(func.sign()) {
@@ -187,6 +378,10 @@ impl ExecScope {
     }
 
     pub(crate) fn insert_node(&mut self, name: Ident, node: Node) -> ExecResult {
+        if self.get_layer(&name).is_some() {
+            return Err(ExecError::VariableRedefinition(name));
+        }
+
         match self.nodes.entry(name.clone()) {
             hash_map::Entry::Occupied(_) => Err(ExecError::VariableRedefinition(name)),
             hash_map::Entry::Vacant(e) => {
@@ -201,24 +396,25 @@ impl ExecScope {
     }
 }
 
-pub type ExecResult = Result<(), ExecError>;
+pub type ExecResult<T = ()> = Result<T, ExecError>;
 
 pub trait Exec {
-    fn exec(self, scope: &mut ExecScope) -> ExecResult;
+    fn exec(self, scope: &mut ExecScope) -> ExecResult<Vec<Warning>>;
 }
 
 impl Exec for Vec<Definition> {
-    fn exec(self, scope: &mut ExecScope) -> ExecResult {
+    fn exec(self, scope: &mut ExecScope) -> ExecResult<Vec<Warning>> {
         // TODO: nested scope; recover on error
+        let mut warnings = Vec::new();
         for def in self {
-            def.exec(scope)?;
+            warnings.extend(def.exec(scope)?);
         }
-        Ok(())
+        Ok(warnings)
     }
 }
 
 impl Exec for Definition {
-    fn exec(self, scope: &mut ExecScope) -> ExecResult {
+    fn exec(self, scope: &mut ExecScope) -> ExecResult<Vec<Warning>> {
         match self {
             Definition::ValueDefinition(val_def) => val_def.exec(scope),
             Definition::FunctionDefinition(var_def) => var_def.exec(scope),
@@ -227,15 +423,17 @@ impl Exec for Definition {
 }
 
 impl Exec for ValueDefinition {
-    fn exec(self, scope: &mut ExecScope) -> ExecResult {
-        scope.insert_node(self.name.clone(), Node::from_value_definition(self, scope)?)?;
+    fn exec(self, scope: &mut ExecScope) -> ExecResult<Vec<Warning>> {
+        let name = self.name.clone();
+        let (node, warnings) = Node::from_value_definition(self, scope)?;
+        scope.insert_node(name, node)?;
 
-        Ok(())
+        Ok(warnings)
     }
 }
 
 impl Exec for FunctionDefinition {
-    fn exec(self, scope: &mut ExecScope) -> ExecResult {
+    fn exec(self, scope: &mut ExecScope) -> ExecResult<Vec<Warning>> {
         Function::push_from_definition(self, scope)
     }
 }
@@ -285,6 +483,76 @@ mod test {
         }
     }
 
+    #[test]
+    fn check_definition() {
+        let mut scope = ExecScope::new();
+        for def in parser::definitions(
+            r#"
+            fact x:int -> int = if x == 0 then 1, else x * fact (x - 1)
+            a = fact 100000000
+            b = a + 1
+            "#,
+        )
+        .unwrap()
+        {
+            scope.check_definition(def).unwrap();
+        }
+
+        assert_eq!(
+            scope.get_item(&Ident::from("b")),
+            Some(Value::none(ValueType::Int))
+        );
+        assert!(matches!(
+            scope.check_definition(parser::definition("c:real = b").unwrap()),
+            Err(ExecError::UnexpectedType {
+                expected: ValueType::Real,
+                got: ValueType::Int
+            })
+        ));
+        assert_eq!(
+            scope
+                .check_expr(parser::expr("b as real").unwrap())
+                .unwrap(),
+            ValueType::Real
+        );
+    }
+
+    #[test]
+    fn warnings() {
+        let mut scope = ExecScope::new();
+        let warnings = parser::definitions(
+            r#"
+            first x:int y:int -> int = x
+            a = 1
+            b = let a = 2, in a
+            c = if true then 1, else 2
+            d = if false then 1, a == 1 then 2, true then 3, a == 2 then 4
+            "#,
+        )
+        .unwrap()
+        .exec(&mut scope)
+        .unwrap();
+
+        assert_eq!(
+            warnings,
+            vec![
+                Warning::UnusedArgument {
+                    func: FunctionSignature {
+                        name: Ident::from("first"),
+                        arg_types: vec![ValueType::Int, ValueType::Int],
+                    },
+                    arg: Ident::from("y"),
+                },
+                Warning::ShadowedLetBinding(Ident::from("a")),
+                Warning::ConstantIfCondition(true),
+                Warning::UnreachableIfCase,
+                Warning::ConstantIfCondition(false),
+                Warning::ConstantIfCondition(true),
+                Warning::UnreachableIfCase,
+            ]
+        );
+    }
+
     #[test]
     fn get_all_items() {
         let mut scope = ExecScope::new();
@@ -0,0 +1,103 @@
+use types::core::Pt;
+
+use super::*;
+
+// Every function takes the tolerance as the last argument.
+// The scope also has versions without it, see [crate::tolerance].
+pub(super) fn populate(builtins: &mut FuncMap) {
+    builtin!(INTO builtins INSERT
+        // Approx eq
+        fn "approx_eq" (lhs: Real, rhs: Real, eps: Real) -> Bool {
+            unwrap_none!(eps);
+            Ok(Value::Real(lhs).approx_eq(&Value::Real(rhs), eps.into()))
+        }
+        fn "approx_eq" (lhs: Pt, rhs: Pt, eps: Real) -> Bool {
+            unwrap_none!(eps);
+            Ok(Value::Pt(lhs).approx_eq(&Value::Pt(rhs), eps.into()))
+        }
+        fn "approx_eq" (lhs: Line, rhs: Line, eps: Real) -> Bool {
+            unwrap_none!(eps);
+            Ok(Value::Line(lhs).approx_eq(&Value::Line(rhs), eps.into()))
+        }
+        fn "approx_eq" (lhs: Circ, rhs: Circ, eps: Real) -> Bool {
+            unwrap_none!(eps);
+            Ok(Value::Circ(lhs).approx_eq(&Value::Circ(rhs), eps.into()))
+        }
+    );
+
+    simple_builtin!(INTO builtins INSERT
+        // Incidence
+        fn "on" (p: Pt, l: Line, eps: Real) -> Bool { l.contains(p, eps.into()) }
+        fn "on" (p: Pt, c: Circ, eps: Real) -> Bool { c.contains(p, eps.into()) }
+        // Predicates
+        fn "parallel?" (l1: Line, l2: Line, eps: Real) -> Bool { l1.parallel(l2, eps.into()) }
+        fn "perpendicular?" (l1: Line, l2: Line, eps: Real) -> Bool { l1.perpendicular(l2, eps.into()) }
+        fn "collinear?" (a: Pt, b: Pt, c: Pt, eps: Real) -> Bool { Pt::collinear(a, b, c, eps.into()) }
+        fn "concyclic?" (a: Pt, b: Pt, c: Pt, d: Pt, eps: Real) -> Bool { Pt::concyclic(a, b, c, d, eps.into()) }
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cexpr::eval::eval;
+
+    #[test]
+    fn approx_eq() {
+        assert_eq!(eval("approx_eq (0.1 + 0.2) 0.3 1e-9"), true.into());
+        assert_eq!(eval("approx_eq 1.0 1.1 0.2"), true.into());
+        assert_eq!(eval("approx_eq 1.0 1.1 0.01"), false.into());
+        assert_eq!(eval("approx_eq (pt 0.0 0.0) (pt 0.3 0.4) 0.5"), true.into());
+        assert_eq!(
+            eval(
+                "approx_eq (line (pt 0.0 0.0) (pt 1.0 1.0)) (line (pt 2.0 2.0) (pt 0.0 0.0)) 1e-9"
+            ),
+            true.into()
+        );
+        assert_eq!(
+            eval("approx_eq (circ (pt 0.0 0.0) 1.0) (circ (pt 0.0 0.0) 1.5) 0.1"),
+            false.into()
+        );
+        assert_eq!(eval("approx_eq (none pt) (none pt) 0.1"), true.into());
+        assert_eq!(eval("approx_eq (pt 0.0 0.0) (none pt) 0.1"), false.into());
+    }
+
+    #[test]
+    fn predicates() {
+        assert_eq!(
+            eval("on (pt 0.5 0.5) (line (pt 0.0 0.0) (pt 1.0 1.0)) 1e-9"),
+            true.into()
+        );
+        assert_eq!(
+            eval("on (pt 0.0 1.0) (circ (pt 0.0 0.0) 1.0) 1e-9"),
+            true.into()
+        );
+        assert_eq!(
+            eval("on (pt 0.0 1.1) (circ (pt 0.0 0.0) 1.0) 1e-9"),
+            false.into()
+        );
+        assert_eq!(
+            eval(
+                "parallel? (line (pt 0.0 0.0) (pt 1.0 1.0)) (line (pt 0.0 1.0) (pt 1.0 2.0)) 1e-9"
+            ),
+            true.into()
+        );
+        assert_eq!(
+            eval("perpendicular? (line (pt 0.0 0.0) (pt 1.0 1.0)) (line (pt 0.0 1.0) (pt 1.0 2.0)) 1e-9"),
+            false.into()
+        );
+        assert_eq!(
+            eval(
+                "parallel? (line (pt 0.0 0.0) (pt 0.0 0.0)) (line (pt 0.0 1.0) (pt 1.0 2.0)) 1e-9"
+            ),
+            Option::<bool>::None.into()
+        );
+        assert_eq!(
+            eval("collinear? (pt 0.0 0.0) (pt 1.0 2.0) (pt 2.0 4.0) 1e-9"),
+            true.into()
+        );
+        assert_eq!(
+            eval("concyclic? (pt 1.0 0.0) (pt 0.0 1.0) (pt (-1.0) 0.0) (pt 0.0 (-1.0)) 1e-9"),
+            true.into()
+        );
+    }
+}
@@ -20,23 +20,17 @@ pub(super) fn populate(builtins: &mut FuncMap) {
         fn "#geq" (lhs:  Str, rhs:  Str) -> Bool { lhs >= rhs }
     );
 
+    // Reals, points, lines and circles are compared with the scope's tolerance,
+    // see [crate::tolerance]
     builtin!(INTO builtins INSERT
         // Eq
         fn "#eq" (lhs: Bool, rhs: Bool) -> Bool { Ok(lhs == rhs) }
         fn "#eq" (lhs:  Int, rhs:  Int) -> Bool { Ok(lhs == rhs) }
-        fn "#eq" (lhs: Real, rhs: Real) -> Bool { Ok(lhs == rhs) }
         fn "#eq" (lhs:  Str, rhs:  Str) -> Bool { Ok(lhs == rhs) }
-        fn "#eq" (lhs:   Pt, rhs:   Pt) -> Bool { Ok(lhs == rhs) }
-        fn "#eq" (lhs: Line, rhs: Line) -> Bool { Ok(lhs == rhs) }
-        fn "#eq" (lhs: Circ, rhs: Circ) -> Bool { Ok(lhs == rhs) }
         // Neq
         fn "#neq" (lhs: Bool, rhs: Bool) -> Bool { Ok(lhs != rhs) }
         fn "#neq" (lhs:  Int, rhs:  Int) -> Bool { Ok(lhs != rhs) }
-        fn "#neq" (lhs: Real, rhs: Real) -> Bool { Ok(lhs != rhs) }
         fn "#neq" (lhs:  Str, rhs:  Str) -> Bool { Ok(lhs != rhs) }
-        fn "#neq" (lhs:   Pt, rhs:   Pt) -> Bool { Ok(lhs != rhs) }
-        fn "#neq" (lhs: Line, rhs: Line) -> Bool { Ok(lhs != rhs) }
-        fn "#neq" (lhs: Circ, rhs: Circ) -> Bool { Ok(lhs != rhs) }
     );
 }
 
@@ -105,6 +99,17 @@ mod test {
             true.into()
         );
 
+        // Up to the tolerance
+        assert_eq!(eval("0.1 + 0.2 == 0.3"), true.into());
+        assert_eq!(
+            eval("(pt 0.1 0.0) + (pt 0.2 0.0) == pt 0.3 0.0"),
+            true.into()
+        );
+        assert_eq!(
+            eval("line (pt 1.0 2.0) (pt 3.0 4.0) == line (pt 5.0 6.0) (pt 1.0 2.0)"),
+            true.into()
+        );
+
         // False
         assert_eq!(eval("true == false"), false.into());
         assert_eq!(eval("3   == 4"), false.into());
@@ -14,12 +14,12 @@ pub(super) fn populate(builtins: &mut FuncMap) {
         fn "#sub" (lhs:  Int, rhs:  Int) -> Int  { lhs - rhs }
         fn "#sub" (lhs: Real, rhs: Real) -> Real { lhs - rhs }
         // Mul
-        fn "#mul" (lhs: Real, rhs:   Pt) -> Pt   { Pt { x: rhs.x * lhs, y: lhs * rhs.y } }
-        fn "#mul" (lhs:   Pt, rhs: Real) -> Pt   { Pt { x: lhs.x * rhs, y: rhs * lhs.y } }
+        fn "#mul" (lhs: Real, rhs:   Pt) -> Pt   { lhs * rhs }
+        fn "#mul" (lhs:   Pt, rhs: Real) -> Pt   { lhs * rhs }
         fn "#mul" (rhs:  Int, lhs:  Int) -> Int  { lhs * rhs }
         fn "#mul" (lhs: Real, rhs: Real) -> Real { lhs * rhs }
         // Div
-        fn "#div" (lhs:   Pt, rhs: Real) -> Pt   { Pt { x: lhs.x / rhs,        y: lhs.y / rhs        } }
+        fn "#div" (lhs:   Pt, rhs: Real) -> Pt   { lhs / rhs }
         fn "#div" (lhs:  Int, rhs:  Int) -> Int  { lhs / rhs }
         fn "#div" (lhs: Real, rhs: Real) -> Real { lhs / rhs }
         // Pow
@@ -47,7 +47,7 @@ mod test {
     fn add() {
         assert_eq!(
             eval("(pt 1.0 2.0) + (pt 3.0 4.0)"),
-            Pt { x: 4.0, y: 6.0 }.into()
+            Pt::new(4.0, 6.0).into()
         );
         assert_eq!(eval("1   + 1"), 2.into());
         assert_eq!(eval("1.0 + 1.0"), 2.0.into());
@@ -58,7 +58,7 @@ mod test {
     fn sub() {
         assert_eq!(
             eval("(pt 1.0 5.0) - (pt 3.0 4.0)"),
-            Pt { x: -2.0, y: 1.0 }.into()
+            Pt::new(-2.0, 1.0).into()
         );
         assert_eq!(eval("1   - 1"), 0.into());
         assert_eq!(eval("1.0 - 1.0"), 0.0.into());
@@ -66,15 +66,15 @@ mod test {
 
     #[test]
     fn mul() {
-        assert_eq!(eval("(pt 1.0 2.0) * 3.0"), Pt { x: 3.0, y: 6.0 }.into());
-        assert_eq!(eval("3.0 * (pt 1.0 2.0)"), Pt { x: 3.0, y: 6.0 }.into());
+        assert_eq!(eval("(pt 1.0 2.0) * 3.0"), Pt::new(3.0, 6.0).into());
+        assert_eq!(eval("3.0 * (pt 1.0 2.0)"), Pt::new(3.0, 6.0).into());
         assert_eq!(eval("2   * 2"), 4.into());
         assert_eq!(eval("2.0 * 2.0"), 4.0.into());
     }
 
     #[test]
     fn div() {
-        assert_eq!(eval("(pt 3.0 6.0) / 3.0"), Pt { x: 1.0, y: 2.0 }.into());
+        assert_eq!(eval("(pt 3.0 6.0) / 3.0"), Pt::new(1.0, 2.0).into());
         assert_eq!(eval("4   / 2"), 2.into());
         assert_eq!(eval("4.0 / 2.0"), 2.0.into());
     }
@@ -12,10 +12,12 @@ use types::{
 use super::{FuncMap, Function, FunctionInner, FunctionInnerKind};
 use crate::cexpr::eval::{EvalError, EvalResult};
 
+mod approx;
 mod cmp;
 mod logic;
 mod math;
 mod struct_ops;
+mod transform;
 mod type_casts;
 
 impl Function {
@@ -119,9 +121,11 @@ static BUILT_IN_FUNCS: Lazy<FuncMap> = Lazy::new(|| {
 
     math::populate(&mut builtins);
     cmp::populate(&mut builtins);
+    approx::populate(&mut builtins);
     logic::populate(&mut builtins);
     type_casts::populate(&mut builtins);
     struct_ops::populate(&mut builtins);
+    transform::populate(&mut builtins);
 
     builtins
 });
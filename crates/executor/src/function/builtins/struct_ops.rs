@@ -1,8 +1,8 @@
 use super::*;
 
-use types::core::{Circ, Line, Pt};
+use types::core::{Circ, Line, Pt, Real};
 
-fn line_to_abc(Line { p1, p2 }: Line) -> (f64, f64, f64) {
+fn line_to_abc(Line { p1, p2 }: Line) -> (Real, Real, Real) {
     let a = p2.y - p1.y;
     let b = p1.x - p2.x;
     let c = -a * p1.x - b * p1.y;
@@ -11,7 +11,7 @@ fn line_to_abc(Line { p1, p2 }: Line) -> (f64, f64, f64) {
 
 pub(super) fn populate(builtins: &mut FuncMap) {
     simple_builtin!(INTO builtins INSERT
-        fn "pt" (x: Real, y: Real) -> Pt { Pt {x, y} }
+        fn "pt" (x: Real, y: Real) -> Pt { Pt { x, y } }
         fn "x" (p: Pt) -> Real { p.x }
         fn "y" (p: Pt) -> Real { p.y }
 
@@ -22,9 +22,12 @@ pub(super) fn populate(builtins: &mut FuncMap) {
         fn "b" (l: Line) -> Real { line_to_abc(l).1 }
         fn "c" (l: Line) -> Real { line_to_abc(l).2 }
 
-        fn "circ" (o: Pt, r: Real) -> Circ { Circ {o, r} }
+        fn "circ" (o: Pt, r: Real) -> Circ { Circ { o, r } }
         fn "o" (c: Circ) -> Pt { c.o }
         fn "r" (c: Circ) -> Real { c.r }
+
+        fn "pt_on" (l: Line, t: Real) -> Pt { l.at(t) }
+        fn "pt_on" (c: Circ, angle: Real) -> Pt { c.at(angle.to_f64()) }
     );
 }
 
@@ -37,7 +40,7 @@ mod test {
 
     #[test]
     fn pt() {
-        let pt = Pt { x: 1.0, y: 2.0 };
+        let pt = Pt::new(1.0, 2.0);
         assert_eq!(eval("pt 1.0 2.0"), pt.into());
         assert_eq!(eval("x (pt 1.0 2.0)"), pt.x.into());
         assert_eq!(eval("y (pt 1.0 2.0)"), pt.y.into());
@@ -45,8 +48,8 @@ mod test {
 
     #[test]
     fn line() {
-        let p1 = Pt { x: 1.0, y: 2.0 };
-        let p2 = Pt { x: 3.0, y: 4.0 };
+        let p1 = Pt::new(1.0, 2.0);
+        let p2 = Pt::new(3.0, 4.0);
         let l = Line { p1, p2 };
         assert_eq!(eval("line (pt 1.0 2.0) (pt 3.0 4.0)"), l.into());
         assert_eq!(eval("p1 (line (pt 1.0 2.0) (pt 3.0 4.0))"), l.p1.into());
@@ -55,8 +58,8 @@ mod test {
 
     #[test]
     fn line_to_abc() {
-        let p1 = Pt { x: 1.0, y: 2.0 };
-        let p2 = Pt { x: 3.0, y: 4.0 };
+        let p1 = Pt::new(1.0, 2.0);
+        let p2 = Pt::new(3.0, 4.0);
         let l_str = "line (pt 1.0 2.0) (pt 3.0 4.0)";
 
         let Value::Real(Some(a)) = eval(&format!("({l_str}).a")) else {
@@ -68,17 +71,29 @@ mod test {
         let Value::Real(Some(c)) = eval(&format!("({l_str}).c")) else {
             panic!();
         };
-        assert!(a * p1.x + b * p1.y + c == 0.0);
-        assert!(a * p2.x + b * p2.y + c == 0.0);
+        assert!(a * p1.x + b * p1.y + c == Real::from(0));
+        assert!(a * p2.x + b * p2.y + c == Real::from(0));
     }
 
     #[test]
     fn circ() {
-        let o = Pt { x: 1.0, y: 2.0 };
-        let r = 3.0;
-        let c = Circ { o, r };
+        let o = Pt::new(1.0, 2.0);
+        let c = Circ::new(o, 3.0);
         assert_eq!(eval("circ (pt 1.0 2.0) 3.0"), c.into());
         assert_eq!(eval("o (circ (pt 1.0 2.0) 3.0)"), c.o.into());
         assert_eq!(eval("r (circ (pt 1.0 2.0) 3.0)"), c.r.into());
     }
+
+    #[test]
+    fn pt_on() {
+        assert_eq!(
+            eval("pt_on (line (pt 1.0 2.0) (pt 3.0 4.0)) 0.5"),
+            Pt::new(2.0, 3.0).into()
+        );
+
+        let Value::Pt(Some(p)) = eval("pt_on (circ (pt 1.0 2.0) 3.0) 0.0") else {
+            panic!();
+        };
+        assert_eq!(p, Pt::new(4.0, 2.0));
+    }
 }
@@ -0,0 +1,105 @@
+use types::core::Transform;
+
+use super::*;
+
+pub(super) fn populate(builtins: &mut FuncMap) {
+    simple_builtin!(INTO builtins INSERT
+        // Constructors
+        fn "translate" (v: Pt) -> Transform { Transform::translation(v) }
+        fn "rotate" (center: Pt, angle: Real) -> Transform { Transform::rotation(center, angle.into()) }
+        fn "scale" (center: Pt, k: Real) -> Transform { Transform::homothety(center, k.into()) }
+        // Composition; the right one is applied first
+        fn "#mul" (lhs: Transform, rhs: Transform) -> Transform { lhs * rhs }
+        // Apply
+        fn "apply" (t: Transform, p: Pt) -> Pt { t.apply_pt(p) }
+        fn "apply" (t: Transform, l: Line) -> Line { t.apply_line(l) }
+        fn "apply" (t: Transform, c: Circ) -> Circ { t.apply_circ(c) }
+    );
+
+    // None for degenerate lines and transforms
+    builtin!(INTO builtins INSERT
+        fn "reflect" (l: Line) -> Transform {
+            unwrap_none!(l);
+            Ok(Transform::reflection(l))
+        }
+        fn "inverse" (t: Transform) -> Transform {
+            unwrap_none!(t);
+            Ok(t.inverse())
+        }
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use types::core::{Circ, Line, Pt};
+
+    use super::*;
+    use crate::cexpr::eval::eval;
+
+    fn assert_pt(expr: &str, x: f64, y: f64) {
+        let res = eval(expr);
+        assert!(
+            res.approx_eq(&Pt::new(x, y).into(), 1e-9),
+            "{expr} = {res}, expected pt {x} {y}"
+        );
+    }
+
+    #[test]
+    fn apply() {
+        assert_pt("apply (translate (pt 1.0 2.0)) (pt 3.0 4.0)", 4.0, 6.0);
+        assert_pt(
+            "apply (rotate (pt 1.0 1.0) (3.14159265358979 / 2.0)) (pt 2.0 1.0)",
+            1.0,
+            2.0,
+        );
+        assert_pt("apply (scale (pt 1.0 0.0) 3.0) (pt 2.0 1.0)", 4.0, 3.0);
+        assert_pt(
+            "apply (reflect (line (pt 0.0 1.0) (pt 1.0 2.0))) (pt 1.0 0.0)",
+            -1.0,
+            2.0,
+        );
+
+        assert_eq!(
+            eval("apply (translate (pt 1.0 0.0)) (line (pt 0.0 0.0) (pt 1.0 1.0))"),
+            Line::new(Pt::new(1.0, 0.0), Pt::new(2.0, 1.0)).into()
+        );
+        assert_eq!(
+            eval("apply (scale (pt 0.0 0.0) (-2.0)) (circ (pt 1.0 1.0) 1.0)"),
+            Circ::new(Pt::new(-2.0, -2.0), 2.0).into()
+        );
+    }
+
+    #[test]
+    fn compose() {
+        // The right transform is applied first
+        assert_pt(
+            "apply ((translate (pt 1.0 0.0)) * (scale (pt 0.0 0.0) 2.0)) (pt 1.0 1.0)",
+            3.0,
+            2.0,
+        );
+        assert_pt(
+            "apply ((scale (pt 0.0 0.0) 2.0) * (translate (pt 1.0 0.0))) (pt 1.0 1.0)",
+            4.0,
+            2.0,
+        );
+
+        assert_pt(
+            "let t = (rotate (pt 1.0 2.0) 0.7) * (reflect (line (pt 0.0 0.0) (pt 1.0 3.0))) in \
+            apply ((inverse t) * t) (pt 5.0 (-3.0))",
+            5.0,
+            -3.0,
+        );
+    }
+
+    #[test]
+    fn degenerate() {
+        assert_eq!(
+            eval("reflect (line (pt 1.0 1.0) (pt 1.0 1.0))"),
+            Value::none(ValueType::Transform)
+        );
+        assert_eq!(
+            eval("inverse (scale (pt 1.0 1.0) 0.0)"),
+            Value::none(ValueType::Transform)
+        );
+    }
+}
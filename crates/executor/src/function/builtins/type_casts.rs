@@ -1,3 +1,5 @@
+use types::core::Real;
+
 use super::*;
 
 pub(super) fn populate(builtins: &mut FuncMap) {
@@ -5,12 +7,17 @@ pub(super) fn populate(builtins: &mut FuncMap) {
         // As
         fn "#as_bool" (v: Bool) -> Bool { v }
         fn "#as_bool" (v:  Int) -> Bool { v != 0 }
-        fn "#as_bool" (v: Real) -> Bool { v != 0.0 }
+        fn "#as_bool" (v: Real) -> Bool { v.to_f64() != 0.0 }
         fn "#as_int"  (v: Bool) -> Int  { if v { 1 } else { 0 } }
         fn "#as_int"  (v:  Int) -> Int  { v }
-        fn "#as_int"  (v: Real) -> Int  { v as i64 }
-        fn "#as_real" (v: Bool) -> Real { if v { 1.0 } else { 0.0 } }
-        fn "#as_real" (v:  Int) -> Real { v as f64 }
+        fn "#as_int"  (v: Real) -> Int  {
+            match v {
+                Real::Exact(v) => v.num() / v.den(),
+                Real::Float(v) => v as i64,
+            }
+        }
+        // Casts of bools and ints to reals depend on the scope's arithmetic,
+        // see [crate::exact]
         fn "#as_real" (v: Real) -> Real { v }
     );
 
@@ -6,7 +6,7 @@ use std::{
 
 use types::{
     core::{Ident, Value, ValueType},
-    lang::{FunctionDefinition, FunctionSignature},
+    lang::{FunctionDefinition, FunctionSignature, Warning},
 };
 
 use crate::{
@@ -96,7 +96,7 @@ impl Function {
     pub(crate) fn push_from_definition(
         def: FunctionDefinition,
         scope: &mut ExecScope,
-    ) -> ExecResult {
+    ) -> ExecResult<Vec<Warning>> {
         let FunctionDefinition {
             name,
             args,
@@ -141,10 +141,13 @@ impl Function {
         }
 
         // Check for unused arguments
-        for arg_name in &arg_names_set {
+        let mut warnings = cscope.take_warnings();
+        for arg_name in &arg_names {
             if !body.required_vars().contains(arg_name) {
-                // TODO: use better warning processing
-                println!("WARN: unused variable {arg_name}");
+                warnings.push(Warning::UnusedArgument {
+                    func: sign.clone(),
+                    arg: arg_name.clone(),
+                });
             }
         }
 
@@ -164,7 +167,7 @@ impl Function {
             }))
             .expect("initialization");
 
-        Ok(())
+        Ok(warnings)
     }
 }
 
@@ -0,0 +1,343 @@
+//! Implements [types::api] requests on top of [ExecScope].
+//!
+//! It is shared by the server and the embedded client, so both behave the same.
+
+use std::collections::HashMap;
+
+use types::api::{self, IntoError, Request};
+
+use crate::exec::{Exec, ExecScope};
+
+pub type HandleResult<REQ> = Result<<REQ as Request>::Response, api::Error>;
+
+pub trait Handle: Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self>;
+}
+
+impl Handle for api::clear::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        scope.clear();
+        Ok(api::clear::Response {})
+    }
+}
+
+impl Handle for api::eval::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        let values = self
+            .exprs
+            .into_iter()
+            .map(|expr| {
+                scope
+                    .eval_expr(expr, HashMap::new())
+                    .map_err(IntoError::into_error)
+            })
+            .collect();
+
+        Ok(api::eval::Response { values })
+    }
+}
+
+impl Handle for api::exec::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        let warnings = self.defs.exec(scope).map_err(IntoError::into_error)?;
+        Ok(api::exec::Response { warnings })
+    }
+}
+
+impl Handle for api::func::list::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        Ok(api::func::list::Response {
+            func_list: scope.list_funcs(),
+        })
+    }
+}
+
+impl Handle for api::items::get_all::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        Ok(api::items::get_all::Response {
+            items: scope.get_all_items(),
+        })
+    }
+}
+
+impl Handle for api::items::get::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        match scope.get_item(&self.name) {
+            Some(value) => Ok(api::items::get::Response { value }),
+            None => Err(api::Error {
+                msg: format!("item {} not found", self.name),
+            }),
+        }
+    }
+}
+
+impl Handle for api::rm::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        scope.rm(self.name).map_err(IntoError::into_error)?;
+        Ok(api::rm::Response {})
+    }
+}
+
+impl Handle for api::set::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        let value = scope
+            .eval_expr(self.expr, HashMap::new())
+            .map_err(IntoError::into_error)?;
+
+        scope
+            .set(&self.name, value)
+            .map_err(IntoError::into_error)?;
+
+        Ok(api::set::Response {})
+    }
+}
+
+impl Handle for api::style::get::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        let style = scope.get_style(&self.name).map_err(IntoError::into_error)?;
+        Ok(api::style::get::Response { style })
+    }
+}
+
+impl Handle for api::style::get_all::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        Ok(api::style::get_all::Response {
+            styles: scope.get_all_styles(),
+        })
+    }
+}
+
+impl Handle for api::style::set::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        scope
+            .set_style(self.name, self.style)
+            .map_err(IntoError::into_error)?;
+        Ok(api::style::set::Response {})
+    }
+}
+
+impl Handle for api::slider::get_all::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        Ok(api::slider::get_all::Response {
+            sliders: scope.get_all_sliders(),
+        })
+    }
+}
+
+impl Handle for api::slider::set::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        scope
+            .set_slider(self.name, self.slider)
+            .map_err(IntoError::into_error)?;
+        Ok(api::slider::set::Response {})
+    }
+}
+
+impl Handle for api::pt_on::get_all::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        Ok(api::pt_on::get_all::Response {
+            pt_ons: scope.get_all_pt_ons(),
+        })
+    }
+}
+
+impl Handle for api::labels::get_all::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        Ok(api::labels::get_all::Response {
+            labels: scope.get_all_labels(),
+        })
+    }
+}
+
+impl Handle for api::labels::show::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        scope.set_show_labels(self.show);
+        Ok(api::labels::show::Response {})
+    }
+}
+
+impl Handle for api::layer::get_all::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        Ok(api::layer::get_all::Response {
+            layers: scope.get_all_layers(),
+        })
+    }
+}
+
+impl Handle for api::layer::add::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        scope
+            .add_to_layer(self.name, self.items)
+            .map_err(IntoError::into_error)?;
+        Ok(api::layer::add::Response {})
+    }
+}
+
+impl Handle for api::layer::update::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        scope
+            .update_layer(&self.name, self.hidden, self.locked)
+            .map_err(IntoError::into_error)?;
+        Ok(api::layer::update::Response {})
+    }
+}
+
+impl Handle for api::layer::rm::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        scope.rm_layer(&self.name).map_err(IntoError::into_error)?;
+        Ok(api::layer::rm::Response {})
+    }
+}
+
+impl Handle for api::import::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        let warnings = scope
+            .import(self.source, self.namespace)
+            .map_err(IntoError::into_error)?;
+        Ok(api::import::Response { warnings })
+    }
+}
+
+impl Handle for api::drawing::import::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        let items = scope
+            .import_drawing(self.format, &self.content, &self.options)
+            .map_err(IntoError::into_error)?;
+        Ok(api::drawing::import::Response { items })
+    }
+}
+
+impl Handle for api::json::dump::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        Ok(api::json::dump::Response {
+            json: scope.to_json(),
+        })
+    }
+}
+
+impl Handle for api::json::load::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        let loaded = ExecScope::from_json(&self.json).map_err(IntoError::into_error)?;
+        *scope = loaded.with_lib_dir(scope.lib_dir.take());
+        Ok(api::json::load::Response {})
+    }
+}
+
+impl Handle for api::svg::dump::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        Ok(api::svg::dump::Response {
+            svg: scope.to_svg(&self.options),
+        })
+    }
+}
+
+impl Handle for api::png::dump::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        Ok(api::png::dump::Response {
+            png: scope.to_png(&self.options).map_err(IntoError::into_error)?,
+        })
+    }
+}
+
+impl Handle for api::tikz::dump::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        Ok(api::tikz::dump::Response {
+            tikz: scope.to_tikz(&self.options),
+        })
+    }
+}
+
+impl Handle for api::pdf::dump::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        Ok(api::pdf::dump::Response {
+            pdf: scope.to_pdf(&self.options),
+        })
+    }
+}
+
+impl Handle for api::animation::svg::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        Ok(api::animation::svg::Response {
+            svg: scope
+                .animation_to_svg(&self.name, self.sweep, &self.options)
+                .map_err(IntoError::into_error)?,
+        })
+    }
+}
+
+impl Handle for api::animation::frames::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        Ok(api::animation::frames::Response {
+            frames: scope
+                .animation_to_frames(&self.name, self.sweep, self.format, &self.options)
+                .map_err(IntoError::into_error)?,
+        })
+    }
+}
+
+impl Handle for api::check::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        Ok(api::check::Response {
+            report: scope
+                .check(self.expr, &self.options)
+                .map_err(IntoError::into_error)?,
+        })
+    }
+}
+
+impl Handle for api::tolerance::get::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        Ok(api::tolerance::get::Response {
+            tolerance: scope.get_tolerance(),
+        })
+    }
+}
+
+impl Handle for api::tolerance::set::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        scope
+            .set_tolerance(self.tolerance)
+            .map_err(IntoError::into_error)?;
+        Ok(api::tolerance::set::Response {})
+    }
+}
+
+impl Handle for api::exact::get::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        Ok(api::exact::get::Response {
+            exact: scope.is_exact(),
+        })
+    }
+}
+
+impl Handle for api::exact::set::Request {
+    fn handle(self, scope: &mut ExecScope) -> HandleResult<Self> {
+        scope.set_exact(self.exact).map_err(IntoError::into_error)?;
+        Ok(api::exact::set::Response {})
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn handle() {
+        let mut scope = ExecScope::new();
+
+        api::exec::Request {
+            defs: vec![parser::definition("x = 1 + 2").unwrap()],
+        }
+        .handle(&mut scope)
+        .unwrap();
+
+        let resp = api::items::get::Request { name: "x".into() }
+            .handle(&mut scope)
+            .unwrap();
+        assert_eq!(resp.value, 3.into());
+
+        assert!(api::items::get::Request { name: "y".into() }
+            .handle(&mut scope)
+            .is_err());
+    }
+}
@@ -0,0 +1,474 @@
+//! Imports function libraries from `.geom` files.
+//!
+//! A library is a file, that contains only function definitions and `import!` commands.
+
+use std::{
+    collections::{HashMap, HashSet},
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use types::{
+    api::LibrarySource,
+    core::{Ident, Value},
+    lang::{
+        AsExpr, CommandArg, Definition, DotExpr, Expr, FuncCallExpr, FunctionDefinition,
+        FunctionSignature, IfExpr, InfixExpr, LetExpr, Statement, UnaryExpr, Warning,
+    },
+};
+
+use crate::exec::{Exec, ExecResult, ExecScope};
+
+/// Is used to find libraries, if the scope has no library directory set.
+pub const LIB_DIR_ENV: &str = "GEOMETRICA_LIB_DIR";
+
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error("failed to read {path}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("failed to parse {path}: {msg}")]
+    Parse { path: PathBuf, msg: String },
+
+    #[error("{path} is not a library: only function definitions and import! are allowed")]
+    NotALibrary { path: PathBuf },
+
+    #[error("cyclic import: {}", .0.iter().map(|path| path.display().to_string()).collect::<Vec<_>>().join(" -> "))]
+    Cycle(Vec<PathBuf>),
+
+    #[error("library directory is not set, use --lib-dir or ${LIB_DIR_ENV}")]
+    NoLibDir,
+
+    #[error("bad import! arguments: {0}")]
+    BadArgs(String),
+}
+
+/// A library, that was imported into the scope.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Library {
+    pub source: LibrarySource,
+
+    /// Canonical path of the imported file.
+    pub path: PathBuf,
+
+    /// Prefix of the library's functions, e.g. `geo` for `geo::perp`.
+    pub namespace: Option<Ident>,
+
+    /// Functions, that were defined by the library itself.
+    pub funcs: Vec<FunctionSignature>,
+}
+
+impl ExecScope {
+    /// Imports function definitions from a library.
+    ///
+    /// Functions of libraries from the library directory are put into a namespace
+    /// named after the library, unless another namespace is given.
+    /// Importing the same library into the same namespace twice does nothing.
+    ///
+    /// If the import fails, none of the functions are defined.
+    pub fn import(
+        &mut self,
+        source: LibrarySource,
+        namespace: Option<Ident>,
+    ) -> ExecResult<Vec<Warning>> {
+        let (funcs, libs) = (self.funcs.clone(), self.libs.clone());
+
+        let res = self.import_from(source, namespace, None, &mut Vec::new());
+        if res.is_err() {
+            self.funcs = funcs;
+            self.libs = libs;
+        }
+        res
+    }
+
+    /// Executes `import!` with it's arguments: `(ident | expr) ident?`.
+    pub fn import_command(&mut self, args: Vec<CommandArg>) -> ExecResult<Vec<Warning>> {
+        let (source, namespace) = self.import_args(args)?;
+        self.import(source, namespace)
+    }
+
+    pub fn list_libs(&self) -> &[Library] {
+        &self.libs
+    }
+
+    /// `base_dir` is used to resolve relative file paths in nested imports.
+    ///
+    /// `stack` contains paths of libraries, that are being imported.
+    fn import_from(
+        &mut self,
+        source: LibrarySource,
+        namespace: Option<Ident>,
+        base_dir: Option<&Path>,
+        stack: &mut Vec<PathBuf>,
+    ) -> ExecResult<Vec<Warning>> {
+        let path = self.resolve(&source, base_dir)?;
+
+        if stack.contains(&path) {
+            let mut cycle = stack.clone();
+            cycle.push(path);
+            return Err(ImportError::Cycle(cycle).into());
+        }
+
+        let namespace = namespace.or_else(|| match &source {
+            LibrarySource::Lib(name) => Some(name.clone()),
+            LibrarySource::File(_) => None,
+        });
+
+        if self
+            .libs
+            .iter()
+            .any(|lib| lib.path == path && lib.namespace == namespace)
+        {
+            return Ok(Vec::new());
+        }
+
+        let text = fs::read_to_string(&path).map_err(|source| ImportError::Io {
+            path: path.clone(),
+            source,
+        })?;
+        let script = parser::script(&text).map_err(|err| ImportError::Parse {
+            path: path.clone(),
+            msg: err.to_string(),
+        })?;
+
+        let mut warnings = Vec::new();
+        let mut defs = Vec::new();
+
+        stack.push(path.clone());
+        for stmt in script {
+            match stmt {
+                Statement::Definition(Definition::FunctionDefinition(def)) => defs.push(def),
+                Statement::Command(cmd) if cmd.name.0 == "import" => {
+                    let (source, namespace) = self.import_args(cmd.args)?;
+                    warnings.extend(self.import_from(source, namespace, path.parent(), stack)?);
+                }
+                _ => return Err(ImportError::NotALibrary { path }.into()),
+            }
+        }
+        stack.pop();
+
+        if let Some(namespace) = &namespace {
+            let names = defs.iter().map(|def| def.name.clone()).collect();
+            for def in &mut defs {
+                qualify_definition(def, namespace, &names);
+            }
+        }
+
+        let funcs = defs
+            .iter()
+            .map(|def| FunctionSignature {
+                name: def.name.clone(),
+                arg_types: def.args.iter().map(|arg| arg.value_type.clone()).collect(),
+            })
+            .collect();
+
+        for def in defs {
+            warnings.extend(def.exec(self)?);
+        }
+
+        self.libs.push(Library {
+            source,
+            path,
+            namespace,
+            funcs,
+        });
+
+        Ok(warnings)
+    }
+
+    fn resolve(&self, source: &LibrarySource, base_dir: Option<&Path>) -> ExecResult<PathBuf> {
+        let path = match source {
+            LibrarySource::File(path) => match base_dir {
+                Some(base_dir) => base_dir.join(path),
+                None => path.clone(),
+            },
+            LibrarySource::Lib(name) => {
+                let lib_dir = self
+                    .lib_dir
+                    .clone()
+                    .or_else(|| env::var_os(LIB_DIR_ENV).map(PathBuf::from))
+                    .ok_or(ImportError::NoLibDir)?;
+
+                let mut path = lib_dir;
+                path.extend(name.0.split("::"));
+                path.set_extension("geom");
+                path
+            }
+        };
+
+        Ok(path
+            .canonicalize()
+            .map_err(|source| ImportError::Io { path, source })?)
+    }
+
+    fn import_args(&self, args: Vec<CommandArg>) -> ExecResult<(LibrarySource, Option<Ident>)> {
+        let bad_args = |msg: &str| ImportError::BadArgs(msg.to_string());
+
+        let mut args = args.into_iter();
+
+        let source = match args.next() {
+            Some(CommandArg::Ident(name)) => LibrarySource::Lib(name),
+            Some(CommandArg::Expr(expr)) => match self.eval_expr(expr, HashMap::new())? {
+                Value::Str(Some(path)) => LibrarySource::File(PathBuf::from(path)),
+                _ => return Err(bad_args("file path must be a str").into()),
+            },
+            None => return Err(bad_args("expected library name or file path").into()),
+        };
+
+        let namespace = match args.next() {
+            Some(CommandArg::Ident(namespace)) => Some(namespace),
+            Some(CommandArg::Expr(_)) => return Err(bad_args("namespace must be an ident").into()),
+            None => None,
+        };
+
+        if args.next().is_some() {
+            return Err(bad_args("too many arguments").into());
+        }
+
+        Ok((source, namespace))
+    }
+}
+
+/// Puts the function into the namespace along with calls to functions from `names`.
+fn qualify_definition(def: &mut FunctionDefinition, namespace: &Ident, names: &HashSet<Ident>) {
+    qualify_name(&mut def.name, namespace, names);
+    qualify_expr(&mut def.body, namespace, names);
+}
+
+fn qualify_name(name: &mut Ident, namespace: &Ident, names: &HashSet<Ident>) {
+    if names.contains(name) {
+        *name = Ident(format!("{namespace}::{name}"));
+    }
+}
+
+fn qualify_expr(expr: &mut Expr, namespace: &Ident, names: &HashSet<Ident>) {
+    match expr {
+        Expr::Value(_) | Expr::Variable(_) => {}
+        Expr::FuncCall(FuncCallExpr { name, args }) => {
+            qualify_name(name, namespace, names);
+            for arg in args {
+                qualify_expr(arg, namespace, names);
+            }
+        }
+        Expr::Dot(DotExpr { name, body }) => {
+            qualify_name(name, namespace, names);
+            qualify_expr(body, namespace, names);
+        }
+        Expr::If(IfExpr {
+            cases,
+            default_value,
+        }) => {
+            for case in cases {
+                qualify_expr(&mut case.cond, namespace, names);
+                qualify_expr(&mut case.value, namespace, names);
+            }
+            if let Some(default_value) = default_value {
+                qualify_expr(default_value, namespace, names);
+            }
+        }
+        Expr::Let(LetExpr { defs, body }) => {
+            for def in defs {
+                qualify_expr(&mut def.body, namespace, names);
+            }
+            qualify_expr(body, namespace, names);
+        }
+        Expr::Infix(InfixExpr { lhs, rhs, .. }) => {
+            qualify_expr(lhs, namespace, names);
+            qualify_expr(rhs, namespace, names);
+        }
+        Expr::Unary(UnaryExpr { body, .. }) | Expr::As(AsExpr { body, .. }) => {
+            qualify_expr(body, namespace, names);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use tempfile::TempDir;
+    use types::core::ValueType;
+
+    use crate::exec::ExecError;
+
+    use super::*;
+
+    fn write(dir: &TempDir, name: &str, text: &str) -> PathBuf {
+        let path = dir.path().join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, text).unwrap();
+        path
+    }
+
+    fn eval(scope: &ExecScope, expr: &str) -> Value {
+        scope
+            .eval_expr(parser::expr(expr).unwrap(), HashMap::new())
+            .unwrap()
+    }
+
+    #[test]
+    fn file() {
+        let dir = TempDir::new().unwrap();
+        let path = write(
+            &dir,
+            "sq.geom",
+            r#"
+            sq x:int -> int = x * x
+            sum_sq x:int y:int -> int = sq x + y.sq
+            "#,
+        );
+
+        let mut scope = ExecScope::new();
+        scope.import(LibrarySource::File(path), None).unwrap();
+        assert_eq!(eval(&scope, "sum_sq 2 3"), 13.into());
+
+        let mut scope = ExecScope::new();
+        scope
+            .import_command(vec![
+                CommandArg::Expr(
+                    parser::expr(&format!("{:?}", dir.path().join("sq.geom"))).unwrap(),
+                ),
+                CommandArg::Ident("s".into()),
+            ])
+            .unwrap();
+        assert_eq!(eval(&scope, "s::sum_sq 2 3"), 13.into());
+        assert_eq!(eval(&scope, "(s::sq 2).s::sq"), 16.into());
+        assert!(scope
+            .eval_expr(parser::expr("sq 2").unwrap(), HashMap::new())
+            .is_err());
+    }
+
+    #[test]
+    fn lib_dir() {
+        let dir = TempDir::new().unwrap();
+        write(&dir, "geo.geom", "mid a:pt b:pt -> pt = (a + b) / 2.0");
+        write(
+            &dir,
+            "geo/shapes.geom",
+            r#"
+            import! geo
+            import! "../util.geom"
+            diam a:pt b:pt -> circ = circ (geo::mid a b) (half ((b - a).x))
+            "#,
+        );
+        write(&dir, "util.geom", "half x:real -> real = x / 2.0");
+
+        let mut scope = ExecScope::new().with_lib_dir(Some(dir.path().to_path_buf()));
+        scope
+            .import(LibrarySource::Lib("geo::shapes".into()), None)
+            .unwrap();
+
+        assert_eq!(
+            eval(&scope, "(geo::shapes::diam (pt 0.0 0.0) (pt 2.0 0.0)).r"),
+            1.0.into()
+        );
+        assert_eq!(
+            scope
+                .list_libs()
+                .iter()
+                .map(|lib| lib.namespace.as_ref().map(|ns| ns.0.as_str()))
+                .collect::<Vec<_>>(),
+            vec![Some("geo"), None, Some("geo::shapes")]
+        );
+        assert_eq!(
+            scope.list_libs()[2].funcs,
+            vec![FunctionSignature {
+                name: "geo::shapes::diam".into(),
+                arg_types: vec![ValueType::Pt, ValueType::Pt],
+            }]
+        );
+
+        // Already imported
+        scope
+            .import(LibrarySource::Lib("geo".into()), None)
+            .unwrap();
+        assert_eq!(scope.list_libs().len(), 3);
+
+        assert!(matches!(
+            ExecScope::new().import(LibrarySource::Lib("geo".into()), None),
+            Err(ExecError::ImportError(ImportError::NoLibDir))
+        ));
+    }
+
+    #[test]
+    fn errors() {
+        let dir = TempDir::new().unwrap();
+        let a = write(&dir, "a.geom", "import! \"b.geom\"\nf x:int -> int = x");
+        write(&dir, "b.geom", "import! \"a.geom\"\ng x:int -> int = x");
+        let c = write(&dir, "c.geom", "f x:int -> int = x + 1");
+        let d = write(&dir, "d.geom", "x = 1");
+        let e = write(&dir, "e.geom", "h x:int -> int = x\nf x:int -> int = x");
+        let nested = write(
+            &dir,
+            "nested.geom",
+            "h x:int -> int = x\nimport! \"d.geom\"",
+        );
+
+        let mut scope = ExecScope::new();
+        match scope.import(LibrarySource::File(a.clone()), None) {
+            Err(ExecError::ImportError(ImportError::Cycle(cycle))) => {
+                let names: Vec<_> = cycle
+                    .iter()
+                    .map(|path| path.file_name().unwrap().to_str().unwrap())
+                    .collect();
+                assert_eq!(names, ["a.geom", "b.geom", "a.geom"]);
+            }
+            res => panic!("expected cyclic import error, got {res:?}"),
+        }
+
+        let mut scope = ExecScope::new();
+        scope
+            .check_definition(parser::definition("f x:int -> int = x").unwrap())
+            .unwrap();
+        assert!(matches!(
+            scope.import(LibrarySource::File(c.clone()), None),
+            Err(ExecError::FunctionRedefinition(_))
+        ));
+
+        // Failed imports define nothing
+        for lib in [e, nested] {
+            assert!(scope
+                .import(LibrarySource::File(lib.clone()), None)
+                .is_err());
+            assert!(scope
+                .eval_expr(parser::expr("h 1").unwrap(), HashMap::new())
+                .is_err());
+            assert!(scope.list_libs().is_empty());
+            assert_eq!(scope.list_funcs().user_defined.len(), 1);
+        }
+        scope
+            .import(LibrarySource::File(c), Some("c".into()))
+            .unwrap();
+
+        assert!(matches!(
+            scope.import(LibrarySource::File(d), None),
+            Err(ExecError::ImportError(ImportError::NotALibrary { .. }))
+        ));
+        assert!(matches!(
+            scope.import(LibrarySource::File(dir.path().join("none.geom")), None),
+            Err(ExecError::ImportError(ImportError::Io { .. }))
+        ));
+    }
+
+    #[test]
+    fn json() {
+        let dir = TempDir::new().unwrap();
+        let path = write(&dir, "sq.geom", "sq x:int -> int = x * x");
+
+        let mut scope = ExecScope::new();
+        scope
+            .import(LibrarySource::File(path.clone()), Some("m".into()))
+            .unwrap();
+
+        let scope = ExecScope::from_json(&scope.to_json()).unwrap();
+        assert_eq!(eval(&scope, "m::sq 3"), 9.into());
+        assert_eq!(scope.list_libs().len(), 1);
+        assert_eq!(scope.list_libs()[0].source, LibrarySource::File(path));
+    }
+}
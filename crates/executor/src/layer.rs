@@ -0,0 +1,237 @@
+//! Layers are named groups of items, that are hidden, locked and exported together.
+
+use types::{core::Ident, layer::Layer};
+
+use crate::exec::{ExecError, ExecResult, ExecScope};
+
+impl ExecScope {
+    /// Returns layers in order of creation.
+    pub fn get_all_layers(&self) -> Vec<Layer> {
+        self.layers.clone()
+    }
+
+    /// Moves items to the layer, creating it if needed.
+    pub fn add_to_layer(&mut self, name: Ident, items: Vec<Ident>) -> ExecResult {
+        if self.nodes.contains_key(&name) {
+            return Err(ExecError::LayerNameTaken(name));
+        }
+
+        if let Some(item) = items.iter().find(|item| !self.nodes.contains_key(item)) {
+            return Err(ExecError::UndefinedVariable(item.clone()));
+        }
+
+        // An item belongs to at most one layer
+        for layer in &mut self.layers {
+            layer.items.retain(|item| !items.contains(item));
+        }
+
+        let idx = match self.layers.iter().position(|layer| layer.name == name) {
+            Some(idx) => idx,
+            None => {
+                self.layers.push(Layer::new(name));
+                self.layers.len() - 1
+            }
+        };
+
+        let layer = &mut self.layers[idx];
+        for item in items {
+            if !layer.contains(&item) {
+                layer.items.push(item);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Changes the given flags of the layer.
+    pub fn update_layer(
+        &mut self,
+        name: &Ident,
+        hidden: Option<bool>,
+        locked: Option<bool>,
+    ) -> ExecResult {
+        let layer = self
+            .layers
+            .iter_mut()
+            .find(|layer| &layer.name == name)
+            .ok_or_else(|| ExecError::UndefinedLayer(name.clone()))?;
+
+        if let Some(hidden) = hidden {
+            layer.hidden = hidden;
+        }
+        if let Some(locked) = locked {
+            layer.locked = locked;
+        }
+
+        Ok(())
+    }
+
+    /// Removes the layer along with it's items.
+    pub fn rm_layer(&mut self, name: &Ident) -> ExecResult {
+        let layer = self
+            .get_layer(name)
+            .ok_or_else(|| ExecError::UndefinedLayer(name.clone()))?;
+
+        if layer.locked {
+            return Err(ExecError::LayerLocked(name.clone()));
+        }
+
+        for item in layer.items.clone() {
+            // Might have been removed along with another item
+            if self.nodes.contains_key(&item) {
+                self.rm(item)?;
+            }
+        }
+
+        self.layers.retain(|layer| &layer.name != name);
+
+        Ok(())
+    }
+
+    pub fn get_layer(&self, name: &Ident) -> Option<&Layer> {
+        self.layers.iter().find(|layer| &layer.name == name)
+    }
+
+    /// Returns the layer, that contains the item.
+    pub fn layer_of(&self, item: &Ident) -> Option<&Layer> {
+        self.layers.iter().find(|layer| layer.contains(item))
+    }
+
+    /// Whether the item is hidden by itself or by it's layer.
+    pub fn is_hidden(&self, item: &Ident) -> bool {
+        self.styles.get(item).is_some_and(|style| style.hidden)
+            || self.layer_of(item).is_some_and(|layer| layer.hidden)
+    }
+
+    pub fn is_locked(&self, item: &Ident) -> bool {
+        self.layer_of(item).is_some_and(|layer| layer.locked)
+    }
+
+    /// Forgets removed items; empty layers are kept.
+    pub(crate) fn prune_layers(&mut self) {
+        let nodes = &self.nodes;
+        for layer in &mut self.layers {
+            layer.items.retain(|item| nodes.contains_key(item));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use types::core::{Pt, Value};
+
+    use crate::exec::Exec;
+
+    use super::*;
+
+    fn scope() -> ExecScope {
+        let mut scope = ExecScope::new();
+        parser::definitions(
+            r#"
+            A = pt 0.0 0.0
+            B = pt 1.0 0.0
+            C = pt 0.0 1.0
+            l = line A B
+            "#,
+        )
+        .unwrap()
+        .exec(&mut scope)
+        .unwrap();
+        scope
+    }
+
+    #[test]
+    fn add() {
+        let mut scope = scope();
+        let [a, b, c, l] = ["A", "B", "C", "l"].map(Ident::from);
+
+        scope
+            .add_to_layer("base".into(), vec![a.clone(), b.clone()])
+            .unwrap();
+        scope
+            .add_to_layer("lines".into(), vec![l.clone(), b.clone()])
+            .unwrap();
+
+        let layers = scope.get_all_layers();
+        assert_eq!(layers.len(), 2);
+        assert_eq!(layers[0].items, vec![a.clone()]);
+        assert_eq!(layers[1].items, vec![l.clone(), b.clone()]);
+        assert_eq!(scope.layer_of(&b).unwrap().name, "lines".into());
+        assert!(scope.layer_of(&c).is_none());
+
+        assert!(matches!(
+            scope.add_to_layer("A".into(), vec![c.clone()]),
+            Err(ExecError::LayerNameTaken(_))
+        ));
+        assert!(matches!(
+            scope.add_to_layer("base".into(), vec!["D".into()]),
+            Err(ExecError::UndefinedVariable(_))
+        ));
+        assert!(matches!(
+            parser::definition("base = 1").unwrap().exec(&mut scope),
+            Err(ExecError::VariableRedefinition(_))
+        ));
+
+        // Removed items are forgotten
+        scope.rm(a.clone()).unwrap();
+        assert!(scope.get_layer(&"base".into()).unwrap().items.is_empty());
+        assert!(scope.layer_of(&l).is_none());
+    }
+
+    #[test]
+    fn flags() {
+        let mut scope = scope();
+        let [a, b, c] = ["A", "B", "C"].map(Ident::from);
+        let layer = Ident::from("base");
+
+        scope
+            .add_to_layer(layer.clone(), vec![a.clone(), b.clone()])
+            .unwrap();
+
+        scope.update_layer(&layer, Some(true), None).unwrap();
+        assert!(scope.is_hidden(&a));
+        assert!(!scope.is_hidden(&c));
+
+        scope.update_layer(&layer, None, Some(true)).unwrap();
+        assert!(scope.is_locked(&a));
+        assert!(scope.get_layer(&layer).unwrap().hidden);
+
+        assert!(matches!(
+            scope.set(&a, Value::from(Pt::new(1.0, 1.0))),
+            Err(ExecError::ItemLocked(_))
+        ));
+        assert!(matches!(scope.rm(a.clone()), Err(ExecError::ItemLocked(_))));
+        // Items, that depend on locked ones, are not locked
+        scope.rm("l".into()).unwrap();
+        assert!(matches!(
+            scope.rm_layer(&layer),
+            Err(ExecError::LayerLocked(_))
+        ));
+        assert!(matches!(
+            scope.update_layer(&"other".into(), Some(true), None),
+            Err(ExecError::UndefinedLayer(_))
+        ));
+
+        scope
+            .update_layer(&layer, Some(false), Some(false))
+            .unwrap();
+        assert!(!scope.is_hidden(&a));
+        scope.set(&c, Value::from(Pt::new(1.0, 1.0))).unwrap();
+    }
+
+    #[test]
+    fn rm() {
+        let mut scope = scope();
+        let layer = Ident::from("base");
+
+        scope
+            .add_to_layer(layer.clone(), vec!["A".into(), "l".into()])
+            .unwrap();
+        scope.rm_layer(&layer).unwrap();
+
+        assert!(scope.get_all_layers().is_empty());
+        let mut names: Vec<_> = scope.get_all_items().into_keys().collect();
+        names.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(names, vec!["B".into(), "C".into()]);
+    }
+}
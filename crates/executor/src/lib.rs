@@ -1,8 +1,21 @@
+pub mod animate;
 mod cexpr;
+mod check;
+pub mod drawing;
+mod exact;
 pub mod exec;
 mod function;
+pub mod handle;
+pub mod import;
+mod layer;
 mod node;
+mod pdf;
+mod pt_on;
+pub mod raster;
+mod slider;
 mod store;
 mod svg;
+mod tikz;
+mod tolerance;
 
 pub use cexpr::{compile, eval};
@@ -6,7 +6,7 @@ use std::{
 
 use types::{
     core::{Ident, Value, ValueType},
-    lang::ValueDefinition,
+    lang::{ValueDefinition, Warning},
 };
 
 use crate::{
@@ -95,15 +95,24 @@ impl Node {
     }
 
     pub(crate) fn from_cexpr(body: CExpr, bindings: Vec<(Ident, Node)>) -> Result<Self, EvalError> {
+        let value = body.eval(
+            &bindings
+                .iter()
+                .map(|(name, node)| (name.clone(), node.get_value()))
+                .collect(),
+        )?;
+        Ok(Self::from_cexpr_with_value(body, bindings, value))
+    }
+
+    /// Creates a node with a `none` value, that keeps the structure of the definition.
+    pub(crate) fn from_cexpr_unevaluated(body: CExpr, bindings: Vec<(Ident, Node)>) -> Self {
+        let value = Value::none(body.value_type());
+        Self::from_cexpr_with_value(body, bindings, value)
+    }
+
+    fn from_cexpr_with_value(body: CExpr, bindings: Vec<(Ident, Node)>, value: Value) -> Self {
         let node = Node::from(NodeInnerKind::CExpr(CExprNode {
-            value: Mutex::new(
-                body.eval(
-                    &bindings
-                        .iter()
-                        .map(|(name, node)| (name.clone(), node.get_value()))
-                        .collect(),
-                )?,
-            ),
+            value: Mutex::new(value),
             body,
             bindings: bindings.clone(),
         }));
@@ -117,7 +126,7 @@ impl Node {
                 .push(node.downgrade());
         }
 
-        Ok(node)
+        node
     }
 
     pub(crate) fn downgrade(&self) -> WeakNode {
@@ -141,12 +150,45 @@ impl Node {
     pub(crate) fn from_value_definition(
         def: ValueDefinition,
         scope: &ExecScope,
-    ) -> Result<Node, ExecError> {
+    ) -> Result<(Node, Vec<Warning>), ExecError> {
+        let (body, warnings) = Self::compile_value_definition(def, scope)?;
+
+        let node = if body.required_vars().is_empty() {
+            Node::from_value(body.eval(&HashMap::new())?)
+        } else {
+            let bindings = Self::bindings(&body, scope);
+            Node::from_cexpr(body, bindings)?
+        };
+
+        Ok((node, warnings))
+    }
+
+    /// Nodes of the variables, that are required by the compiled body.
+    pub(crate) fn bindings(body: &CExpr, scope: &ExecScope) -> Vec<(Ident, Node)> {
+        body.required_vars()
+            .iter()
+            .map(|var| {
+                (
+                    var.clone(),
+                    scope
+                        .get_node(var)
+                        .expect("var should be defined as body was successfully compiled"),
+                )
+            })
+            .collect()
+    }
+
+    /// Compiles definition's body and checks it's type without evaluating it.
+    pub(crate) fn compile_value_definition(
+        def: ValueDefinition,
+        scope: &ExecScope,
+    ) -> Result<(CExpr, Vec<Warning>), ExecError> {
         let ValueDefinition {
             value_type, body, ..
         } = def;
 
-        let body = body.compile(&CScope::new(scope))?;
+        let cscope = CScope::new(scope);
+        let body = body.compile(&cscope)?;
 
         if let Some(expected_type) = value_type {
             if body.value_type() != expected_type {
@@ -157,26 +199,7 @@ impl Node {
             }
         }
 
-        let node = if body.required_vars().is_empty() {
-            Node::from_value(body.eval(&HashMap::new())?)
-        } else {
-            let bindings: Vec<(Ident, Node)> = body
-                .required_vars()
-                .iter()
-                .map(|var| {
-                    (
-                        var.clone(),
-                        scope
-                            .get_node(var)
-                            .expect("var should be defined as body was successfully compiled"),
-                    )
-                })
-                .collect();
-
-            Node::from_cexpr(body, bindings.clone())?
-        };
-
-        Ok(node)
+        Ok((body, cscope.take_warnings()))
     }
 
     pub(crate) fn set(&self, value: Value) -> Result<(), EvalError> {
@@ -215,6 +238,37 @@ impl Node {
         }
     }
 
+    /// Evaluates the nodes and all nodes, that they depend on, again,
+    /// even if none of their values have changed.
+    ///
+    /// Dependencies are evaluated before dependent nodes.
+    pub(crate) fn reevaluate_all(nodes: impl IntoIterator<Item = Node>) -> Result<(), EvalError> {
+        #[allow(clippy::mutable_key_type)]
+        fn inner(evaluated: &mut HashSet<Node>, node: Node) -> Result<(), EvalError> {
+            if evaluated.contains(&node) {
+                return Ok(());
+            }
+
+            if let NodeInnerKind::CExpr(CExprNode { bindings, .. }) = &node.0.kind {
+                for (_name, binding) in bindings {
+                    inner(evaluated, binding.clone())?;
+                }
+            }
+            node.update_self()?;
+
+            evaluated.insert(node);
+            Ok(())
+        }
+
+        #[allow(clippy::mutable_key_type)]
+        let mut evaluated = HashSet::new();
+        for node in nodes {
+            inner(&mut evaluated, node)?;
+        }
+
+        Ok(())
+    }
+
     fn update(&self) -> Result<(), EvalError> {
         if !self.update_self()? {
             return Ok(());
@@ -0,0 +1,472 @@
+//! This module implements exporting ExecScope to pdf.
+//!
+//! The scene is the one of the svg exporter, so both outputs match.
+//! Pdf is written by hand: a single page with vector graphics
+//! and labels in the standard Helvetica font, that does not need embedding.
+
+use std::{collections::HashMap, io::Write};
+
+use types::{
+    core::{Circ, Ident, Line, Polyline, Pt, Value},
+    export::PdfOptions,
+    style::{Color, Style},
+};
+
+use crate::{
+    exec::ExecScope,
+    svg::{Item, LABEL_OFFSET, LABEL_SIZE, PT_RADIUS, STROKE_WIDTH},
+    tikz::num,
+};
+
+/// Pdf points per css pixel.
+const PT_PER_PX: f64 = 0.75;
+
+const PT_PER_MM: f64 = 72.0 / 25.4;
+
+/// Height of lowercase letters in Helvetica, relative to the font size.
+const HELVETICA_X_HEIGHT: f64 = 0.523;
+
+/// Distance from a control point to the end of a cubic bezier, that approximates a quarter of a circle.
+const CIRCLE_KAPPA: f64 = 0.552_284_75;
+
+impl ExecScope {
+    pub fn to_pdf(&self, options: &PdfOptions) -> Vec<u8> {
+        let page_width = options.page.width * PT_PER_MM;
+        let page_height = options.page.height * PT_PER_MM;
+
+        let mut export = options.export.clone();
+        if options.fit {
+            let margin = options.margin * PT_PER_MM;
+            export.width = Some((page_width - 2.0 * margin) / PT_PER_PX);
+            export.height = Some((page_height - 2.0 * margin) / PT_PER_PX);
+        }
+
+        let scene = self.scene(&export);
+        let [x, y, width, height] = scene.frame.view_box;
+
+        let mut content = Content::default();
+
+        // Svg units to pdf points: the view box is centered on the page and y axis is flipped
+        content.op(format!(
+            "{} 0 0 {} {} {} cm",
+            num(PT_PER_PX),
+            num(-PT_PER_PX),
+            num(page_width / 2.0 - PT_PER_PX * (x + width / 2.0)),
+            num(page_height / 2.0 + PT_PER_PX * (y + height / 2.0)),
+        ));
+
+        let view_box = format!("{} {} {} {} re", num(x), num(y), num(width), num(height));
+        content.op(format!("{view_box} W n"));
+
+        if let Some(background) = export.background {
+            content.fill_color(background);
+            content.op(format!("{view_box} f"));
+        }
+
+        content.op("1 J 1 j");
+
+        for (layer, items) in &scene.groups {
+            if let Some(layer) = layer {
+                content.op(format!("% layer {}", layer.name));
+            }
+            content.draw_items(items, &scene.labels, scene.frame.scale);
+        }
+
+        write_document(page_width, page_height, &content)
+    }
+}
+
+/// Page content stream with resources, that it uses.
+#[derive(Default)]
+struct Content {
+    ops: Vec<String>,
+
+    /// Opacities of graphics states `/GS0`, `/GS1`, ...
+    alphas: Vec<u8>,
+}
+
+impl Content {
+    fn op(&mut self, op: impl Into<String>) {
+        self.ops.push(op.into());
+    }
+
+    fn draw_items(&mut self, items: &[Item], labels: &HashMap<Ident, String>, scale: f64) {
+        for (_name, value, style) in items {
+            self.op("q");
+            self.draw_value(value, style, scale);
+            self.op("Q");
+        }
+
+        // Labels are drawn over all objects
+        for (name, value, style) in items {
+            if let Some(label) = labels.get(name) {
+                self.op("q");
+                self.draw_label(value, label, style, scale);
+                self.op("Q");
+            }
+        }
+    }
+
+    fn draw_value(&mut self, value: &Value, style: &Style, scale: f64) {
+        let color = style.color.unwrap_or(Color::BLACK);
+
+        match value {
+            Value::Pt(Some(pt)) => {
+                // Points are round caps of zero-length lines in svg, so the width is a diameter
+                let radius = style.width.unwrap_or(PT_RADIUS / 2.0);
+                self.fill_color(color);
+                self.circle(*pt * scale, radius);
+                self.op("f");
+            }
+
+            Value::Line(Some(Line { p1, p2 })) => {
+                let ((x1, y1), (x2, y2)) = ((*p1 * scale).to_f64(), (*p2 * scale).to_f64());
+                self.stroke_style(color, style);
+                self.op(format!("{} {} m", num(x1), num(y1)));
+                self.op(format!("{} {} l S", num(x2), num(y2)));
+            }
+
+            Value::Circ(Some(Circ { o, r })) => {
+                // Fill is painted separately, as it may have it's own opacity;
+                // graphics state can't be changed inside a path
+                if let Some(fill) = style.fill {
+                    self.op("q");
+                    self.fill_color(fill);
+                    self.circle(*o * scale, r.to_f64() * scale);
+                    self.op("f");
+                    self.op("Q");
+                }
+                self.stroke_style(color, style);
+                self.circle(*o * scale, r.to_f64() * scale);
+                self.op("S");
+            }
+
+            Value::Polyline(Some(Polyline { pts })) if !pts.is_empty() => {
+                self.stroke_style(color, style);
+                for (i, pt) in pts.iter().enumerate() {
+                    let (x, y) = (*pt * scale).to_f64();
+                    let op = if i == 0 { "m" } else { "l" };
+                    self.op(format!("{} {} {op}", num(x), num(y)));
+                }
+                self.op("S");
+            }
+
+            _ => {}
+        }
+    }
+
+    fn draw_label(&mut self, value: &Value, label: &str, style: &Style, scale: f64) {
+        let Some((anchor, dir)) = value.label_anchor() else {
+            return;
+        };
+        let (x, y) = (anchor * scale + dir * LABEL_OFFSET).to_f64();
+
+        let text = to_win_ansi(label);
+        let width = helvetica_width(&text) * LABEL_SIZE;
+
+        self.fill_color(style.color.unwrap_or(Color::BLACK));
+
+        // Text matrix flips y back, so that glyphs are not upside down;
+        // text is centered horizontally and vertically, as in svg
+        self.op(format!(
+            "BT /F1 {} Tf 1 0 0 -1 {} {} Tm ({}) Tj ET",
+            num(LABEL_SIZE),
+            num(x - width / 2.0),
+            num(y + HELVETICA_X_HEIGHT * LABEL_SIZE / 2.0),
+            escape_string(&text),
+        ));
+    }
+
+    fn stroke_style(&mut self, color: Color, style: &Style) {
+        self.stroke_color(color);
+        self.op(format!("{} w", num(style.width.unwrap_or(STROKE_WIDTH))));
+
+        if let Some(dash) = &style.dash {
+            let dash: Vec<_> = dash.iter().map(|len| num(*len)).collect();
+            self.op(format!("[{}] 0 d", dash.join(" ")));
+        }
+    }
+
+    fn stroke_color(&mut self, color: Color) {
+        let Color { r, g, b, a } = color;
+        self.op(format!("{} {} {} RG", channel(r), channel(g), channel(b)));
+        self.alpha("CA", a);
+    }
+
+    fn fill_color(&mut self, color: Color) {
+        let Color { r, g, b, a } = color;
+        self.op(format!("{} {} {} rg", channel(r), channel(g), channel(b)));
+        self.alpha("ca", a);
+    }
+
+    /// Sets stroke (`CA`) or fill (`ca`) opacity with a graphics state.
+    fn alpha(&mut self, key: &str, alpha: u8) {
+        if alpha == 255 {
+            return;
+        }
+
+        let idx = match self.alphas.iter().position(|a| *a == alpha) {
+            Some(idx) => idx,
+            None => {
+                self.alphas.push(alpha);
+                self.alphas.len() - 1
+            }
+        };
+
+        self.op(format!("/GS{idx}{key} gs"));
+    }
+
+    /// Adds a circle to the current path.
+    fn circle(&mut self, o: Pt, r: f64) {
+        let (x, y) = o.to_f64();
+        let k = CIRCLE_KAPPA * r;
+        self.op(format!("{} {} m", num(x + r), num(y)));
+        for [c1, c2, end] in [
+            [(x + r, y + k), (x + k, y + r), (x, y + r)],
+            [(x - k, y + r), (x - r, y + k), (x - r, y)],
+            [(x - r, y - k), (x - k, y - r), (x, y - r)],
+            [(x + k, y - r), (x + r, y - k), (x + r, y)],
+        ] {
+            self.op(format!(
+                "{} {} {} {} {} {} c",
+                num(c1.0),
+                num(c1.1),
+                num(c2.0),
+                num(c2.1),
+                num(end.0),
+                num(end.1),
+            ));
+        }
+        self.op("h");
+    }
+}
+
+fn channel(c: u8) -> String {
+    num(c as f64 / 255.0)
+}
+
+/// Writes a single page document.
+fn write_document(page_width: f64, page_height: f64, content: &Content) -> Vec<u8> {
+    let stream = content.ops.join("\n");
+
+    let ext_g_states = if content.alphas.is_empty() {
+        String::new()
+    } else {
+        let states: Vec<_> = content
+            .alphas
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, alpha)| {
+                let alpha = num(*alpha as f64 / 255.0);
+                [
+                    format!("/GS{idx}CA << /CA {alpha} >>"),
+                    format!("/GS{idx}ca << /ca {alpha} >>"),
+                ]
+            })
+            .collect();
+        format!(" /ExtGState << {} >>", states.join(" "))
+    };
+
+    let objects = [
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+        format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+            /Resources << /Font << /F1 4 0 R >>{ext_g_states} >> \
+            /Contents 5 0 R >>",
+            num(page_width),
+            num(page_height),
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+        format!(
+            "<< /Length {} >>\nstream\n{stream}\nendstream",
+            stream.len()
+        ),
+    ];
+
+    // Writing to a Vec never fails
+    let mut pdf = Vec::new();
+    pdf.extend_from_slice(b"%PDF-1.4\n");
+
+    let mut offsets = Vec::new();
+    for (idx, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        writeln!(pdf, "{} 0 obj\n{object}\nendobj", idx + 1).unwrap();
+    }
+
+    let xref = pdf.len();
+    writeln!(pdf, "xref\n0 {}", objects.len() + 1).unwrap();
+    // Entries are exactly 20 bytes long
+    write!(pdf, "0000000000 65535 f\r\n").unwrap();
+    for offset in offsets {
+        write!(pdf, "{offset:010} 00000 n\r\n").unwrap();
+    }
+    write!(
+        pdf,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+        objects.len() + 1
+    )
+    .unwrap();
+
+    pdf
+}
+
+/// Encodes text for the standard font; unsupported characters become `?`.
+fn to_win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c as u32 {
+            code @ (0x20..=0x7e | 0xa0..=0xff) => code as u8,
+            _ => b'?',
+        })
+        .collect()
+}
+
+fn escape_string(text: &[u8]) -> String {
+    let mut escaped = String::new();
+    for &byte in text {
+        match byte {
+            b'(' | b')' | b'\\' => {
+                escaped.push('\\');
+                escaped.push(byte as char);
+            }
+            0x20..=0x7e => escaped.push(byte as char),
+            _ => escaped.push_str(&format!("\\{byte:03o}")),
+        }
+    }
+    escaped
+}
+
+/// Width of the text in Helvetica relative to the font size.
+fn helvetica_width(text: &[u8]) -> f64 {
+    /// Widths of ascii characters from ' ' to '~' in thousandths of the font size.
+    #[rustfmt::skip]
+    const WIDTHS: [u16; 95] = [
+        278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+        556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556,
+        1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778,
+        667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556,
+        333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+        556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+    ];
+
+    text.iter()
+        .map(|&byte| match byte {
+            0x20..=0x7e => WIDTHS[(byte - 0x20) as usize],
+            // Most of latin-1 letters are about as wide as the ascii ones
+            _ => 556,
+        })
+        .map(|width| width as f64 / 1000.0)
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use types::export::ExportOptions;
+
+    use crate::exec::Exec;
+
+    use super::*;
+
+    fn scope() -> ExecScope {
+        let mut scope = ExecScope::new();
+        parser::definitions(
+            r#"
+            A = pt 0.0 0.0
+            B = pt 2.0 1.0
+            l = line A B
+            c = circ B 0.5
+            "#,
+        )
+        .unwrap()
+        .exec(&mut scope)
+        .unwrap();
+        scope
+    }
+
+    #[test]
+    fn structure() {
+        let mut scope = scope();
+        let mut style = Style::default();
+        style.apply("color: #ff000080; fill: blue").unwrap();
+        scope.set_style(Ident::from("c"), style).unwrap();
+        scope.set_show_labels(true);
+
+        let pdf = scope.to_pdf(&PdfOptions::default());
+        let text = String::from_utf8_lossy(&pdf);
+
+        assert!(text.starts_with("%PDF-1.4\n"));
+        assert!(text.ends_with("%%EOF\n"));
+        assert!(text.contains("/MediaBox [0 0 595.2756 841.8898]"));
+
+        // Cross-reference table points to the objects
+        let xref: usize = text
+            .split("startxref\n")
+            .nth(1)
+            .unwrap()
+            .lines()
+            .next()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(text[xref..].starts_with("xref\n0 6\n"));
+        for (idx, entry) in text[xref..].lines().skip(3).take(5).enumerate() {
+            let offset: usize = entry[..10].parse().unwrap();
+            assert!(text[offset..].starts_with(&format!("{} 0 obj", idx + 1)));
+        }
+
+        // Stream length matches
+        let (_, stream) = text.split_once(">>\nstream\n").unwrap();
+        let (stream, _) = stream.split_once("\nendstream").unwrap();
+        assert!(text.contains(&format!("/Length {}", stream.len())));
+
+        // Styles
+        assert!(stream.contains("1 0 0 RG"));
+        assert!(stream.contains("0 0 1 rg"));
+        assert!(stream.contains("/GS0CA gs"));
+        assert!(text.contains("/GS0CA << /CA 0.502 >>"));
+        assert!(stream.contains("(A) Tj"));
+    }
+
+    #[test]
+    fn geometry() {
+        let scope = scope();
+
+        // Without fitting the drawing has the size of the svg
+        let mut options = PdfOptions::default();
+        options.apply("fit: none; width: 200; height: 100").unwrap();
+        let pdf = String::from_utf8_lossy(&scope.to_pdf(&options)).to_string();
+        let svg = scope.to_svg(&options.export);
+
+        assert!(svg.contains(r#"viewBox="-33.33333333333333 -90 200 100""#));
+        assert!(svg.contains(r#"x1="-20" x2="166.66666666666669" y1="10" y2="-83.33333333333334""#));
+        // The view box is centered on the page
+        assert!(pdf.contains("0.75 0 0 -0.75 247.6378 390.9449 cm"));
+        assert!(pdf.contains("-33.3333 -90 200 100 re W n"));
+        assert!(pdf.contains("-20 10 m\n166.6667 -83.3333 l S"));
+
+        // Fitting overrides the size
+        options.apply("fit: page").unwrap();
+        let export = ExportOptions {
+            width: Some((210.0 - 30.0) * PT_PER_MM / PT_PER_PX),
+            height: Some((297.0 - 30.0) * PT_PER_MM / PT_PER_PX),
+            ..options.export.clone()
+        };
+        let [x, y, width, height] = scope.scene(&export).frame.view_box;
+        let pdf = String::from_utf8_lossy(&scope.to_pdf(&options)).to_string();
+        assert!(pdf.contains(&format!(
+            "{} {} {} {} re W n",
+            num(x),
+            num(y),
+            num(width),
+            num(height)
+        )));
+    }
+
+    #[test]
+    fn text() {
+        assert_eq!(to_win_ansi("Aé→"), b"A\xe9?");
+        assert_eq!(escape_string(b"(a\\b)\xe9"), r"\(a\\b\)\351");
+        assert_eq!(helvetica_width(b"AB"), 1.334);
+    }
+}
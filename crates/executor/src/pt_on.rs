@@ -0,0 +1,138 @@
+//! Points, that are constrained to objects, i.e. defined as `P = pt_on <object> <param>`.
+//!
+//! Such a point is moved by changing it's parameter, which must be a real value item.
+
+use std::collections::HashMap;
+
+use types::core::{Ident, PtOn, ValueType};
+
+use crate::{
+    cexpr::{eval::Eval, CExpr, CExprInnerKind, FuncCallCExpr},
+    exec::ExecScope,
+    function::Function,
+    node::{CExprNode, Node, NodeInnerKind},
+};
+
+impl ExecScope {
+    /// Returns all points, that are defined with the builtin `pt_on` function
+    /// and whose parameter is a free real item.
+    pub fn get_all_pt_ons(&self) -> HashMap<Ident, PtOn> {
+        self.nodes
+            .iter()
+            .filter_map(|(name, node)| {
+                let (object, param) = self.pt_on_parts(node)?;
+
+                let NodeInnerKind::CExpr(CExprNode { bindings, .. }) = &node.inner().kind else {
+                    unreachable!("pt_on nodes are cexpr nodes");
+                };
+                let vars = bindings
+                    .iter()
+                    .map(|(name, node)| (name.clone(), node.get_value()))
+                    .collect();
+                let object = object.eval(&vars).ok()?;
+
+                Some((
+                    name.clone(),
+                    PtOn {
+                        object,
+                        param: param.clone(),
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    /// Returns the object's expression and the parameter of a point,
+    /// that is defined as `pt_on <object> <param>` with a free real parameter.
+    pub(crate) fn pt_on_parts<'a>(&self, node: &'a Node) -> Option<(&'a CExpr, &'a Ident)> {
+        let NodeInnerKind::CExpr(CExprNode { body, .. }) = &node.inner().kind else {
+            return None;
+        };
+
+        let CExprInnerKind::FuncCall(FuncCallCExpr { func, args }) = &body.inner().kind else {
+            return None;
+        };
+
+        let sign = func.sign();
+        if sign.name.0 != "pt_on" || Function::get_builtin(&sign).as_ref() != Some(func) {
+            return None;
+        }
+
+        let CExprInnerKind::Variable(param) = &args[1].inner().kind else {
+            return None;
+        };
+
+        let param_node = self.get_node(param)?;
+        if !matches!(param_node.inner().kind, NodeInnerKind::Value(_))
+            || param_node.value_type() != ValueType::Real
+        {
+            return None;
+        }
+
+        Some((&args[0], param))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use types::core::{Line, Pt, Value};
+
+    use crate::exec::Exec;
+
+    use super::*;
+
+    fn scope() -> ExecScope {
+        let mut scope = ExecScope::new();
+        parser::definitions(
+            r#"
+            t = 0.5
+            u = 2.0 * t
+            l = line (pt 0.0 0.0) (pt 2.0 0.0)
+            P = pt_on l t
+            Q = pt_on (circ (pt 0.0 0.0) 1.0) 0.0
+            R = pt_on l u
+            S = pt_on l 1.0
+            "#,
+        )
+        .unwrap()
+        .exec(&mut scope)
+        .unwrap();
+        scope
+    }
+
+    #[test]
+    fn get_all() {
+        let mut scope = scope();
+
+        // Only P has a free parameter
+        let l = Line {
+            p1: Pt::new(0.0, 0.0),
+            p2: Pt::new(2.0, 0.0),
+        };
+        assert_eq!(
+            scope.get_all_pt_ons(),
+            HashMap::from([(
+                Ident::from("P"),
+                PtOn {
+                    object: l.into(),
+                    param: Ident::from("t"),
+                }
+            )])
+        );
+
+        scope.set(&Ident::from("t"), Value::from(0.25)).unwrap();
+        assert_eq!(
+            scope.get_item(&Ident::from("P")),
+            Some(Pt::new(0.5, 0.0).into())
+        );
+    }
+
+    #[test]
+    fn json() {
+        let scope = ExecScope::from_json(&scope().to_json()).unwrap();
+        assert_eq!(
+            scope.get_all_pt_ons().keys().collect::<Vec<_>>(),
+            vec![&Ident::from("P")]
+        );
+    }
+}
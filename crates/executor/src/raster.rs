@@ -0,0 +1,434 @@
+//! This module implements exporting ExecScope to png.
+//!
+//! It draws the same scene as the svg exporter with the same styling,
+//! so that images look alike in both formats.
+
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+use thiserror::Error;
+use tiny_skia::{
+    FillRule, LineCap, LineJoin, Paint, Path, PathBuilder, Pixmap, Stroke, StrokeDash, Transform,
+};
+use ttf_parser::{Face, OutlineBuilder};
+use types::{
+    core::{Circ, Ident, Line, Polyline, Pt, Value},
+    export::{ExportOptions, DEFAULT_DPI},
+    style::{Color, Style},
+};
+
+use crate::{
+    exec::ExecScope,
+    svg::{Item, Scene, LABEL_OFFSET, LABEL_SIZE, PT_RADIUS, STROKE_WIDTH},
+};
+
+/// Larger images are most likely mistakes in export options.
+const MAX_SIDE: u32 = 16384;
+
+#[derive(Debug, Error)]
+pub enum RasterError {
+    #[error("image is too large: {width}x{height}, max side is {MAX_SIDE}")]
+    TooLarge { width: u32, height: u32 },
+
+    #[error("failed to encode png")]
+    Encoding(#[from] png::EncodingError),
+}
+
+/// System fonts for labels; loading them is slow, so it's done once.
+static FONTS: Lazy<fontdb::Database> = Lazy::new(|| {
+    let mut db = fontdb::Database::new();
+    db.load_system_fonts();
+    db
+});
+
+impl ExecScope {
+    /// Renders the scope to png.
+    ///
+    /// Output size is scaled by `dpi / 96`, so that `dpi` does not change the proportions.
+    pub fn to_png(&self, options: &ExportOptions) -> Result<Vec<u8>, RasterError> {
+        scene_to_png(&self.scene(options), options)
+    }
+}
+
+pub(crate) fn scene_to_png(scene: &Scene, options: &ExportOptions) -> Result<Vec<u8>, RasterError> {
+    let [x, y, width, height] = scene.frame.view_box;
+    let scale = scene.frame.scale;
+
+    // Device pixels per svg unit
+    let density = options.dpi / DEFAULT_DPI;
+
+    let pixmap_width = (width * density).ceil() as u32;
+    let pixmap_height = (height * density).ceil() as u32;
+    if pixmap_width > MAX_SIDE || pixmap_height > MAX_SIDE {
+        return Err(RasterError::TooLarge {
+            width: pixmap_width,
+            height: pixmap_height,
+        });
+    }
+
+    // Is `None` only for zero sizes, which are checked above
+    let mut pixmap = Pixmap::new(pixmap_width.max(1), pixmap_height.max(1)).unwrap();
+
+    if let Some(background) = options.background {
+        pixmap.fill(to_skia_color(background));
+    }
+
+    let mut canvas = Canvas {
+        pixmap: &mut pixmap,
+        transform: Transform::from_row(
+            density as f32,
+            0.0,
+            0.0,
+            density as f32,
+            (-x * density) as f32,
+            (-y * density) as f32,
+        ),
+        scale,
+    };
+
+    for (_layer, items) in &scene.groups {
+        canvas.draw_items(items, &scene.labels);
+    }
+
+    encode(&pixmap, options.dpi)
+}
+
+/// Draws in svg units.
+struct Canvas<'a> {
+    pixmap: &'a mut Pixmap,
+
+    /// Svg units to device pixels.
+    transform: Transform,
+
+    /// Svg units per world unit.
+    scale: f64,
+}
+
+impl Canvas<'_> {
+    fn draw_items(&mut self, items: &[Item], labels: &HashMap<Ident, String>) {
+        for (_name, value, style) in items {
+            self.draw_value(value, style);
+        }
+
+        // Labels are drawn over all objects
+        for (name, value, style) in items {
+            if let Some(label) = labels.get(name) {
+                self.draw_label(value, label, style);
+            }
+        }
+    }
+
+    fn draw_value(&mut self, value: &Value, style: &Style) {
+        let color = style.color.unwrap_or(Color::BLACK);
+
+        match value {
+            Value::Pt(Some(pt)) => {
+                // Points are round caps of zero-length lines in svg, so the width is a diameter
+                let radius = style.width.unwrap_or(PT_RADIUS / 2.0);
+                let (x, y) = (*pt * self.scale).to_f64();
+                if let Some(path) = PathBuilder::from_circle(x as f32, y as f32, radius as f32) {
+                    self.fill(&path, color);
+                }
+            }
+
+            Value::Line(Some(Line { p1, p2 })) => {
+                let ((x1, y1), (x2, y2)) =
+                    ((*p1 * self.scale).to_f64(), (*p2 * self.scale).to_f64());
+                let mut pb = PathBuilder::new();
+                pb.move_to(x1 as f32, y1 as f32);
+                pb.line_to(x2 as f32, y2 as f32);
+                if let Some(path) = pb.finish() {
+                    self.stroke(&path, color, style);
+                }
+            }
+
+            Value::Circ(Some(Circ { o, r })) => {
+                let (x, y) = (*o * self.scale).to_f64();
+                let r = r.to_f64() * self.scale;
+                let Some(path) = PathBuilder::from_circle(x as f32, y as f32, r as f32) else {
+                    return;
+                };
+                if let Some(fill) = style.fill {
+                    self.fill(&path, fill);
+                }
+                self.stroke(&path, color, style);
+            }
+
+            Value::Polyline(Some(Polyline { pts })) => {
+                let mut pb = PathBuilder::new();
+                for (i, pt) in pts.iter().enumerate() {
+                    let (x, y) = (*pt * self.scale).to_f64();
+                    if i == 0 {
+                        pb.move_to(x as f32, y as f32);
+                    } else {
+                        pb.line_to(x as f32, y as f32);
+                    }
+                }
+                if let Some(path) = pb.finish() {
+                    self.stroke(&path, color, style);
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    fn draw_label(&mut self, value: &Value, label: &str, style: &Style) {
+        let Some((anchor, dir)) = value.label_anchor() else {
+            return;
+        };
+        let center = anchor * self.scale + dir * LABEL_OFFSET;
+        let color = style.color.unwrap_or(Color::BLACK);
+
+        // Labels are skipped, if there are no fonts
+        let Some(font) = FONTS.query(&fontdb::Query {
+            families: &[
+                fontdb::Family::SansSerif,
+                fontdb::Family::Name("DejaVu Sans"),
+                fontdb::Family::Name("Liberation Sans"),
+                fontdb::Family::Name("Noto Sans"),
+            ],
+            ..Default::default()
+        }) else {
+            return;
+        };
+
+        let path = FONTS.with_face_data(font, |data, index| {
+            let face = Face::parse(data, index).ok()?;
+            text_path(&face, label, center)
+        });
+
+        if let Some(path) = path.flatten() {
+            self.fill(&path, color);
+        }
+    }
+
+    fn fill(&mut self, path: &Path, color: Color) {
+        self.pixmap
+            .fill_path(path, &paint(color), FillRule::Winding, self.transform, None);
+    }
+
+    fn stroke(&mut self, path: &Path, color: Color, style: &Style) {
+        let stroke = Stroke {
+            width: style.width.unwrap_or(STROKE_WIDTH) as f32,
+            line_cap: LineCap::Round,
+            line_join: LineJoin::Round,
+            dash: style.dash.as_ref().and_then(|dash| {
+                // Svg repeats odd dash lists
+                let mut dash: Vec<f32> = dash.iter().map(|len| *len as f32).collect();
+                if dash.len() % 2 == 1 {
+                    dash.extend(dash.clone());
+                }
+                StrokeDash::new(dash, 0.0)
+            }),
+            ..Default::default()
+        };
+
+        self.pixmap
+            .stroke_path(path, &paint(color), &stroke, self.transform, None);
+    }
+}
+
+fn paint(color: Color) -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.set_color(to_skia_color(color));
+    paint.anti_alias = true;
+    paint
+}
+
+fn to_skia_color(Color { r, g, b, a }: Color) -> tiny_skia::Color {
+    tiny_skia::Color::from_rgba8(r, g, b, a)
+}
+
+/// Outlines of the text, centered at the point, as in svg with
+/// `text-anchor: middle` and `dominant-baseline: middle`.
+fn text_path(face: &Face, text: &str, center: Pt) -> Option<Path> {
+    let font_scale = LABEL_SIZE as f32 / face.units_per_em() as f32;
+
+    let glyphs: Vec<_> = text.chars().filter_map(|c| face.glyph_index(c)).collect();
+    let width: f32 = glyphs
+        .iter()
+        .map(|glyph| face.glyph_hor_advance(*glyph).unwrap_or(0) as f32 * font_scale)
+        .sum();
+
+    // Middle of lowercase letters is put at the center
+    let x_height = face
+        .x_height()
+        .map_or(face.ascender() as f32 / 2.0, |h| h as f32);
+
+    let mut builder = GlyphBuilder {
+        pb: PathBuilder::new(),
+        x: center.x.to_f64() as f32 - width / 2.0,
+        y: center.y.to_f64() as f32 + x_height * font_scale / 2.0,
+        scale: font_scale,
+    };
+
+    for glyph in glyphs {
+        face.outline_glyph(glyph, &mut builder);
+        builder.x += face.glyph_hor_advance(glyph).unwrap_or(0) as f32 * font_scale;
+    }
+
+    builder.pb.finish()
+}
+
+/// Puts glyph outlines at the pen position; font's y axis points up.
+struct GlyphBuilder {
+    pb: PathBuilder,
+    x: f32,
+    y: f32,
+    scale: f32,
+}
+
+impl GlyphBuilder {
+    fn map(&self, x: f32, y: f32) -> (f32, f32) {
+        (self.x + x * self.scale, self.y - y * self.scale)
+    }
+}
+
+impl OutlineBuilder for GlyphBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.map(x, y);
+        self.pb.move_to(x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.map(x, y);
+        self.pb.line_to(x, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (x1, y1) = self.map(x1, y1);
+        let (x, y) = self.map(x, y);
+        self.pb.quad_to(x1, y1, x, y);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (x1, y1) = self.map(x1, y1);
+        let (x2, y2) = self.map(x2, y2);
+        let (x, y) = self.map(x, y);
+        self.pb.cubic_to(x1, y1, x2, y2, x, y);
+    }
+
+    fn close(&mut self) {
+        self.pb.close();
+    }
+}
+
+fn encode(pixmap: &Pixmap, dpi: f64) -> Result<Vec<u8>, RasterError> {
+    // Png stores premultiplied colors as is
+    let data: Vec<u8> = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let pixel = pixel.demultiply();
+            [pixel.red(), pixel.green(), pixel.blue(), pixel.alpha()]
+        })
+        .collect();
+
+    let mut png = Vec::new();
+
+    let mut encoder = png::Encoder::new(&mut png, pixmap.width(), pixmap.height());
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let pixels_per_meter = (dpi / 0.0254).round() as u32;
+    encoder.set_pixel_dims(Some(png::PixelDimensions {
+        xppu: pixels_per_meter,
+        yppu: pixels_per_meter,
+        unit: png::Unit::Meter,
+    }));
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&data)?;
+    writer.finish()?;
+
+    Ok(png)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::exec::Exec;
+
+    use super::*;
+
+    fn decode(png: &[u8]) -> (png::OutputInfo, Vec<u8>, Option<png::PixelDimensions>) {
+        let mut reader = png::Decoder::new(png).read_info().unwrap();
+        let dims = reader.info().pixel_dims;
+        let mut data = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut data).unwrap();
+        (info, data, dims)
+    }
+
+    #[test]
+    fn png() {
+        let mut scope = ExecScope::new();
+        parser::definitions(
+            r#"
+            A = pt 0.0 0.0
+            B = pt 2.0 2.0
+            l = line A B
+            "#,
+        )
+        .unwrap()
+        .exec(&mut scope)
+        .unwrap();
+
+        let mut options = ExportOptions::default();
+        options
+            .apply("viewport: 0 0 2 2; width: 100; background: white")
+            .unwrap();
+
+        let (info, data, dims) = decode(&scope.to_png(&options).unwrap());
+        assert_eq!((info.width, info.height), (100, 100));
+        assert_eq!(dims.unwrap().xppu, 3780);
+
+        let pixel = |x: u32, y: u32| {
+            let i = 4 * (y * info.width + x) as usize;
+            [data[i], data[i + 1], data[i + 2], data[i + 3]]
+        };
+        assert_eq!(pixel(90, 90), [255, 255, 255, 255]);
+        // Y axis points up, so the line goes from the bottom left corner to the top right one
+        assert!(pixel(49, 50)[0] < 128);
+        assert!(pixel(89, 10)[0] < 128);
+        assert!(pixel(9, 90)[0] < 128);
+        assert_eq!(pixel(10, 10), [255, 255, 255, 255]);
+
+        // Dpi scales the image
+        options.apply("dpi: 192").unwrap();
+        let (info, _data, dims) = decode(&scope.to_png(&options).unwrap());
+        assert_eq!((info.width, info.height), (200, 200));
+        assert_eq!(dims.unwrap().xppu, 7559);
+
+        options.apply("width: 100000").unwrap();
+        assert!(matches!(
+            scope.to_png(&options),
+            Err(RasterError::TooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn labels() {
+        let mut scope = ExecScope::new();
+        parser::definitions("A = pt 0.0 0.0")
+            .unwrap()
+            .exec(&mut scope)
+            .unwrap();
+
+        let options = ExportOptions::default();
+        let count_dark = |png: &[u8]| {
+            let (_info, data, _dims) = decode(png);
+            data.chunks(4).filter(|pixel| pixel[3] > 128).count()
+        };
+
+        let without_label = count_dark(&scope.to_png(&options).unwrap());
+        scope.set_show_labels(true);
+        let with_label = count_dark(&scope.to_png(&options).unwrap());
+
+        // Labels need a system font
+        if FONTS.is_empty() {
+            assert_eq!(with_label, without_label);
+        } else {
+            assert!(with_label > without_label);
+        }
+    }
+}
@@ -0,0 +1,116 @@
+//! Sliders are ranges of free numeric items, that are changed by dragging in the gui.
+
+use std::collections::HashMap;
+
+use types::{
+    core::{Ident, ValueType},
+    slider::Slider,
+};
+
+use crate::{
+    exec::{ExecError, ExecResult, ExecScope},
+    node::NodeInnerKind,
+};
+
+impl ExecScope {
+    pub fn get_all_sliders(&self) -> HashMap<Ident, Slider> {
+        self.sliders.clone()
+    }
+
+    /// Puts a slider on the item or removes it with `None`.
+    ///
+    /// The item's value is not changed, even if it's out of the range.
+    pub fn set_slider(&mut self, name: Ident, slider: Option<Slider>) -> ExecResult {
+        let node = self
+            .get_node(&name)
+            .ok_or_else(|| ExecError::UndefinedVariable(name.clone()))?;
+
+        let Some(slider) = slider else {
+            self.sliders.remove(&name);
+            return Ok(());
+        };
+
+        if !matches!(node.inner().kind, NodeInnerKind::Value(_)) {
+            return Err(ExecError::SliderOnNonFree(name));
+        }
+
+        match node.value_type() {
+            ValueType::Real => {}
+            ValueType::Int => {
+                let whole = [slider.min, slider.max, slider.step.unwrap_or(1.0)]
+                    .iter()
+                    .all(|v| v.fract() == 0.0);
+                if !whole {
+                    return Err(ExecError::SliderNotWhole(name));
+                }
+            }
+            _ => return Err(ExecError::SliderOnNonFree(name)),
+        }
+
+        self.sliders.insert(name, slider);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::exec::Exec;
+
+    use super::*;
+
+    #[test]
+    fn set() {
+        let mut scope = ExecScope::new();
+        parser::definitions(
+            r#"
+            r = 50.0
+            n = 3
+            d = 2.0 * r
+            A = pt 0.0 0.0
+            "#,
+        )
+        .unwrap()
+        .exec(&mut scope)
+        .unwrap();
+
+        let [r, n, d, a] = ["r", "n", "d", "A"].map(Ident::from);
+        let slider = |decls| {
+            let mut slider = Slider::default();
+            slider.apply(decls).unwrap();
+            slider
+        };
+
+        scope
+            .set_slider(r.clone(), Some(slider("step: 0.5")))
+            .unwrap();
+        scope
+            .set_slider(n.clone(), Some(slider("min: 3; max: 12")))
+            .unwrap();
+        assert_eq!(scope.get_all_sliders().len(), 2);
+
+        assert!(matches!(
+            scope.set_slider(n.clone(), Some(slider("step: 0.5"))),
+            Err(ExecError::SliderNotWhole(_))
+        ));
+        assert!(matches!(
+            scope.set_slider(d.clone(), Some(slider("max: 10"))),
+            Err(ExecError::SliderOnNonFree(_))
+        ));
+        assert!(matches!(
+            scope.set_slider(a, Some(slider("max: 10"))),
+            Err(ExecError::SliderOnNonFree(_))
+        ));
+        assert!(matches!(
+            scope.set_slider("x".into(), None),
+            Err(ExecError::UndefinedVariable(_))
+        ));
+
+        // Sliders are saved
+        let mut scope = ExecScope::from_json(&scope.to_json()).unwrap();
+        assert_eq!(scope.get_all_sliders()[&r], slider("step: 0.5"));
+
+        scope.set_slider(n, None).unwrap();
+        scope.rm(r).unwrap();
+        assert!(scope.get_all_sliders().is_empty());
+    }
+}
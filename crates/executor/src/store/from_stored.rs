@@ -4,11 +4,13 @@ use std::{
 };
 
 use crate::{
-    cexpr::{CExpr, CExprInner, CExprInnerKind, FuncCallCExpr, IfCExpr, IfCExprCase},
+    cexpr::{CExpr, CExprInner, CExprInnerKind, FuncCallCExpr, IfCExpr, IfCExprCase, LocusCExpr},
+    exact::Arithmetic,
     exec::{ExecError, ExecScope},
     function::{CustomFunction, Function, FunctionInner, FunctionInnerKind},
     node::Node,
     store::LoadError,
+    tolerance::Tolerance,
 };
 
 use super::models::*;
@@ -16,6 +18,8 @@ use super::models::*;
 impl ExecScope {
     pub(super) fn from_stored(stored_exec_scope: StoredExecScope) -> Result<ExecScope, ExecError> {
         let mut scope = FromStoredScope {
+            tolerance: Tolerance::new(stored_exec_scope.tolerance),
+            arithmetic: Arithmetic::new(stored_exec_scope.exact),
             stored_exec_scope,
             nodes: HashMap::new(),
             cexprs: HashMap::new(),
@@ -38,7 +42,18 @@ impl ExecScope {
                 .is_none());
         }
 
-        Ok(ExecScope { funcs, nodes })
+        Ok(ExecScope {
+            funcs,
+            nodes,
+            styles: scope.stored_exec_scope.styles,
+            sliders: scope.stored_exec_scope.sliders,
+            show_labels: scope.stored_exec_scope.show_labels,
+            tolerance: scope.tolerance,
+            arithmetic: scope.arithmetic,
+            layers: scope.stored_exec_scope.layers,
+            libs: scope.stored_exec_scope.libs,
+            lib_dir: None,
+        })
     }
 }
 
@@ -50,6 +65,12 @@ struct FromStoredScope {
     cexprs: HashMap<StoredCExprId, CExpr>,
     funcs: HashMap<StoredFunctionId, Function>,
 
+    /// Builtins, that use the tolerance, are resolved to it's functions
+    tolerance: Tolerance,
+
+    /// Same for casts to reals
+    arithmetic: Arithmetic,
+
     /// Only for checking for circular dependencies
     processing: HashSet<Id>,
 }
@@ -150,6 +171,19 @@ impl CExpr {
                 default_case_value: default_case_value
                     .map_or(Ok(None), |id| CExpr::from_stored(id, scope).map(Some))?,
             }),
+            StoredCExprKind::Locus {
+                body,
+                param,
+                start,
+                end,
+                samples,
+            } => CExprInnerKind::Locus(LocusCExpr {
+                body: CExpr::from_stored(body, scope)?,
+                param,
+                start,
+                end,
+                samples: CExpr::from_stored(samples, scope)?,
+            }),
         };
 
         let cexpr = CExpr::from(CExprInner {
@@ -189,18 +223,23 @@ impl Function {
             .take()
             .expect("only initialized OnceLocks are deserialized")
         {
-            StoredFunctionKind::Builtin(sign) => match Function::get_builtin(&sign) {
-                Some(func) => {
-                    assert!(scope.funcs.insert(id, func.clone()).is_none());
-                    func
-                }
-                None => {
-                    return Err(LoadError::CorruptedData {
-                        msg: format!("undefined builtin function: {sign}"),
+            StoredFunctionKind::Builtin(sign) => {
+                match Function::get_builtin(&sign)
+                    .or_else(|| scope.tolerance.get_func(&sign))
+                    .or_else(|| scope.arithmetic.get_func(&sign))
+                {
+                    Some(func) => {
+                        assert!(scope.funcs.insert(id, func.clone()).is_none());
+                        func
+                    }
+                    None => {
+                        return Err(LoadError::CorruptedData {
+                            msg: format!("undefined builtin function: {sign}"),
+                        }
+                        .into())
                     }
-                    .into())
                 }
-            },
+            }
             StoredFunctionKind::CExpr { arg_names, body } => {
                 let mut func = Function::from(FunctionInner {
                     sign,
@@ -32,7 +32,7 @@ impl ExecScope {
 
 #[cfg(test)]
 mod test {
-    use types::core::Ident;
+    use types::core::{Ident, Pt};
 
     use crate::exec::{Exec, ExecScope};
 
@@ -78,4 +78,35 @@ mod test {
         let json = create_json();
         check_json(&json);
     }
+
+    #[test]
+    fn transform_to_json_and_back() {
+        let mut scope = ExecScope::new();
+        parser::definitions(
+            r#"
+            c = pt 1.0 1.0
+            t = (rotate c 0.5) * (scale c 2.0)
+            twice x:transform -> transform = x * x
+            P = apply (twice t) (pt 2.0 1.0)
+            "#,
+        )
+        .unwrap()
+        .exec(&mut scope)
+        .unwrap();
+
+        let mut loaded = ExecScope::from_json(&scope.to_json()).unwrap();
+        for name in ["t", "P"] {
+            let name = Ident::from(name);
+            assert_eq!(loaded.get_item(&name), scope.get_item(&name));
+        }
+
+        // Transform depends on c
+        loaded
+            .set(&Ident::from("c"), Pt::new(2.0, 1.0).into())
+            .unwrap();
+        assert_eq!(
+            loaded.get_item(&Ident::from("P")).unwrap(),
+            Pt::new(2.0, 1.0).into()
+        );
+    }
 }
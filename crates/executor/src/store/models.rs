@@ -13,8 +13,13 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use types::{
     core::{Ident, Value, ValueType},
     lang::FunctionSignature,
+    layer::Layer,
+    slider::Slider,
+    style::Style,
 };
 
+use crate::{import::Library, tolerance::DEFAULT_TOLERANCE};
+
 pub(super) type Id = u64;
 pub(super) type StoredCExprId = Id;
 pub(super) type StoredFunctionId = Id;
@@ -32,6 +37,35 @@ pub(super) struct StoredExecScope {
 
     #[serde_as(as = "Vec<(_, _)>")]
     pub(super) sign_to_func: HashMap<FunctionSignature, StoredNodeId>,
+
+    /// Is missing in files saved before styles were introduced.
+    #[serde(default)]
+    #[serde_as(as = "Vec<(_, _)>")]
+    pub(super) styles: HashMap<Ident, Style>,
+
+    #[serde(default)]
+    #[serde_as(as = "Vec<(_, _)>")]
+    pub(super) sliders: HashMap<Ident, Slider>,
+
+    #[serde(default)]
+    pub(super) show_labels: bool,
+
+    #[serde(default = "default_tolerance")]
+    pub(super) tolerance: f64,
+
+    #[serde(default)]
+    pub(super) exact: bool,
+
+    #[serde(default)]
+    pub(super) layers: Vec<Layer>,
+
+    /// Is missing in files saved before libraries were introduced.
+    #[serde(default)]
+    pub(super) libs: Vec<Library>,
+}
+
+fn default_tolerance() -> f64 {
+    DEFAULT_TOLERANCE
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -56,6 +90,13 @@ pub(super) enum StoredCExprKind {
         )>,
         default_case_value: Option<StoredCExprId>,
     },
+    Locus {
+        body: StoredCExprId,
+        param: Ident,
+        start: f64,
+        end: f64,
+        samples: StoredCExprId,
+    },
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
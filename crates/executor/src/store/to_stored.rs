@@ -4,7 +4,7 @@ use std::{
 };
 
 use crate::{
-    cexpr::{CExpr, CExprInner, CExprInnerKind, FuncCallCExpr, IfCExpr},
+    cexpr::{CExpr, CExprInner, CExprInnerKind, FuncCallCExpr, IfCExpr, LocusCExpr},
     exec::ExecScope,
     function::{CustomFunction, Function, FunctionInner, FunctionInnerKind},
     node::{CExprNode, Node, NodeInnerKind},
@@ -20,6 +20,13 @@ impl ExecScope {
             funcs: HashMap::new(),
             name_to_node: HashMap::new(),
             sign_to_func: HashMap::new(),
+            styles: self.styles.clone(),
+            sliders: self.sliders.clone(),
+            show_labels: self.show_labels,
+            tolerance: self.tolerance.get(),
+            exact: self.arithmetic.is_exact(),
+            layers: self.layers.clone(),
+            libs: self.libs.clone(),
         };
 
         let mut scope = ToStoredScope {
@@ -140,6 +147,19 @@ impl CExpr {
                     .collect(),
                 default_case_value: default_case_value.map(|case| case.to_stored(scope)),
             },
+            CExprInnerKind::Locus(LocusCExpr {
+                body,
+                param,
+                start,
+                end,
+                samples,
+            }) => StoredCExprKind::Locus {
+                body: body.to_stored(scope),
+                param,
+                start,
+                end,
+                samples: samples.to_stored(scope),
+            },
         };
 
         let stored_cexpr = StoredCExpr {
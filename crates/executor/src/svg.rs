@@ -1,76 +1,419 @@
 //! This module implements exporting ExecScope to svg.
+//!
+//! Raster exporters draw the same [Scene] with the same styling.
 
 use core::f64;
+use std::collections::HashMap;
 
 use indoc::indoc;
 use svg::{
-    node::element::{Circle as SvgCircle, Line as SvgLine, Style},
-    Document,
+    node::element::{
+        Circle as SvgCircle, Group, Line as SvgLine, Polyline as SvgPolyline, Rectangle as SvgRect,
+        Style as SvgStyle, Text as SvgText,
+    },
+    Document, Node,
+};
+use types::{
+    core::{Circ, Ident, Line, Polyline, Pt, Value},
+    export::{ExportOptions, Rect},
+    layer::Layer,
+    style::{Color, Style},
 };
-use types::core::{Circ, Line, Pt, Value};
 
 use crate::exec::ExecScope;
 
-const STROKE_WIDTH: f64 = 1.0;
-const PT_RADIUS: f64 = 4.0;
+pub(crate) const STROKE_WIDTH: f64 = 1.0;
+/// Diameter of points, despite the name.
+pub(crate) const PT_RADIUS: f64 = 4.0;
 const VIEWBOX_PADDING: f64 = 10.0;
+pub(crate) const LABEL_SIZE: f64 = 20.0;
+/// Distance between an object and it's label's center
+pub(crate) const LABEL_OFFSET: f64 = 16.0;
+
+pub(crate) const SCALE_ATTR: &str = "data-scale";
+pub(crate) const Y_AXIS_ATTR: &str = "data-y-axis";
 
-const PT_CLASS: &str = "pt";
+pub(crate) const PT_CLASS: &str = "pt";
 const LINE_CLASS: &str = "line";
 const CIRC_CLASS: &str = "circ";
+const POLYLINE_CLASS: &str = "polyline";
+const LABEL_CLASS: &str = "label";
+const LAYER_CLASS: &str = "layer";
+const BACKGROUND_CLASS: &str = "background";
+
+/// Where the drawing is put in the svg.
+#[derive(Clone)]
+pub(crate) struct Frame {
+    /// (min_x, min_y, width, height) in svg units.
+    pub(crate) view_box: [f64; 4],
+
+    /// Svg units per world unit.
+    pub(crate) scale: f64,
+}
+
+impl Frame {
+    /// The visible part of the plane in world units (with y possibly flipped).
+    fn world_rect(&self) -> Rect {
+        let [x, y, w, h] = self.view_box.map(|v| v / self.scale);
+        Rect::new(Pt::new(x, y), Pt::new(x + w, y + h))
+    }
+}
+
+/// A visible item with it's style; coordinates are in world units with y possibly flipped.
+pub(crate) type Item = (Ident, Value, Style);
+
+/// Everything, that is exported, in drawing order.
+pub(crate) struct Scene<'a> {
+    pub(crate) frame: Frame,
+
+    /// Items without a layer go first, then visible layers in order of creation.
+    pub(crate) groups: Vec<(Option<&'a Layer>, Vec<Item>)>,
+
+    pub(crate) labels: HashMap<Ident, String>,
+}
 
 impl ExecScope {
-    pub fn to_svg(&self) -> String {
-        let (bounds, scale) = self.get_bounds_and_scale();
-        let doc = self.build_doc(bounds, scale);
+    pub fn to_svg(&self, options: &ExportOptions) -> String {
+        let scene = self.scene(options);
+        let doc = build_doc(&scene, options);
         doc.to_string()
     }
 
-    /// Svg does not seem to like float numbers,
-    /// so just scaling everything.
-    fn get_bounds_and_scale(&self) -> ([f64; 4], f64) {
-        let mut bounds = None;
+    pub(crate) fn scene(&self, options: &ExportOptions) -> Scene<'_> {
+        let items = self.visible_items(options);
+        let frame = self.get_frame(&items, options);
+        self.scene_in_frame(items, frame, options)
+    }
 
-        for value in self.get_all_items().values() {
-            bounds = value.update_bounds(bounds);
+    /// Scene of the given items, that are put into the given frame,
+    /// e.g. into the one, that fits all frames of an animation.
+    pub(crate) fn scene_in_frame(
+        &self,
+        items: Vec<Item>,
+        frame: Frame,
+        options: &ExportOptions,
+    ) -> Scene<'_> {
+        let items: Vec<_> = if options.clip_lines {
+            let Rect { min, max } = frame.world_rect();
+            items
+                .into_iter()
+                .filter_map(|(name, value, style)| match value {
+                    Value::Line(Some(line)) => {
+                        Some((name, Value::from(line.clip(min, max)?), style))
+                    }
+                    value => Some((name, value, style)),
+                })
+                .collect()
+        } else {
+            items
+        };
+
+        // Items without a layer go first, sorted, so that the output is stable
+        let (mut free_items, mut layered_items): (Vec<_>, Vec<_>) = items
+            .into_iter()
+            .partition(|(name, _value, _style)| self.layer_of(name).is_none());
+        free_items.sort_by(|(a, _, _), (b, _, _)| a.0.cmp(&b.0));
+
+        let mut groups = vec![(None, free_items)];
+
+        for layer in self.layers.iter().filter(|layer| !layer.hidden) {
+            let items: Vec<_> = layer
+                .items
+                .iter()
+                .filter_map(|name| {
+                    let idx = layered_items.iter().position(|(n, _, _)| n == name)?;
+                    Some(layered_items.swap_remove(idx))
+                })
+                .collect();
+
+            groups.push((Some(layer), items));
+        }
+
+        Scene {
+            frame,
+            groups,
+            labels: self.get_all_labels(),
         }
+    }
+
+    /// Svg does not seem to like float numbers,
+    /// so just scaling everything.
+    pub(crate) fn get_frame(&self, items: &[Item], options: &ExportOptions) -> Frame {
+        let (rect, padding) = match options.viewport {
+            Some(Rect { min, max }) => {
+                let rect = if options.y_up {
+                    Rect::new(flip_y(min), flip_y(max))
+                } else {
+                    Rect { min, max }
+                };
+
+                // Explicit viewport is exported exactly
+                (rect, 0.0)
+            }
+            None => {
+                let mut bounds = None;
+                for (_name, value, _style) in items {
+                    bounds = value.update_bounds(bounds);
+                }
+                let [min_x, min_y, max_x, max_y] = bounds.unwrap_or([0.0; 4]);
+                let rect = Rect {
+                    min: Pt::new(min_x, min_y),
+                    max: Pt::new(max_x, max_y),
+                };
+
+                // Labels stick out of objects
+                let padding = if self.get_all_labels().is_empty() {
+                    VIEWBOX_PADDING
+                } else {
+                    VIEWBOX_PADDING + LABEL_OFFSET + LABEL_SIZE
+                };
+
+                (rect, padding)
+            }
+        };
 
-        let [min_x, min_y, max_x, max_y] = bounds.unwrap_or([0.0; 4]);
-        let wid = max_x - min_x;
-        let hei = max_y - min_y;
+        let (wid, hei) = (rect.width(), rect.height());
         let res = [wid, hei]
             .into_iter()
             .filter(|v| *v != 0.0)
             .min_by(|x, y| x.total_cmp(y))
             .unwrap_or(1.0);
-        let scale = 1000.0 / res;
-
-        (
-            [
-                scale * min_x - VIEWBOX_PADDING,
-                scale * min_y - VIEWBOX_PADDING,
-                scale * wid + 2.0 * VIEWBOX_PADDING,
-                scale * hei + 2.0 * VIEWBOX_PADDING,
-            ],
+
+        // Scale, that fits a side of the rect into the given size
+        let fit = |size: f64, side: f64| {
+            let side = if side == 0.0 { res } else { side };
+            (size - 2.0 * padding).max(1.0) / side
+        };
+        let scale = match (options.width, options.height) {
+            (Some(width), Some(height)) => fit(width, wid).min(fit(height, hei)),
+            (Some(width), None) => fit(width, wid),
+            (None, Some(height)) => fit(height, hei),
+            (None, None) => 1000.0 / res,
+        };
+
+        let width = options.width.unwrap_or(scale * wid + 2.0 * padding);
+        let height = options.height.unwrap_or(scale * hei + 2.0 * padding);
+
+        // The drawing is centered, if the size is not proportional to the rect
+        let (cx, cy) = ((rect.min + rect.max) * scale / 2.0).to_f64();
+
+        Frame {
+            view_box: [cx - width / 2.0, cy - height / 2.0, width, height],
             scale,
-        )
+        }
+    }
+
+    /// Items with their styles, except for hidden ones.
+    ///
+    /// Svg's y axis points down, so items are flipped for the y-up convention.
+    pub(crate) fn visible_items(&self, options: &ExportOptions) -> Vec<Item> {
+        self.get_all_items()
+            .into_iter()
+            .filter(|(name, _value)| !self.is_hidden(name))
+            .map(|(name, value)| {
+                let style = self.styles.get(&name).cloned().unwrap_or_default();
+                let value = if options.y_up {
+                    flip_value(value)
+                } else {
+                    value
+                };
+                (name, value, style)
+            })
+            .collect()
     }
+}
+
+fn build_doc(scene: &Scene, options: &ExportOptions) -> Document {
+    let doc = build_empty_doc(&scene.frame, options);
+    populate_doc_with_scene(doc, scene, true)
+}
 
-    fn build_doc(&self, bounds: [f64; 4], scale: f64) -> Document {
-        let mut doc = Document::new()
-            .add(svg_style())
-            .set("viewBox", <(f64, f64, f64, f64)>::from(bounds));
+/// Document with the frame, the css and the background, but without any items.
+pub(crate) fn build_empty_doc(frame: &Frame, options: &ExportOptions) -> Document {
+    let [x, y, width, height] = frame.view_box;
+    let scale = frame.scale;
+
+    // Scale and y axis allow to import the drawing back in the plane's coordinates
+    let mut doc = Document::new()
+        .set("viewBox", (num(x), num(y), width, height))
+        .set("width", width)
+        .set("height", height)
+        .set(SCALE_ATTR, scale)
+        .set(Y_AXIS_ATTR, if options.y_up { "up" } else { "down" })
+        .add(svg_style());
+
+    if let Some(background) = options.background {
+        let rect = SvgRect::new()
+            .set("x", num(x))
+            .set("y", num(y))
+            .set("width", width)
+            .set("height", height);
+        let style = Style {
+            fill: Some(background),
+            ..Default::default()
+        };
+        doc = doc.add(styled(rect, BACKGROUND_CLASS, &style));
+    }
+
+    doc
+}
 
-        for value in self.get_all_items().values() {
-            doc = value.populate_doc(doc, scale);
+/// Adds the scene's items to the node.
+///
+/// Layer ids are unique only within a scene, so they can be left out.
+pub(crate) fn populate_doc_with_scene<N: Node>(mut doc: N, scene: &Scene, layer_ids: bool) -> N {
+    let scale = scene.frame.scale;
+
+    for (layer, items) in &scene.groups {
+        match layer {
+            None => doc = populate_doc_with_items(doc, items, &scene.labels, scale),
+
+            // Every layer is a group, so that it can be edited as a whole in other programs
+            Some(layer) => {
+                let mut group = Group::new().set("class", LAYER_CLASS);
+                if layer_ids {
+                    group = group.set("id", layer.name.to_string());
+                }
+                doc.append(populate_doc_with_items(group, items, &scene.labels, scale));
+            }
         }
+    }
 
-        doc
+    doc
+}
+
+fn populate_doc_with_items<N: Node>(
+    mut doc: N,
+    items: &[Item],
+    labels: &HashMap<Ident, String>,
+    scale: f64,
+) -> N {
+    for (_name, value, style) in items {
+        doc = value.populate_doc(doc, scale, style);
+    }
+
+    // Labels are drawn over all objects
+    for (name, value, style) in items {
+        if let Some(label) = labels.get(name) {
+            doc = populate_doc_with_label(doc, value, scale, label, style);
+        }
+    }
+
+    doc
+}
+
+pub(crate) fn flip_y(Pt { x, y }: Pt) -> Pt {
+    Pt { x, y: -y }
+}
+
+/// Drops the sign of negative zero, that [flip_y] gives for points on the x axis.
+fn num(x: f64) -> f64 {
+    if x == 0.0 {
+        0.0
+    } else {
+        x
     }
 }
 
-fn svg_style() -> Style {
-    Style::new(format!(
+pub(crate) fn flip_value(value: Value) -> Value {
+    match value {
+        Value::Pt(Some(pt)) => flip_y(pt).into(),
+        Value::Line(Some(Line { p1, p2 })) => Line {
+            p1: flip_y(p1),
+            p2: flip_y(p2),
+        }
+        .into(),
+        Value::Circ(Some(Circ { o, r })) => Circ { o: flip_y(o), r }.into(),
+        Value::Polyline(Some(Polyline { pts })) => {
+            Polyline::new(pts.into_iter().map(flip_y).collect()).into()
+        }
+        value => value,
+    }
+}
+
+fn populate_doc_with_label<N: Node>(
+    mut doc: N,
+    value: &Value,
+    scale: f64,
+    label: &str,
+    style: &Style,
+) -> N {
+    let Some((anchor, dir)) = value.label_anchor() else {
+        return doc;
+    };
+    let (x, y) = (anchor * scale + dir * LABEL_OFFSET).to_f64();
+
+    // Labels are colored as their objects
+    let style = Style {
+        color: style.color,
+        ..Default::default()
+    };
+
+    let node = SvgText::new(label).set("x", num(x)).set("y", num(y));
+
+    doc.append(styled(node, LABEL_CLASS, &style));
+    doc
+}
+
+/// Inline css for the item's style, which overrides [svg_style].
+///
+/// Points are filled with their color and have width as radius.
+/// Fill is used only for circles and the background.
+fn inline_style(style: &Style, class: &str) -> Option<String> {
+    fn paint(decls: &mut Vec<String>, property: &str, color: Color) {
+        let Color { r, g, b, a } = color;
+        decls.push(format!("{property}: rgb({r}, {g}, {b})"));
+        if a != 255 {
+            decls.push(format!("{property}-opacity: {}", a as f64 / 255.0));
+        }
+    }
+
+    let mut decls = Vec::new();
+
+    if let Some(color) = style.color {
+        if class != LABEL_CLASS {
+            paint(&mut decls, "stroke", color);
+        }
+        if class == PT_CLASS || class == LABEL_CLASS {
+            paint(&mut decls, "fill", color);
+        }
+    }
+
+    if let Some(width) = style.width {
+        let width = if class == PT_CLASS {
+            2.0 * width
+        } else {
+            width
+        };
+        decls.push(format!("stroke-width: {width}"));
+    }
+
+    if let Some(dash) = style.dash.as_ref().filter(|_| class != PT_CLASS) {
+        let dash: Vec<_> = dash.iter().map(f64::to_string).collect();
+        decls.push(format!("stroke-dasharray: {}", dash.join(" ")));
+    }
+
+    if let Some(fill) = style
+        .fill
+        .filter(|_| class == CIRC_CLASS || class == BACKGROUND_CLASS)
+    {
+        paint(&mut decls, "fill", fill);
+    }
+
+    (!decls.is_empty()).then(|| decls.join("; "))
+}
+
+/// Sets the class and the inline style of an element.
+fn styled<T: Node>(mut node: T, class: &str, style: &Style) -> T {
+    node.assign("class", class);
+    if let Some(inline_style) = inline_style(style, class) {
+        node.assign("style", inline_style);
+    }
+    node
+}
+
+fn svg_style() -> SvgStyle {
+    SvgStyle::new(format!(
         indoc!(
             r"
             .{line} {{
@@ -92,11 +435,30 @@ fn svg_style() -> Style {
                 stroke: black;
                 stroke-width: {stroke_width};
             }}
+
+            .{polyline} {{
+                fill: none;
+                stroke: black;
+                stroke-width: {stroke_width};
+                stroke-linecap: round;
+                stroke-linejoin: round;
+            }}
+
+            .{label} {{
+                fill: black;
+                font-family: sans-serif;
+                font-size: {label_size}px;
+                text-anchor: middle;
+                dominant-baseline: middle;
+            }}
         "
         ),
         line = LINE_CLASS,
         pt = PT_CLASS,
         circ = CIRC_CLASS,
+        polyline = POLYLINE_CLASS,
+        label = LABEL_CLASS,
+        label_size = LABEL_SIZE,
         pt_radius = PT_RADIUS,
         stroke_width = STROKE_WIDTH,
     ))
@@ -108,7 +470,7 @@ trait ToSvg {
     // [bounds]: (min_x, min_y, max_x, max_y)
     fn update_bounds(&self, bounds: Bounds) -> Bounds;
 
-    fn populate_doc(&self, doc: Document, scale: f64) -> Document;
+    fn populate_doc<N: Node>(&self, doc: N, scale: f64, style: &Style) -> N;
 }
 
 impl ToSvg for Value {
@@ -117,15 +479,17 @@ impl ToSvg for Value {
             Value::Pt(Some(pt)) => pt.update_bounds(bounds),
             Value::Line(Some(line)) => line.update_bounds(bounds),
             Value::Circ(Some(circ)) => circ.update_bounds(bounds),
+            Value::Polyline(Some(polyline)) => polyline.update_bounds(bounds),
             _ => bounds,
         }
     }
 
-    fn populate_doc(&self, doc: Document, scale: f64) -> Document {
+    fn populate_doc<N: Node>(&self, doc: N, scale: f64, style: &Style) -> N {
         match self {
-            Value::Pt(Some(pt)) => pt.populate_doc(doc, scale),
-            Value::Line(Some(line)) => line.populate_doc(doc, scale),
-            Value::Circ(Some(circ)) => circ.populate_doc(doc, scale),
+            Value::Pt(Some(pt)) => pt.populate_doc(doc, scale, style),
+            Value::Line(Some(line)) => line.populate_doc(doc, scale, style),
+            Value::Circ(Some(circ)) => circ.populate_doc(doc, scale, style),
+            Value::Polyline(Some(polyline)) => polyline.populate_doc(doc, scale, style),
             _ => doc,
         }
     }
@@ -133,7 +497,7 @@ impl ToSvg for Value {
 
 impl ToSvg for Pt {
     fn update_bounds(&self, bounds: Bounds) -> Bounds {
-        let Pt { x, y } = *self;
+        let (x, y) = self.to_f64();
         Some(match bounds {
             Some([min_x, min_y, max_x, max_y]) => {
                 [min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y)]
@@ -142,19 +506,17 @@ impl ToSvg for Pt {
         })
     }
 
-    fn populate_doc(&self, doc: Document, scale: f64) -> Document {
-        let Pt { mut x, mut y } = self;
-        x *= scale;
-        y *= scale;
+    fn populate_doc<N: Node>(&self, mut doc: N, scale: f64, style: &Style) -> N {
+        let (x, y) = (*self * scale).to_f64();
 
         let node = SvgLine::new()
-            .set("class", PT_CLASS)
-            .set("x1", x)
-            .set("y1", y)
-            .set("x2", x)
-            .set("y2", y);
+            .set("x1", num(x))
+            .set("y1", num(y))
+            .set("x2", num(x))
+            .set("y2", num(y));
 
-        doc.add(node)
+        doc.append(styled(node, PT_CLASS, style));
+        doc
     }
 }
 
@@ -164,20 +526,17 @@ impl ToSvg for Line {
         self.p2.update_bounds(bounds)
     }
 
-    fn populate_doc(&self, doc: Document, scale: f64) -> Document {
-        let Line {
-            p1: Pt { x: x1, y: y1 },
-            p2: Pt { x: x2, y: y2 },
-        } = self;
+    fn populate_doc<N: Node>(&self, mut doc: N, scale: f64, style: &Style) -> N {
+        let ((x1, y1), (x2, y2)) = (self.p1.to_f64(), self.p2.to_f64());
 
         let node = SvgLine::new()
-            .set("class", LINE_CLASS)
-            .set("x1", scale * x1)
-            .set("y1", scale * y1)
-            .set("x2", scale * x2)
-            .set("y2", scale * y2);
+            .set("x1", num(scale * x1))
+            .set("y1", num(scale * y1))
+            .set("x2", num(scale * x2))
+            .set("y2", num(scale * y2));
 
-        doc.add(node)
+        doc.append(styled(node, LINE_CLASS, style));
+        doc
     }
 }
 
@@ -187,10 +546,10 @@ impl ToSvg for Circ {
 
         let pts = [
             o,
-            o + Pt { x: r, y: 0.0 },
-            o + Pt { x: -r, y: 0.0 },
-            o + Pt { x: 0.0, y: r },
-            o + Pt { x: 0.0, y: -r },
+            o + Pt::new(r, 0.0),
+            o + Pt::new(-r, 0.0),
+            o + Pt::new(0.0, r),
+            o + Pt::new(0.0, -r),
         ]
         .into_iter();
 
@@ -201,14 +560,214 @@ impl ToSvg for Circ {
         bounds
     }
 
-    fn populate_doc(&self, doc: Document, scale: f64) -> Document {
-        let Circ { o: Pt { x, y }, r } = self;
+    fn populate_doc<N: Node>(&self, mut doc: N, scale: f64, style: &Style) -> N {
+        let (x, y) = self.o.to_f64();
         let node = SvgCircle::new()
-            .set("class", CIRC_CLASS)
-            .set("cx", scale * x)
-            .set("cy", scale * y)
-            .set("r", scale * r);
+            .set("cx", num(scale * x))
+            .set("cy", num(scale * y))
+            .set("r", scale * self.r.to_f64());
+
+        doc.append(styled(node, CIRC_CLASS, style));
+        doc
+    }
+}
+
+impl ToSvg for Polyline {
+    fn update_bounds(&self, bounds: Bounds) -> Bounds {
+        self.pts
+            .iter()
+            .fold(bounds, |bounds, pt| pt.update_bounds(bounds))
+    }
 
-        doc.add(node)
+    fn populate_doc<N: Node>(&self, mut doc: N, scale: f64, style: &Style) -> N {
+        let points = self
+            .pts
+            .iter()
+            .map(|pt| {
+                let (x, y) = pt.to_f64();
+                format!("{},{}", num(scale * x), num(scale * y))
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        let node = SvgPolyline::new().set("points", points);
+
+        doc.append(styled(node, POLYLINE_CLASS, style));
+        doc
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::exec::Exec;
+
+    use super::*;
+
+    #[test]
+    fn styles() {
+        let mut scope = ExecScope::new();
+        parser::definitions(
+            r#"
+            A = pt 0.0 0.0
+            B = pt 1.0 1.0
+            l = line A B
+            c = circ A 1.0
+            "#,
+        )
+        .unwrap()
+        .exec(&mut scope)
+        .unwrap();
+
+        let mut style = Style::default();
+        style
+            .apply("color: #ff000080; width: 2; dash: 4 2")
+            .unwrap();
+        scope.set_style(Ident::from("l"), style).unwrap();
+
+        let mut style = Style::default();
+        style.apply("hidden").unwrap();
+        scope.set_style(Ident::from("c"), style).unwrap();
+
+        let svg = scope.to_svg(&ExportOptions::default());
+        assert!(svg.contains(
+            "stroke: rgb(255, 0, 0); stroke-opacity: 0.5019607843137255; \
+            stroke-width: 2; stroke-dasharray: 4 2"
+        ));
+        assert!(!svg.contains("<circle"));
+        assert_eq!(svg.matches("style=").count(), 1);
+    }
+
+    #[test]
+    fn polyline() {
+        let mut scope = ExecScope::new();
+        parser::definitions(
+            r#"
+            t = 0.0
+            l = line (pt 0.0 0.0) (pt 2.0 2.0)
+            P = pt_on l t
+            L = locus P P 3
+            "#,
+        )
+        .unwrap()
+        .exec(&mut scope)
+        .unwrap();
+
+        // Y axis is flipped
+        let svg = scope.to_svg(&ExportOptions::default());
+        assert!(svg.contains(r#"class="polyline" points="0,0 500,-500 1000,-1000""#));
+    }
+
+    #[test]
+    fn layers() {
+        let mut scope = ExecScope::new();
+        parser::definitions(
+            r#"
+            A = pt 0.0 0.0
+            B = pt 1.0 1.0
+            C = pt 2.0 0.0
+            l = line A B
+            "#,
+        )
+        .unwrap()
+        .exec(&mut scope)
+        .unwrap();
+
+        scope
+            .add_to_layer("base".into(), vec!["A".into(), "B".into()])
+            .unwrap();
+        scope
+            .add_to_layer("lines".into(), vec!["l".into()])
+            .unwrap();
+        scope.add_to_layer("empty".into(), Vec::new()).unwrap();
+
+        let svg = scope.to_svg(&ExportOptions::default());
+        assert_eq!(svg.matches("<g").count(), 3);
+        assert!(svg.contains(r#"<g class="layer" id="base">"#));
+        assert!(svg.contains(r#"<g class="layer" id="lines">"#));
+        assert!(svg.contains(r#"<g class="layer" id="empty"/>"#));
+        assert_eq!(svg.matches(r#"class="pt""#).count(), 3);
+
+        scope
+            .update_layer(&"base".into(), Some(true), None)
+            .unwrap();
+        let svg = scope.to_svg(&ExportOptions::default());
+        assert_eq!(svg.matches("<g").count(), 2);
+        assert_eq!(svg.matches(r#"class="pt""#).count(), 1);
+    }
+
+    #[test]
+    fn options() {
+        let mut scope = ExecScope::new();
+        parser::definitions(
+            r#"
+            A = pt 0.0 0.0
+            B = pt 1.0 2.0
+            C = pt 1.0 1.0
+            l = line A C
+            "#,
+        )
+        .unwrap()
+        .exec(&mut scope)
+        .unwrap();
+
+        let svg = |decls: &str| {
+            let mut options = ExportOptions::default();
+            options.apply(decls).unwrap();
+            scope.to_svg(&options)
+        };
+
+        // Y axis points up as on the canvas
+        let up = svg("");
+        assert!(up.contains(r#"y1="-2000""#));
+        assert!(!up.contains(r#"y1="2000""#));
+        let down = svg("y-axis: down");
+        assert!(down.contains(r#"y1="2000""#));
+
+        let framed = svg("viewport: 0 0 2 2; width: 200; background: white");
+        assert!(framed.contains(r#"viewBox="0 -200 200 200""#));
+        assert!(framed.contains(r#"width="200""#));
+        assert!(framed.contains(r#"height="200""#));
+        assert!(framed.contains(r#"class="background""#));
+        assert!(framed.contains(r#"style="fill: rgb(255, 255, 255)""#));
+
+        // Extra space is split evenly
+        let wide = svg("viewport: 0 0 2 2; width: 400; height: 200; y-axis: down");
+        assert!(wide.contains(r#"viewBox="-100 0 400 200""#));
+
+        // Lines are infinite by default
+        let clipped = svg("viewport: 0 0 2 2; width: 200; y-axis: down");
+        assert!(clipped.contains(r#"x2="200""#));
+        let segments = svg("viewport: 0 0 2 2; width: 200; y-axis: down; lines: segments");
+        assert!(!segments.contains(r#"x2="200""#));
+    }
+
+    #[test]
+    fn labels() {
+        let mut scope = ExecScope::new();
+        parser::definitions(
+            r#"
+            A = pt 0.0 0.0
+            B = pt 1.0 1.0
+            l = line A B
+            "#,
+        )
+        .unwrap()
+        .exec(&mut scope)
+        .unwrap();
+
+        assert!(!scope.to_svg(&ExportOptions::default()).contains("<text"));
+
+        scope.set_show_labels(true);
+        let mut style = Style::default();
+        style.apply("label: hide").unwrap();
+        scope.set_style(Ident::from("l"), style).unwrap();
+        let mut style = Style::default();
+        style.apply("color: red; label-text: A & B").unwrap();
+        scope.set_style(Ident::from("A"), style).unwrap();
+
+        let svg = scope.to_svg(&ExportOptions::default());
+        assert_eq!(svg.matches("<text").count(), 2);
+        assert!(svg.contains("A &amp; B"));
+        assert!(svg.contains("\nB\n"));
+        assert!(svg.contains(r#"class="label" style="fill: rgb(255, 0, 0)""#));
     }
 }
@@ -0,0 +1,312 @@
+//! This module implements exporting ExecScope to TikZ for LaTeX documents.
+//!
+//! Coordinates are written as they are in the plane
+//! and are scaled by the `tikzpicture` itself, so that the code is easy to edit by hand.
+
+use std::fmt::{self, Write};
+
+use types::{
+    core::{Circ, Line, Polyline, Pt, Value},
+    export::{Rect, TikzOptions},
+    style::{Color, Style},
+};
+
+use crate::{
+    exec::ExecScope,
+    svg::{flip_value, flip_y, Item, PT_RADIUS, STROKE_WIDTH},
+};
+
+/// Sizes in styles are in svg pixels, TikZ uses points.
+const PT_PER_PX: f64 = 0.75;
+
+/// Distance from an object to it's label, relative to the document's font.
+const LABEL_OFFSET_EM: f64 = 0.8;
+
+impl ExecScope {
+    pub fn to_tikz(&self, options: &TikzOptions) -> String {
+        let mut tikz = String::new();
+        // Writing to a String never fails
+        self.write_tikz(&mut tikz, options).unwrap();
+        tikz
+    }
+
+    fn write_tikz(&self, w: &mut impl Write, options: &TikzOptions) -> fmt::Result {
+        let scene = self.scene(&options.to_export_options());
+
+        writeln!(w, "\\begin{{tikzpicture}}[scale={}]", num(options.scale))?;
+
+        if let Some(Rect { min, max }) = options.viewport {
+            writeln!(w, "  \\clip {} rectangle {};", coord(min), coord(max))?;
+        }
+
+        for (layer, items) in &scene.groups {
+            if items.is_empty() {
+                continue;
+            }
+
+            let indent = match layer {
+                None => "  ",
+                Some(layer) => {
+                    writeln!(w, "  % layer {}", layer.name)?;
+                    writeln!(w, "  \\begin{{scope}}")?;
+                    "    "
+                }
+            };
+
+            // Points are defined first, so that they can be used in later code
+            for (name, value, _style) in items {
+                if let Value::Pt(Some(pt)) = value {
+                    writeln!(w, "{indent}\\coordinate ({name}) at {};", coord(*pt))?;
+                }
+            }
+
+            for item in items {
+                write_item(w, indent, item)?;
+            }
+
+            for (name, value, style) in items {
+                if let Some(label) = scene.labels.get(name) {
+                    write_label(w, indent, value, label, style)?;
+                }
+            }
+
+            if layer.is_some() {
+                writeln!(w, "  \\end{{scope}}")?;
+            }
+        }
+
+        writeln!(w, "\\end{{tikzpicture}}")
+    }
+}
+
+fn write_item(w: &mut impl Write, indent: &str, (name, value, style): &Item) -> fmt::Result {
+    match value {
+        Value::Pt(Some(_)) => {
+            // Points are round caps of zero-length lines in svg, so the width is a diameter
+            let radius = style.width.unwrap_or(PT_RADIUS / 2.0) * PT_PER_PX;
+            let options = options([paint("fill", style.color)]);
+            writeln!(
+                w,
+                "{indent}\\fill{options} ({name}) circle[radius={}pt];",
+                num(radius)
+            )
+        }
+
+        Value::Line(Some(Line { p1, p2 })) => {
+            let options = options(stroke(style));
+            writeln!(
+                w,
+                "{indent}\\draw{options} {} -- {}; % {name}",
+                coord(*p1),
+                coord(*p2)
+            )
+        }
+
+        Value::Circ(Some(Circ { o, r })) => {
+            let mut decls = stroke(style);
+            decls.push(paint("fill", style.fill));
+            let options = options(decls);
+            writeln!(
+                w,
+                "{indent}\\draw{options} {} circle[radius={}]; % {name}",
+                coord(*o),
+                num(r.to_f64())
+            )
+        }
+
+        Value::Polyline(Some(Polyline { pts })) if !pts.is_empty() => {
+            let options = options(stroke(style));
+            let path: Vec<_> = pts.iter().map(|pt| coord(*pt)).collect();
+            writeln!(w, "{indent}\\draw{options} {}; % {name}", path.join(" -- "))
+        }
+
+        _ => Ok(()),
+    }
+}
+
+fn write_label(
+    w: &mut impl Write,
+    indent: &str,
+    value: &Value,
+    label: &str,
+    style: &Style,
+) -> fmt::Result {
+    // Anchors are computed for svg's y axis, that points down
+    let Some((anchor, dir)) = flip_value(value.clone()).label_anchor() else {
+        return Ok(());
+    };
+    let (anchor, dir) = (flip_y(anchor), flip_y(dir) * LABEL_OFFSET_EM);
+
+    let options = options([
+        Some(format!(
+            "shift={{({}em, {}em)}}",
+            num(dir.x.to_f64()),
+            num(dir.y.to_f64())
+        )),
+        paint("text", style.color),
+    ]);
+    writeln!(
+        w,
+        "{indent}\\node{options} at {} {{{}}};",
+        coord(anchor),
+        escape(label)
+    )
+}
+
+/// Line width and dash pattern, if they differ from the default ones.
+fn stroke(style: &Style) -> Vec<Option<String>> {
+    vec![
+        paint("draw", style.color),
+        style
+            .width
+            .filter(|width| *width != STROKE_WIDTH)
+            .map(|width| format!("line width={}pt", num(width * PT_PER_PX))),
+        style.dash.as_ref().map(|dash| {
+            // Svg repeats odd dash lists
+            let mut dash = dash.clone();
+            if dash.len() % 2 == 1 {
+                dash.extend(dash.clone());
+            }
+            let pattern: Vec<_> = dash
+                .chunks(2)
+                .map(|on_off| {
+                    format!(
+                        "on {}pt off {}pt",
+                        num(on_off[0] * PT_PER_PX),
+                        num(on_off[1] * PT_PER_PX)
+                    )
+                })
+                .collect();
+            format!("dash pattern={}", pattern.join(" "))
+        }),
+    ]
+}
+
+fn paint(property: &str, color: Option<Color>) -> Option<String> {
+    let Color { r, g, b, a } = color?;
+    let mut decl = format!("{property}={{rgb,255:red,{r};green,{g};blue,{b}}}");
+    if a != 255 {
+        write!(decl, ", {property} opacity={}", num(a as f64 / 255.0)).unwrap();
+    }
+    Some(decl)
+}
+
+/// `[a, b]` for present options, an empty string if there are none.
+fn options(decls: impl IntoIterator<Item = Option<String>>) -> String {
+    let decls: Vec<_> = decls.into_iter().flatten().collect();
+    if decls.is_empty() {
+        String::new()
+    } else {
+        format!("[{}]", decls.join(", "))
+    }
+}
+
+fn coord(pt: Pt) -> String {
+    let (x, y) = pt.to_f64();
+    format!("({}, {})", num(x), num(y))
+}
+
+/// Rounds away floating point noise, e.g. `0.30000000000000004`, and the sign of zero.
+pub(crate) fn num(x: f64) -> String {
+    let s = format!("{x:.4}");
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    match s {
+        "-0" => "0".to_string(),
+        s => s.to_string(),
+    }
+}
+
+/// Escapes characters, that are special in LaTeX text.
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '#' | '$' | '%' | '&' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+    use types::{core::Ident, style::Style};
+
+    use crate::exec::Exec;
+
+    use super::*;
+
+    #[test]
+    fn tikz() {
+        let mut scope = ExecScope::new();
+        parser::definitions(
+            r#"
+            A_1 = pt 0.0 0.0
+            B = pt 2.0 1.0
+            l = line A_1 B
+            c = circ B 0.5
+            "#,
+        )
+        .unwrap()
+        .exec(&mut scope)
+        .unwrap();
+
+        let mut style = Style::default();
+        style.apply("color: red; width: 2; dash: 4 2").unwrap();
+        scope.set_style(Ident::from("c"), style).unwrap();
+        scope.set_show_labels(true);
+
+        let mut options = TikzOptions::default();
+        options.apply("scale: 0.5; lines: segments").unwrap();
+
+        assert_eq!(
+            scope.to_tikz(&options),
+            indoc! {r#"
+                \begin{tikzpicture}[scale=0.5]
+                  \coordinate (A_1) at (0, 0);
+                  \coordinate (B) at (2, 1);
+                  \fill (A_1) circle[radius=1.5pt];
+                  \fill (B) circle[radius=1.5pt];
+                  \draw[draw={rgb,255:red,255;green,0;blue,0}, line width=1.5pt, dash pattern=on 3pt off 1.5pt] (2, 1) circle[radius=0.5]; % c
+                  \draw (0, 0) -- (2, 1); % l
+                  \node[shift={(0.5657em, 0.5657em)}] at (0, 0) {A\_1};
+                  \node[shift={(0.5657em, 0.5657em)}] at (2, 1) {B};
+                  \node[shift={(0.5657em, 0.5657em)}, text={rgb,255:red,255;green,0;blue,0}] at (2.3536, 1.3536) {c};
+                  \node[shift={(-0.3578em, 0.7155em)}] at (1, 0.5) {l};
+                \end{tikzpicture}
+            "#}
+        );
+    }
+
+    #[test]
+    fn num() {
+        assert_eq!(super::num(0.1 + 0.2), "0.3");
+        assert_eq!(super::num(-1.5), "-1.5");
+        assert_eq!(super::num(-0.0), "0");
+        assert_eq!(super::num(-1e-9), "0");
+    }
+
+    #[test]
+    fn viewport() {
+        let mut scope = ExecScope::new();
+        parser::definitions("l = line (pt 0.0 0.0) (pt 1.0 1.0)")
+            .unwrap()
+            .exec(&mut scope)
+            .unwrap();
+
+        let mut options = TikzOptions::default();
+        options.apply("viewport: -1 -1 2 2").unwrap();
+
+        let tikz = scope.to_tikz(&options);
+        assert!(tikz.contains(r"\clip (-1, -1) rectangle (2, 2);"));
+        // Infinite lines are clipped to the viewport
+        assert!(tikz.contains(r"\draw (-1, -1) -- (2, 2); % l"));
+    }
+}
@@ -0,0 +1,218 @@
+//! This module implements the scope-wide tolerance of geometric comparisons.
+//!
+//! `==`, `!=` and predicates like `parallel?` on reals, points, lines and circles
+//! are the builtins with an explicit tolerance argument (e.g. `approx_eq a b eps`),
+//! that get the scope's tolerance, when they are evaluated.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, OnceLock,
+    },
+};
+
+use types::{
+    core::{Ident, Value, ValueType},
+    lang::FunctionSignature,
+};
+
+use crate::{
+    exec::{ExecError, ExecScope},
+    function::{FuncMap, Function, FunctionInner, FunctionInnerKind},
+    node::Node,
+};
+
+pub(crate) const DEFAULT_TOLERANCE: f64 = 1e-9;
+
+/// The tolerance along with the functions, that use it.
+#[derive(Debug)]
+pub(crate) struct Tolerance {
+    /// Bits of f64; it's shared with the functions.
+    eps: Arc<AtomicU64>,
+
+    funcs: FuncMap,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self::new(DEFAULT_TOLERANCE)
+    }
+}
+
+impl Tolerance {
+    pub(crate) fn new(eps: f64) -> Self {
+        let eps = Arc::new(AtomicU64::new(eps.to_bits()));
+
+        use ValueType::*;
+        let mut funcs = HashMap::new();
+        for value_type in [Real, Pt, Line, Circ] {
+            let args = vec![value_type.clone(), value_type];
+            insert_func(&mut funcs, &eps, "#eq", "approx_eq", args.clone(), false);
+            insert_func(&mut funcs, &eps, "#neq", "approx_eq", args, true);
+        }
+        for (name, args) in [
+            ("on", vec![Pt, Line]),
+            ("on", vec![Pt, Circ]),
+            ("parallel?", vec![Line, Line]),
+            ("perpendicular?", vec![Line, Line]),
+            ("collinear?", vec![Pt, Pt, Pt]),
+            ("concyclic?", vec![Pt, Pt, Pt, Pt]),
+        ] {
+            insert_func(&mut funcs, &eps, name, name, args, false);
+        }
+
+        Self { eps, funcs }
+    }
+
+    pub(crate) fn get(&self) -> f64 {
+        f64::from_bits(self.eps.load(Ordering::Relaxed))
+    }
+
+    /// Changes the tolerance without evaluating items and returns the old one.
+    pub(crate) fn replace(&self, eps: f64) -> f64 {
+        f64::from_bits(self.eps.swap(eps.to_bits(), Ordering::Relaxed))
+    }
+
+    pub(crate) fn get_func(&self, sign: &FunctionSignature) -> Option<Function> {
+        self.funcs.get(sign).cloned()
+    }
+
+    pub(crate) fn list_funcs(&self) -> impl Iterator<Item = &FunctionSignature> {
+        self.funcs.keys()
+    }
+}
+
+/// Inserts a function, that calls the builtin with the tolerance as the last argument,
+/// optionally negating the result.
+fn insert_func(
+    funcs: &mut FuncMap,
+    eps: &Arc<AtomicU64>,
+    name: &str,
+    builtin_name: &str,
+    arg_types: Vec<ValueType>,
+    negate: bool,
+) {
+    let builtin_sign = FunctionSignature {
+        name: Ident::from(builtin_name),
+        arg_types: [arg_types.as_slice(), &[ValueType::Real]].concat(),
+    };
+    let builtin = Function::get_builtin(&builtin_sign).expect("builtin should be defined");
+
+    let eps = eps.clone();
+    let kind = OnceLock::new();
+    kind.set(FunctionInnerKind::BuiltIn(Box::new(move |mut args| {
+        args.push(Value::from(f64::from_bits(eps.load(Ordering::Relaxed))));
+        Ok(match builtin.eval(args)? {
+            Value::Bool(v) if negate => Value::Bool(v.map(|v| !v)),
+            v => v,
+        })
+    })))
+    .expect("initialization");
+
+    let sign = FunctionSignature {
+        name: Ident::from(name),
+        arg_types,
+    };
+    let func = Function::from(FunctionInner {
+        sign: sign.clone(),
+        return_type: ValueType::Bool,
+        kind,
+    });
+    assert!(funcs.insert(sign, func).is_none());
+}
+
+impl ExecScope {
+    pub fn get_tolerance(&self) -> f64 {
+        self.tolerance.get()
+    }
+
+    /// Changes the tolerance and evaluates all items again,
+    /// as their values may depend on it.
+    pub fn set_tolerance(&mut self, eps: f64) -> Result<(), ExecError> {
+        if !(eps.is_finite() && eps >= 0.0) {
+            return Err(ExecError::BadTolerance(eps));
+        }
+
+        self.tolerance.eps.store(eps.to_bits(), Ordering::Relaxed);
+        Node::reevaluate_all(self.nodes.values().cloned())?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::exec::Exec;
+
+    use super::*;
+
+    #[test]
+    fn tolerance() {
+        let mut scope = ExecScope::new();
+        parser::definitions(
+            r#"
+            d = 0.001
+            A = pt 0.0 0.0
+            B = pt d 0.0
+            l1 = line A (pt 1.0 1.0)
+            l2 = line (pt 2.0 2.0) A
+            same_pt = A == B
+            other_pt = A != B
+            same_line = l1 == l2
+            on_line = on (pt d d) l1
+            sum = 0.1 + 0.2 == 0.3
+            cmp x:pt y:pt -> bool = x == y
+            same_pt_fn = cmp A B
+            "#,
+        )
+        .unwrap()
+        .exec(&mut scope)
+        .unwrap();
+
+        let get = |scope: &ExecScope, name: &str| scope.get_item(&Ident::from(name)).unwrap();
+
+        assert_eq!(scope.get_tolerance(), DEFAULT_TOLERANCE);
+        assert_eq!(get(&scope, "same_pt"), false.into());
+        assert_eq!(get(&scope, "other_pt"), true.into());
+        assert_eq!(get(&scope, "same_line"), true.into());
+        assert_eq!(get(&scope, "on_line"), true.into());
+        assert_eq!(get(&scope, "sum"), true.into());
+        assert_eq!(get(&scope, "same_pt_fn"), false.into());
+
+        // Items are evaluated again
+        scope.set_tolerance(0.01).unwrap();
+        assert_eq!(get(&scope, "same_pt"), true.into());
+        assert_eq!(get(&scope, "other_pt"), false.into());
+        assert_eq!(get(&scope, "same_pt_fn"), true.into());
+        assert_eq!(
+            scope
+                .eval_expr(parser::expr("B == pt 0.0 0.0").unwrap(), HashMap::new())
+                .unwrap(),
+            true.into()
+        );
+
+        assert!(matches!(
+            scope.set_tolerance(-1.0),
+            Err(ExecError::BadTolerance(_))
+        ));
+        assert!(scope.set_tolerance(f64::NAN).is_err());
+        assert_eq!(scope.get_tolerance(), 0.01);
+
+        // The tolerance is saved
+        let loaded = ExecScope::from_json(&scope.to_json()).unwrap();
+        assert_eq!(loaded.get_tolerance(), 0.01);
+        assert_eq!(get(&loaded, "same_pt_fn"), true.into());
+    }
+
+    #[test]
+    fn redefinition() {
+        let mut scope = ExecScope::new();
+        assert!(matches!(
+            parser::definitions("on a:pt b:line -> bool = true")
+                .unwrap()
+                .exec(&mut scope),
+            Err(ExecError::BuiltinRedefinition(_))
+        ));
+    }
+}
@@ -1,31 +1,81 @@
 use iced::{
-    widget::canvas::{self, Path},
-    Color,
+    alignment,
+    widget::canvas::{self, LineDash, Path},
+    Color, Pixels,
+};
+use types::{
+    core::{Circ, Line, Polyline, Pt, Value},
+    style::{self, Style},
 };
-use types::core::{Circ, Line, Pt, Value};
 
 use super::helpers::pt_to_point;
 
 pub(super) static POINT_WIDTH: f32 = 5.;
 pub(super) static LINE_WIDTH: f32 = 1.;
+pub(super) static LABEL_SIZE: f32 = 14.;
+
+/// Distance between an object and it's label's center.
+pub(super) static LABEL_OFFSET: f64 = 12.;
 
-pub(super) fn draw_value(value: &Value, frame: &mut canvas::Frame, color: Color) {
+/// Draws value with the given color; the rest is taken from style.
+pub(super) fn draw_value(value: &Value, frame: &mut canvas::Frame, color: Color, style: &Style) {
     match value {
         Value::Pt(Some(pt)) => {
-            draw_pt(pt, frame, color);
+            draw_pt(pt, frame, color, style);
         }
         Value::Line(Some(line)) => {
-            draw_line(line, frame, color);
+            draw_line(line, frame, color, style);
         }
         Value::Circ(Some(circ)) => {
-            draw_circ(circ, frame, color);
+            draw_circ(circ, frame, color, style);
+        }
+        Value::Polyline(Some(polyline)) => {
+            draw_polyline(polyline, frame, color, style);
         }
         _ => {}
     }
 }
 
-fn draw_pt(pt: &Pt, frame: &mut canvas::Frame, color: Color) {
-    let path = Path::circle(pt_to_point(pt), POINT_WIDTH);
+/// Draws label next to the **screen** value.
+pub(super) fn draw_label(value: &Value, frame: &mut canvas::Frame, color: Color, label: &str) {
+    let Some((anchor, dir)) = value.label_anchor() else {
+        return;
+    };
+
+    frame.fill_text(canvas::Text {
+        content: label.to_string(),
+        position: pt_to_point(&(anchor + dir * LABEL_OFFSET)),
+        color,
+        size: Pixels(LABEL_SIZE),
+        horizontal_alignment: alignment::Horizontal::Center,
+        vertical_alignment: alignment::Vertical::Center,
+        ..Default::default()
+    });
+}
+
+pub(super) fn to_iced_color(color: style::Color) -> Color {
+    Color::from_rgba8(color.r, color.g, color.b, color.a as f32 / 255.0)
+}
+
+fn stroke<'a>(color: Color, style: &Style, dash: &'a [f32]) -> canvas::Stroke<'a> {
+    canvas::Stroke {
+        style: canvas::Style::Solid(color),
+        width: style.width.map_or(LINE_WIDTH, |width| width as f32),
+        line_dash: LineDash {
+            segments: dash,
+            offset: 0,
+        },
+        ..Default::default()
+    }
+}
+
+fn dash(style: &Style) -> Vec<f32> {
+    style.dash.iter().flatten().map(|len| *len as f32).collect()
+}
+
+fn draw_pt(pt: &Pt, frame: &mut canvas::Frame, color: Color, style: &Style) {
+    let radius = style.width.map_or(POINT_WIDTH, |width| width as f32);
+    let path = Path::circle(pt_to_point(pt), radius);
     frame.fill(
         &path,
         canvas::Fill {
@@ -35,26 +85,55 @@ fn draw_pt(pt: &Pt, frame: &mut canvas::Frame, color: Color) {
     );
 }
 
-fn draw_line(Line { p1, p2 }: &Line, frame: &mut canvas::Frame, color: Color) {
-    let path = Path::line(pt_to_point(p1), pt_to_point(p2));
-    frame.stroke(
-        &path,
-        canvas::Stroke {
-            style: canvas::Style::Solid(color),
-            width: LINE_WIDTH,
-            ..Default::default()
-        },
-    );
+/// Lines are infinite, so they are clipped to the frame.
+fn draw_line(line: &Line, frame: &mut canvas::Frame, color: Color, style: &Style) {
+    let size = frame.size();
+    let Some(Line { p1, p2 }) = line.clip(
+        Pt::new(0.0, 0.0),
+        Pt::new(size.width as f64, size.height as f64),
+    ) else {
+        return;
+    };
+
+    let path = Path::line(pt_to_point(&p1), pt_to_point(&p2));
+    frame.stroke(&path, stroke(color, style, &dash(style)));
 }
 
-fn draw_circ(Circ { o, r }: &Circ, frame: &mut canvas::Frame, color: Color) {
-    let path = Path::circle(pt_to_point(o), *r as f32);
+fn draw_circ(Circ { o, r }: &Circ, frame: &mut canvas::Frame, color: Color, style: &Style) {
+    let path = Path::circle(pt_to_point(o), r.to_f64() as f32);
+    if let Some(fill) = style.fill {
+        frame.fill(
+            &path,
+            canvas::Fill {
+                style: canvas::Style::Solid(to_iced_color(fill)),
+                ..Default::default()
+            },
+        );
+    }
+    frame.stroke(&path, stroke(color, style, &dash(style)));
+}
+
+fn draw_polyline(
+    Polyline { pts }: &Polyline,
+    frame: &mut canvas::Frame,
+    color: Color,
+    style: &Style,
+) {
+    let Some((first, rest)) = pts.split_first() else {
+        return;
+    };
+
+    let path = Path::new(|builder| {
+        builder.move_to(pt_to_point(first));
+        for pt in rest {
+            builder.line_to(pt_to_point(pt));
+        }
+    });
     frame.stroke(
         &path,
         canvas::Stroke {
-            style: canvas::Style::Solid(color),
-            width: LINE_WIDTH,
-            ..Default::default()
+            line_join: canvas::LineJoin::Round,
+            ..stroke(color, style, &dash(style))
         },
     );
 }
@@ -4,4 +4,4 @@ mod program;
 mod transform;
 mod widget;
 
-pub use widget::{Msg, State};
+pub use widget::{CanvasData, Msg, State};
@@ -1,14 +1,13 @@
 use std::cmp::Ordering;
-use std::collections::HashMap;
 
 use crate::helpers::new_object_name_with_type;
 use crate::mode::Mode;
 use crate::my_colors;
 
-use super::draw::draw_value;
+use super::draw::{draw_label, draw_value, to_iced_color};
 use super::helpers::point_to_pt;
 use super::transform::Transformation;
-use super::widget::Msg;
+use super::widget::{CanvasData, Msg};
 use iced::mouse::ScrollDelta;
 use iced::{
     mouse::{self, Cursor},
@@ -16,13 +15,18 @@ use iced::{
     Rectangle, Renderer, Theme,
 };
 use itertools::Itertools;
-use types::core::{Ident, Pt, Value, ValueType};
+use types::{
+    core::{Ident, Pt, Value, ValueType},
+    layer::Layer,
+    style::Style,
+};
 
 static ZOOM_SPEED: f64 = 0.5;
 
 #[derive(Debug)]
 pub(super) struct Program<'a> {
-    pub(super) vars: &'a HashMap<Ident, Value>,
+    pub(super) data: CanvasData<'a>,
+
     pub(super) mode: &'a Mode,
 
     /// A transformation to abstract from screen size.
@@ -70,11 +74,23 @@ impl canvas::Program<Msg> for Program<'_> {
         let mut frame = canvas::Frame::new(renderer, bounds.size());
         let t = self.complete_transformation();
 
-        for (var_name, var_value_real) in self.vars {
+        for (_name, trail) in self.data.traces.polylines() {
+            if let Some(trail) = t.transform_value(&trail.into()) {
+                draw_value(&trail, &mut frame, my_colors::TRAIL, &Style::default());
+            }
+        }
+
+        for (var_name, var_value_real) in self.data.vars {
+            if self.is_hidden(var_name) {
+                continue;
+            }
+            let style = self.style(var_name);
+
             let Some(var_value_screen) = t.transform_value(var_value_real) else {
                 continue;
             };
 
+            // Highlighting depends on mode and overrides item's own color
             let color = self
                 .mode
                 .to_item_color_and_interaction(
@@ -82,11 +98,21 @@ impl canvas::Program<Msg> for Program<'_> {
                     &var_value_real.value_type(),
                     &state.picked_pt,
                     &state.hovered_item,
-                    my_colors::ITEM_NORMAL,
+                    style.color.map_or(my_colors::ITEM_NORMAL, to_iced_color),
                 )
                 .0;
 
-            draw_value(&var_value_screen, &mut frame, color);
+            // Traced points are shown along with their trails
+            let color = match self.mode {
+                Mode::Trace if self.data.traces.is_traced(var_name) => my_colors::TRAIL,
+                _ => color,
+            };
+
+            draw_value(&var_value_screen, &mut frame, color, style);
+
+            if let Some(label) = self.data.labels.get(var_name) {
+                draw_label(&var_value_screen, &mut frame, color, label);
+            }
         }
 
         vec![frame.into_geometry()]
@@ -139,6 +165,7 @@ impl canvas::Program<Msg> for Program<'_> {
             Mode::Modify => item_inter.unwrap_or(mouse::Interaction::None),
             Mode::CreatePoint => mouse::Interaction::Crosshair,
             Mode::Delete => item_inter.unwrap_or(mouse::Interaction::None),
+            Mode::Trace => item_inter.unwrap_or(mouse::Interaction::None),
             Mode::Function(_) => item_inter.unwrap_or(mouse::Interaction::None),
         }
     }
@@ -181,7 +208,7 @@ impl Program<'_> {
                     (
                         Captured,
                         Some(Msg::CreatePoint(
-                            new_object_name_with_type(Some(ValueType::Pt), self.vars.keys()),
+                            new_object_name_with_type(Some(ValueType::Pt), self.data.vars.keys()),
                             cursor_pos_real,
                         )),
                     )
@@ -193,7 +220,9 @@ impl Program<'_> {
             Mode::Modify => {
                 let cursor_item =
                     self.get_cursor_item(cursor_pos_screen, |v| v.value_type() == ValueType::Pt);
-                let cursor_item_name = cursor_item.map(|(name, _value)| name);
+                let cursor_item_name = cursor_item
+                    .map(|(name, _value)| name)
+                    .filter(|name| !self.is_locked(name));
                 state.hovered_item = state
                     .picked_pt
                     .is_none()
@@ -207,6 +236,13 @@ impl Program<'_> {
                 }
 
                 if let Some(picked_pt) = &state.picked_pt {
+                    if let Some(pt_on) = self.data.pt_ons.get(picked_pt) {
+                        let msg = pt_on
+                            .project(cursor_pos_real)
+                            .map(|t| Msg::SetParameter(pt_on.param.clone(), t.to_f64()));
+                        return (Captured, msg);
+                    }
+
                     return (
                         Captured,
                         Some(Msg::MovePoint(picked_pt.clone(), cursor_pos_real)),
@@ -230,6 +266,21 @@ impl Program<'_> {
                 }
             }
 
+            Mode::Trace => {
+                let cursor_item =
+                    self.get_cursor_item(cursor_pos_screen, |v| v.value_type() == ValueType::Pt);
+                let cursor_item_name = cursor_item.map(|(name, _value)| name);
+                state.hovered_item = cursor_item_name.clone();
+
+                if let (ButtonPressed(Left), Some(cursor_item_name)) =
+                    (mouse_event, cursor_item_name)
+                {
+                    (Captured, Some(Msg::ToggleTrace(cursor_item_name)))
+                } else {
+                    (Ignored, None)
+                }
+            }
+
             Mode::Function(func_mode) => {
                 let cursor_item = self.get_cursor_item(cursor_pos_screen, |v| {
                     v.value_type() == func_mode.next_arg_type()
@@ -302,16 +353,19 @@ impl Program<'_> {
             value: Value,
         }
 
-        self.vars
+        self.data
+            .vars
             .iter()
+            .filter(|(name, _)| !self.is_hidden(name))
             .filter_map(|(name, value_real)| Some((name, t.transform_value(value_real)?)))
             .filter(|(_, value)| cond(value))
             .filter_map(|(name, value)| {
                 Some(WithDist {
-                    dist: match value {
+                    dist: match &value {
                         Value::Pt(Some(pt)) => pt.dist(cursor_pos_screen),
                         Value::Line(Some(line)) => line.dist(cursor_pos_screen),
                         Value::Circ(Some(circ)) => circ.dist(cursor_pos_screen),
+                        Value::Polyline(Some(polyline)) => polyline.dist(cursor_pos_screen),
                         _ => return None,
                     },
                     name,
@@ -320,7 +374,9 @@ impl Program<'_> {
             })
             .filter(|item| item.dist < CLICK_DIST)
             .sorted_by(|lhs, rhs| match (&lhs.value, &rhs.value) {
-                (Value::Pt(_), Value::Line(_)) | (Value::Pt(_), Value::Circ(_)) => Ordering::Less,
+                (Value::Pt(_), Value::Line(_) | Value::Circ(_) | Value::Polyline(_)) => {
+                    Ordering::Less
+                }
 
                 _ => lhs.dist.total_cmp(&rhs.dist),
             })
@@ -328,6 +384,34 @@ impl Program<'_> {
             .map(|item| (item.name.clone(), item.value.clone()))
     }
 
+    fn style(&self, name: &Ident) -> &Style {
+        static DEFAULT: Style = Style {
+            color: None,
+            width: None,
+            dash: None,
+            fill: None,
+            hidden: false,
+            label: None,
+            show_label: None,
+        };
+
+        self.data.styles.get(name).unwrap_or(&DEFAULT)
+    }
+
+    fn layer_of(&self, name: &Ident) -> Option<&Layer> {
+        self.data.layers.iter().find(|layer| layer.contains(name))
+    }
+
+    /// Whether the item is hidden by itself or by it's layer.
+    fn is_hidden(&self, name: &Ident) -> bool {
+        self.style(name).hidden || self.layer_of(name).is_some_and(|layer| layer.hidden)
+    }
+
+    /// Locked points can not be moved.
+    fn is_locked(&self, name: &Ident) -> bool {
+        self.layer_of(name).is_some_and(|layer| layer.locked)
+    }
+
     /// Converts **real** coordinates into **screen** coordinates.
     fn complete_transformation(&self) -> Transformation {
         self.custom_transformation.chain(&self.unify_transformation)
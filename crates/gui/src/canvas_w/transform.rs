@@ -1,4 +1,4 @@
-use types::core::{Circ, Line, Pt, Value};
+use types::core::{Polyline, Pt, Transform, Value};
 
 /// Shift and scale transformation.
 ///
@@ -12,6 +12,9 @@ use types::core::{Circ, Line, Pt, Value};
 ///
 /// Suffixes `_real` and `_screen` in the rest of the code means real coordinates
 /// and screen coordinates, respectively.
+///
+/// Offset and zoom are kept instead of a [Transform], as panning and zooming
+/// change them directly; items are mapped by the [Transform] though.
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub struct Transformation {
     pub(super) offset: Pt,
@@ -36,19 +39,17 @@ impl Transformation {
     pub(super) fn from_bounds(min: Pt, max: Pt, by_min_side: bool) -> Self {
         let offset = (min + max) / 2.0;
 
-        let mut size = max - min;
-
-        size = match size {
-            Pt { x: 0.0, y: 0.0 } => Pt { x: 1.0, y: 1.0 },
-            Pt { x, y: 0.0 } => Pt { x, y: x },
-            Pt { x: 0.0, y } => Pt { x: y, y },
-            _ => size,
+        let size = match (max - min).to_f64() {
+            (0.0, 0.0) => (1.0, 1.0),
+            (x, 0.0) => (x, x),
+            (0.0, y) => (y, y),
+            size => size,
         };
 
         let zoom = if by_min_side {
-            size.y.min(size.x)
+            size.1.min(size.0)
         } else {
-            size.y.max(size.x)
+            size.1.max(size.0)
         } / 2.0;
         assert!(zoom > 0.0);
 
@@ -57,7 +58,7 @@ impl Transformation {
 
     pub(super) fn identity() -> Self {
         Self {
-            offset: Pt { x: 0.0, y: 0.0 },
+            offset: Pt::new(0.0, 0.0),
             zoom: 1.0,
         }
     }
@@ -76,35 +77,27 @@ impl Transformation {
         }
     }
 
+    /// Same affine transformation, that items are mapped by.
+    pub(super) fn to_transform(self) -> Transform {
+        Transform::translation(self.offset)
+            * Transform::homothety(Pt::new(0.0, 0.0), self.zoom.into())
+    }
+
     pub(super) fn transform_value(&self, value: &Value) -> Option<Value> {
+        let t = self.to_transform();
         match value {
-            Value::Pt(Some(pt)) => Some(self.transform_pt(*pt).into()),
-            Value::Line(Some(line)) => Some(self.transform_line(*line).into()),
-            Value::Circ(Some(circ)) => Some(self.transform_circ(*circ).into()),
+            Value::Pt(Some(pt)) => Some(t.apply_pt(*pt).into()),
+            Value::Line(Some(line)) => Some(t.apply_line(*line).into()),
+            Value::Circ(Some(circ)) => Some(t.apply_circ(*circ).into()),
+            Value::Polyline(Some(Polyline { pts })) => {
+                Some(Polyline::new(pts.iter().map(|pt| t.apply_pt(*pt)).collect()).into())
+            }
             _ => None,
         }
     }
 
     pub(super) fn transform_pt(&self, p: Pt) -> Pt {
-        p * self.zoom + self.offset
-    }
-
-    pub(super) fn transform_line(&self, l: Line) -> Line {
-        let Line { mut p1, mut p2 } = l;
-
-        p1 = self.transform_pt(p1);
-        p2 = self.transform_pt(p2);
-
-        Line { p1, p2 }
-    }
-
-    pub(super) fn transform_circ(&self, c: Circ) -> Circ {
-        let Circ { mut o, mut r } = c;
-
-        o = self.transform_pt(o);
-        r *= self.zoom;
-
-        Circ { o, r }
+        self.to_transform().apply_pt(p)
     }
 }
 
@@ -114,11 +107,11 @@ mod test {
 
     #[test]
     fn transform_origin() {
-        let offset = Pt { x: 123.0, y: 456.0 };
+        let offset = Pt::new(123.0, 456.0);
 
         let t = Transformation { offset, zoom: 42.0 };
 
-        let pt = Pt { x: 0.0, y: 0.0 };
+        let pt = Pt::new(0.0, 0.0);
 
         assert_eq!(t.transform_pt(pt), offset);
     }
@@ -126,75 +119,58 @@ mod test {
     #[test]
     fn transform_zoom() {
         let t = Transformation {
-            offset: Pt { x: 0.0, y: 0.0 },
+            offset: Pt::new(0.0, 0.0),
             zoom: 42.0,
         };
 
-        let pt = Pt { x: 1.0, y: 2.0 };
+        let pt = Pt::new(1.0, 2.0);
 
-        assert_eq!(t.transform_pt(pt), Pt { x: 42.0, y: 84.0 });
+        assert_eq!(t.transform_pt(pt), Pt::new(42.0, 84.0));
     }
 
     #[test]
     fn transform_unit() {
-        let offset = Pt { x: 123.0, y: 456.0 };
+        let offset = Pt::new(123.0, 456.0);
         let zoom = 42.0;
         let t = Transformation { offset, zoom };
 
-        let pt = Pt { x: 0.0, y: 1.0 };
+        let pt = Pt::new(0.0, 1.0);
 
-        assert_eq!(
-            t.transform_pt(pt),
-            Pt {
-                x: offset.x,
-                y: offset.y + zoom
-            }
-        );
+        assert_eq!(t.transform_pt(pt), offset + Pt::new(0.0, zoom));
     }
 
     #[test]
     fn transform_complex() {
         let t = Transformation {
-            offset: Pt { x: 4.0, y: 5.0 },
+            offset: Pt::new(4.0, 5.0),
             zoom: 6.0,
         };
 
-        let pt = Pt { x: 7.0, y: 8.0 };
+        let pt = Pt::new(7.0, 8.0);
 
         assert_eq!(
             t.transform_pt(pt),
-            Pt {
-                x: 7.0 * 6.0 + 4.0,
-                y: 8.0 * 6.0 + 5.0,
-            }
+            Pt::new(7.0 * 6.0 + 4.0, 8.0 * 6.0 + 5.0)
         );
     }
 
     #[test]
     fn from_bounds_square_fit() {
-        let t =
-            Transformation::from_bounds(Pt { x: 150.0, y: 300.0 }, Pt { x: 250.0, y: 400.0 }, true);
+        let t = Transformation::from_bounds(Pt::new(150.0, 300.0), Pt::new(250.0, 400.0), true);
 
-        assert_eq!(
-            t.transform_pt(Pt { x: -1.0, y: -1.0 }),
-            Pt { x: 150.0, y: 300.0 }
-        );
+        assert_eq!(t.transform_pt(Pt::new(-1.0, -1.0)), Pt::new(150.0, 300.0));
 
-        assert_eq!(
-            t.transform_pt(Pt { x: 1.0, y: 1.0 }),
-            Pt { x: 250.0, y: 400.0 }
-        );
+        assert_eq!(t.transform_pt(Pt::new(1.0, 1.0)), Pt::new(250.0, 400.0));
     }
 
     #[test]
     fn from_bounds_horizontal_fit() {
-        let t =
-            Transformation::from_bounds(Pt { x: 100.0, y: 200.0 }, Pt { x: 200.0, y: 700.0 }, true);
+        let t = Transformation::from_bounds(Pt::new(100.0, 200.0), Pt::new(200.0, 700.0), true);
 
         assert_eq!(
             t,
             Transformation {
-                offset: Pt { x: 150.0, y: 450.0 },
+                offset: Pt::new(150.0, 450.0),
                 zoom: 50.0
             }
         )
@@ -202,13 +178,12 @@ mod test {
 
     #[test]
     fn from_bounds_vertical_fit() {
-        let t =
-            Transformation::from_bounds(Pt { x: 200.0, y: 100.0 }, Pt { x: 700.0, y: 200.0 }, true);
+        let t = Transformation::from_bounds(Pt::new(200.0, 100.0), Pt::new(700.0, 200.0), true);
 
         assert_eq!(
             t,
             Transformation {
-                offset: Pt { x: 450.0, y: 150.0 },
+                offset: Pt::new(450.0, 150.0),
                 zoom: 50.0
             }
         )
@@ -217,12 +192,12 @@ mod test {
     #[test]
     fn inverse() {
         let t = Transformation {
-            offset: Pt { x: 150.0, y: 450.0 },
+            offset: Pt::new(150.0, 450.0),
             zoom: 50.0,
         };
         let t_ = t.inverse();
 
-        let p_orig = Pt { x: 100.0, y: 200.0 };
+        let p_orig = Pt::new(100.0, 200.0);
         let p_trans = t.transform_pt(p_orig);
         let p_back = t_.transform_pt(p_trans);
 
@@ -232,15 +207,15 @@ mod test {
     #[test]
     fn chain() {
         let t1 = Transformation {
-            offset: Pt { x: 123.0, y: 671.0 },
+            offset: Pt::new(123.0, 671.0),
             zoom: 10.0,
         };
         let t2 = Transformation {
-            offset: Pt { x: 71.0, y: 171.0 },
+            offset: Pt::new(71.0, 171.0),
             zoom: 0.5,
         };
 
-        let p = Pt { x: 3.0, y: 9.0 };
+        let p = Pt::new(3.0, 9.0);
         let p1 = t2.transform_pt(t1.transform_pt(p));
         let p2 = t1.chain(&t2).transform_pt(p);
 
@@ -13,11 +13,13 @@ use iced::{
     Task,
 };
 use types::{
-    core::{Circ, Ident, Line, Pt, Value, ValueType},
+    core::{Circ, Ident, Line, Polyline, Pt, PtOn, Value, ValueType},
     lang::{Definition, Expr, ValueDefinition},
+    layer::Layer,
+    style::Style,
 };
 
-use crate::{helpers::perform_or_status, mode::Mode, status_bar_w::StatusMessage};
+use crate::{helpers::perform_or_status, mode::Mode, status_bar_w::StatusMessage, trace::Traces};
 
 #[derive(Debug, Clone)]
 pub enum Msg {
@@ -29,10 +31,33 @@ pub enum Msg {
 
     CreatePoint(Ident, Pt),
     MovePoint(Ident, Pt),
+    /// Moves a constrained point by setting it's parameter.
+    SetParameter(Ident, f64),
     Delete(Ident),
+    ToggleTrace(Ident),
     PickFunctionArg(Ident),
 }
 
+/// Items of the scope along with their presentation, that the canvas draws.
+#[derive(Debug, Clone, Copy)]
+pub struct CanvasData<'a> {
+    pub vars: &'a HashMap<Ident, Value>,
+
+    /// Styles of items, that are not drawn in the default way.
+    pub styles: &'a HashMap<Ident, Style>,
+
+    /// Labels of items, that have them shown.
+    pub labels: &'a HashMap<Ident, String>,
+
+    pub layers: &'a [Layer],
+
+    /// Points, that are constrained to objects and are moved by their parameters.
+    pub pt_ons: &'a HashMap<Ident, PtOn>,
+
+    /// Trails of traced points, that are drawn under all items.
+    pub traces: &'a Traces,
+}
+
 #[derive(Debug)]
 pub struct State {
     custom_transformation: Transformation,
@@ -45,15 +70,15 @@ impl State {
         }
     }
 
-    pub fn view<'a>(&'a self, vars: &'a HashMap<Ident, Value>, mode: &'a Mode) -> Element<'a, Msg> {
-        responsive(|size| {
+    pub fn view<'a>(&'a self, data: CanvasData<'a>, mode: &'a Mode) -> Element<'a, Msg> {
+        responsive(move |size| {
             let w = size.width as f64;
             let h = size.height as f64;
             let unify_transformation =
-                Transformation::from_bounds(Pt { x: 0.0, y: 0.0 }, Pt { x: w, y: h }, true);
+                Transformation::from_bounds(Pt::new(0.0, 0.0), Pt::new(w, h), true);
 
             canvas::Canvas::new(Program {
-                vars,
+                data,
                 mode,
                 unify_transformation,
                 custom_transformation: self.custom_transformation,
@@ -73,7 +98,7 @@ impl State {
         vars: &'a HashMap<Ident, Value>,
     ) -> Task<Msg> {
         match msg {
-            Msg::SetStatusMessage(_) | Msg::SetMode(_) => {
+            Msg::SetStatusMessage(_) | Msg::SetMode(_) | Msg::ToggleTrace(_) => {
                 unreachable!("should have been processed in parent widget")
             }
             Msg::None => Task::none(),
@@ -91,6 +116,9 @@ impl State {
             Msg::MovePoint(name, pt) => {
                 perform_or_status!(async move { client.set(name, Expr::Value(pt.into())).await })
             }
+            Msg::SetParameter(name, t) => {
+                perform_or_status!(async move { client.set(name, Expr::Value(t.into())).await })
+            }
             Msg::Delete(name) => perform_or_status!({
                 let client = client.clone();
                 async move { client.rm(name).await }
@@ -137,12 +165,32 @@ impl State {
         let bounds = vars
             .values()
             .filter_map(|value| match value {
-                Value::Pt(Some(Pt { x, y })) => Some((*x, *y, *x, *y)),
-                Value::Line(Some(Line {
-                    p1: Pt { x: x1, y: y1 },
-                    p2: Pt { x: x2, y: y2 },
-                })) => Some((x1.min(*x2), y1.min(*y2), x1.max(*x2), y1.max(*y2))),
-                Value::Circ(Some(Circ { o: Pt { x, y }, r })) => Some((x - r, y - r, x + r, x + r)),
+                Value::Pt(Some(pt)) => {
+                    let (x, y) = pt.to_f64();
+                    Some((x, y, x, y))
+                }
+                Value::Line(Some(Line { p1, p2 })) => {
+                    let ((x1, y1), (x2, y2)) = (p1.to_f64(), p2.to_f64());
+                    Some((x1.min(x2), y1.min(y2), x1.max(x2), y1.max(y2)))
+                }
+                Value::Circ(Some(Circ { o, r })) => {
+                    let ((x, y), r) = (o.to_f64(), r.to_f64());
+                    Some((x - r, y - r, x + r, x + r))
+                }
+                Value::Polyline(Some(Polyline { pts })) => pts
+                    .iter()
+                    .map(|pt| {
+                        let (x, y) = pt.to_f64();
+                        (x, y, x, y)
+                    })
+                    .reduce(|(min_x_1, min_y_1, max_x_1, max_y_1), (x, y, _, _)| {
+                        (
+                            min_x_1.min(x),
+                            min_y_1.min(y),
+                            max_x_1.max(x),
+                            max_y_1.max(y),
+                        )
+                    }),
                 _ => None,
             })
             .reduce(
@@ -160,12 +208,9 @@ impl State {
             return;
         };
 
-        let mut t = Transformation::from_bounds(
-            Pt { x: min_x, y: min_y },
-            Pt { x: max_x, y: max_y },
-            false,
-        )
-        .inverse();
+        let mut t =
+            Transformation::from_bounds(Pt::new(min_x, min_y), Pt::new(max_x, max_y), false)
+                .inverse();
 
         // Some padding
         t.zoom /= 1.1;
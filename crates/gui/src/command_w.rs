@@ -17,7 +17,7 @@ use iced::{
 use iced_aw::{grid_row, Grid, GridRow};
 use itertools::Itertools;
 
-use crate::helpers::my_tooltip;
+use crate::{helpers::my_tooltip, status_bar_w::StatusMessage};
 
 #[derive(Debug)]
 pub struct State {
@@ -27,6 +27,8 @@ pub struct State {
 
 #[derive(Debug, Clone)]
 pub enum Msg {
+    SetStatusMessage(StatusMessage),
+
     ScriptEditorAction(text_editor::Action),
     SendScript,
     // Arc-ing ScriptResult to make in Clonable
@@ -121,9 +123,16 @@ impl State {
                     |res| Msg::GotScriptResult(Arc::new(res)),
                 );
             }
+            Msg::SetStatusMessage(_) => {
+                unreachable!("should have been processed in parent widget")
+            }
             Msg::GotScriptResult(script_result) => {
+                let warnings = StatusMessage::from_warnings(&script_result.warnings);
                 self.scripts_and_results
                     .push(ScriptOrResult::Result(script_result));
+                if let Some(warnings) = warnings {
+                    return Task::done(Msg::SetStatusMessage(warnings));
+                }
             }
             Msg::ScriptEditorAction(action) => self.script_editor_content.perform(action),
         }
@@ -161,6 +170,11 @@ impl ScriptOrResult {
                     printed_something = true;
                 }
 
+                for warning in &res.warnings {
+                    body = body.push(text!("warning: {warning}"));
+                    printed_something = true;
+                }
+
                 if let Some(err) = &res.error {
                     body = body.push(text!("{:?}", err));
                     printed_something = true;
@@ -8,6 +8,7 @@ mod mode_selector_w;
 mod my_colors;
 mod status_bar_w;
 mod top_bar_w;
+mod trace;
 mod variable_w;
 
 fn main() -> anyhow::Result<()> {
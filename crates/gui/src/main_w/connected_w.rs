@@ -8,7 +8,12 @@ use iced::{
     Length::Fill,
     Task, Theme,
 };
-use types::core::{Ident, Value};
+use types::{
+    core::{Ident, PtOn, Value},
+    layer::Layer,
+    slider::Slider,
+    style::Style,
+};
 
 use crate::{
     args_w, canvas_w, command_w,
@@ -16,13 +21,21 @@ use crate::{
     mode::Mode,
     mode_selector_w::{self},
     status_bar_w::StatusMessage,
-    top_bar_w, variable_w,
+    top_bar_w,
+    trace::Traces,
+    variable_w,
 };
 
 #[derive(Debug)]
 pub struct State {
     client: Client,
     vars: HashMap<Ident, Value>,
+    styles: HashMap<Ident, Style>,
+    labels: HashMap<Ident, String>,
+    layers: Vec<Layer>,
+    pt_ons: HashMap<Ident, PtOn>,
+    sliders: HashMap<Ident, Slider>,
+    traces: Traces,
     panes: pane_grid::State<Pane>,
 
     mode: Mode,
@@ -40,7 +53,14 @@ pub enum Msg {
     PaneClose(pane_grid::Pane),
 
     SetStatusMessage(StatusMessage),
-    GotVars(HashMap<Ident, Value>),
+    GotVars(
+        HashMap<Ident, Value>,
+        HashMap<Ident, Style>,
+        HashMap<Ident, String>,
+        Vec<Layer>,
+        HashMap<Ident, PtOn>,
+        HashMap<Ident, Slider>,
+    ),
     Disconnected,
 
     CanvasWMsg(canvas_w::Msg),
@@ -94,18 +114,18 @@ impl State {
             mode_selector_w::State::run_with(client.clone());
         let mode_selector_w_task = mode_selector_w_task.map(Msg::ModeSelectorW);
 
-        let fetch_vars_task = perform_or_status!(
-            {
-                let client = client.clone();
-                async move { client.get_all_items().await }
-            },
-            Msg::GotVars
-        );
+        let fetch_vars_task = Self::fetch_vars(client.clone());
 
         (
             Self {
                 client: client.clone(),
                 vars: Default::default(),
+                styles: Default::default(),
+                labels: Default::default(),
+                layers: Default::default(),
+                pt_ons: Default::default(),
+                sliders: Default::default(),
+                traces: Default::default(),
                 panes,
 
                 mode: Default::default(),
@@ -134,14 +154,24 @@ impl State {
                 Pane::CanvasW => (
                     "",
                     self.canvas_w
-                        .view(&self.vars, &self.mode)
+                        .view(
+                            canvas_w::CanvasData {
+                                vars: &self.vars,
+                                styles: &self.styles,
+                                labels: &self.labels,
+                                layers: &self.layers,
+                                pt_ons: &self.pt_ons,
+                                traces: &self.traces,
+                            },
+                            &self.mode,
+                        )
                         .map(Msg::CanvasWMsg),
                 ),
                 Pane::CommandW => ("Command Line", self.command_w.view().map(Msg::CommandWMsg)),
                 Pane::VariableW => (
                     "Variables",
                     self.variable_w
-                        .view(&self.vars, &self.mode)
+                        .view(&self.vars, &self.layers, &self.sliders, &self.mode)
                         .map(Msg::VariableWMsg),
                 ),
                 Pane::ModeSelectorW => (
@@ -180,6 +210,23 @@ impl State {
         .into()
     }
 
+    fn fetch_vars(client: Client) -> Task<Msg> {
+        perform_or_status!(
+            async move {
+                let vars = client.get_all_items().await?;
+                let styles = client.get_all_styles().await?;
+                let labels = client.get_all_labels().await?;
+                let layers = client.get_all_layers().await?;
+                let pt_ons = client.get_all_pt_ons().await?;
+                let sliders = client.get_all_sliders().await?;
+                Ok((vars, styles, labels, layers, pt_ons, sliders))
+            },
+            |(vars, styles, labels, layers, pt_ons, sliders)| Msg::GotVars(
+                vars, styles, labels, layers, pt_ons, sliders
+            )
+        )
+    }
+
     fn title_bar_style(theme: &Theme) -> container::Style {
         let palette = theme.extended_palette();
         container::Style {
@@ -220,18 +267,18 @@ impl State {
                 self.panes.close(pane);
                 Task::none()
             }
-            Msg::GotVars(vars) => {
+            Msg::GotVars(vars, styles, labels, layers, pt_ons, sliders) => {
                 // TODO?: sleep before next request?
 
                 self.vars = vars;
+                self.styles = styles;
+                self.labels = labels;
+                self.layers = layers;
+                self.pt_ons = pt_ons;
+                self.sliders = sliders;
+                self.traces.record(&self.vars);
 
-                perform_or_status!(
-                    {
-                        let client = self.client.clone();
-                        async move { client.get_all_items().await }
-                    },
-                    Msg::GotVars
-                )
+                Self::fetch_vars(self.client.clone())
             }
             Msg::CanvasWMsg(msg) => match msg {
                 canvas_w::Msg::SetStatusMessage(message) => {
@@ -241,15 +288,24 @@ impl State {
                     self.mode = mode;
                     Task::none()
                 }
+                canvas_w::Msg::ToggleTrace(name) => {
+                    self.toggle_trace(name);
+                    Task::none()
+                }
                 _ => self
                     .canvas_w
                     .update(msg, self.client.clone(), &self.mode, &self.vars)
                     .map(Msg::CanvasWMsg),
             },
-            Msg::CommandWMsg(msg) => self
-                .command_w
-                .update(msg, self.client.clone())
-                .map(Msg::CommandWMsg),
+            Msg::CommandWMsg(msg) => match msg {
+                command_w::Msg::SetStatusMessage(message) => {
+                    Task::done(Msg::SetStatusMessage(message))
+                }
+                _ => self
+                    .command_w
+                    .update(msg, self.client.clone())
+                    .map(Msg::CommandWMsg),
+            },
             Msg::VariableWMsg(msg) => match msg {
                 variable_w::Msg::SetStatusMessage(message) => {
                     Task::done(Msg::SetStatusMessage(message))
@@ -258,6 +314,10 @@ impl State {
                     self.mode = mode;
                     Task::none()
                 }
+                variable_w::Msg::ToggleTrace(name) => {
+                    self.toggle_trace(name);
+                    Task::none()
+                }
                 _ => self
                     .variable_w
                     .update(msg, self.client.clone(), &self.mode, &self.vars)
@@ -289,9 +349,36 @@ impl State {
                     self.canvas_w.set_identity_transformation();
                     Task::none()
                 }
+                top_bar_w::Msg::ClearTrails => {
+                    self.traces.clear();
+                    Task::none()
+                }
+                top_bar_w::Msg::TrailsToItems => self.trails_to_items(),
                 _ => top_bar_w::update(msg, self.client.clone()).map(Msg::TopBarWMsg),
             },
             Msg::ArgsWMsg(msg) => args_w::update(msg).map(Msg::ArgsWMsg),
         }
     }
+
+    fn toggle_trace(&mut self, name: Ident) {
+        self.traces.toggle(name);
+        // The current position is the start of the trail
+        self.traces.record(&self.vars);
+    }
+
+    /// Defines a polyline item for every trail.
+    fn trails_to_items(&self) -> Task<Msg> {
+        let defs = self.traces.to_definitions(self.vars.keys());
+
+        if defs.is_empty() {
+            return Task::done(Msg::SetStatusMessage(StatusMessage::warn(
+                "there are no trails, pick points to trace in the trace mode",
+            )));
+        }
+
+        let client = self.client.clone();
+        perform_or_status!(async move { client.define(defs).await }, |_| {
+            Msg::SetStatusMessage(StatusMessage::info("trails are saved as items"))
+        })
+    }
 }
@@ -20,6 +20,8 @@ pub enum Mode {
     Modify,
     CreatePoint,
     Delete,
+    /// Toggles leaving a trail of a point's positions.
+    Trace,
     Function(FunctionMode),
 }
 
@@ -31,6 +33,7 @@ impl Mode {
                 | (Mode::Modify, Mode::Modify)
                 | (Mode::Transform, Mode::Transform)
                 | (Mode::Delete, Mode::Delete)
+                | (Mode::Trace, Mode::Trace)
                 | (Mode::Function { .. }, Mode::Function { .. })
         )
     }
@@ -71,6 +74,9 @@ impl Mode {
             Mode::Modify if is_hovered && item_value_type == &ValueType::Pt => {
                 (my_colors::ITEM_MODIFY_HOVERED, mouse::Interaction::Pointer)
             }
+            Mode::Trace if is_hovered && item_value_type == &ValueType::Pt => {
+                (my_colors::ITEM_TRACE_HOVERED, mouse::Interaction::Pointer)
+            }
             Mode::Delete if is_hovered => (
                 my_colors::ITEM_DELETE_HOVERED,
                 mouse::Interaction::NotAllowed,
@@ -88,6 +94,7 @@ impl Display for Mode {
             Mode::Modify => write!(f, "Modify"),
             Mode::Transform => write!(f, "Transform"),
             Mode::Delete => write!(f, "Delete"),
+            Mode::Trace => write!(f, "Trace"),
             Mode::Function { .. } => write!(f, "Function"),
         }
     }
@@ -177,6 +184,8 @@ impl FunctionMode {
                         .collect_vec(),
                 }),
             }))
-            .await
+            .await?;
+
+        Ok(())
     }
 }
@@ -84,6 +84,7 @@ impl State {
             Mode::Modify,
             Mode::CreatePoint,
             Mode::Delete,
+            Mode::Trace,
         ];
 
         for mode in basic_modes {
@@ -68,3 +68,15 @@ pub static ITEM_DELETE_HOVERED: Color = Color {
     b: 0.0,
     a: 1.0,
 };
+pub static ITEM_TRACE_HOVERED: Color = Color {
+    r: 1.0,
+    g: 0.5,
+    b: 0.0,
+    a: 1.0,
+};
+pub static TRAIL: Color = Color {
+    r: 1.0,
+    g: 0.5,
+    b: 0.0,
+    a: 0.6,
+};
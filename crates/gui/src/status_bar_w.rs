@@ -10,6 +10,8 @@ use iced::{
     Task, Theme,
 };
 
+use types::lang::Warning;
+
 use crate::{helpers::my_tooltip, my_colors};
 
 #[derive(Debug, Clone)]
@@ -39,6 +41,21 @@ impl StatusMessage {
     pub fn info(text: impl ToString) -> Self {
         Self::new(StatusMessageKind::Info, text)
     }
+
+    /// Joins warnings into a single message. Returns `None` if there are no warnings.
+    pub fn from_warnings(warnings: &[Warning]) -> Option<Self> {
+        if warnings.is_empty() {
+            return None;
+        }
+
+        Some(Self::warn(
+            warnings
+                .iter()
+                .map(|warning| warning.to_string())
+                .collect::<Vec<_>>()
+                .join("; "),
+        ))
+    }
 }
 
 impl Display for StatusMessage {
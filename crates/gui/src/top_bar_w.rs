@@ -12,6 +12,7 @@ use iced_aw::menu_bar;
 use iced_aw::{menu, Menu};
 use iced_aw::{menu::Item, style::menu_bar};
 use rfd::AsyncFileDialog;
+use types::export::{ExportOptions, PdfOptions};
 
 use crate::helpers::perform_or_status;
 
@@ -26,12 +27,20 @@ pub enum Msg {
     Save,
     Load,
     ExportAsSvg,
+    ExportAsPng,
+    ExportAsPdf,
     Clear,
 
     // Transformation Menu
     SetIdentityTransformation,
     SetFitAllTransformation,
 
+    // View Menu
+    ShowLabels,
+    HideLabels,
+    ClearTrails,
+    TrailsToItems,
+
     // Server Menu
     Disconnect,
 }
@@ -47,6 +56,10 @@ pub fn view<'a>() -> Element<'a, Msg> {
             bar_item(text("Transformation")),
             transformation_menu()
         )
+        (
+            bar_item(text("View")),
+            view_menu()
+        )
         (
             bar_item(text("Server")),
             server_menu()
@@ -65,6 +78,8 @@ fn file_menu<'a>() -> Menu<'a, Msg, Theme, Renderer> {
         (menu_item(text("Save"), Msg::Save))
         (menu_item(text("Load"), Msg::Load))
         (menu_item(text("Export as SVG"), Msg::ExportAsSvg))
+        (menu_item(text("Export as PNG"), Msg::ExportAsPng))
+        (menu_item(text("Export as PDF"), Msg::ExportAsPdf))
         (menu_item(text("Clear"), Msg::Clear))
     );
 
@@ -90,6 +105,18 @@ fn transformation_menu<'a>() -> Menu<'a, Msg, Theme, Renderer> {
     ans.width(Shrink)
 }
 
+fn view_menu<'a>() -> Menu<'a, Msg, Theme, Renderer> {
+    #[rustfmt::skip]
+    let ans = menu!(
+        (menu_item(text("Show Labels"), Msg::ShowLabels))
+        (menu_item(text("Hide Labels"), Msg::HideLabels))
+        (menu_item(text("Clear Trails"), Msg::ClearTrails))
+        (menu_item(text("Trails to Items"), Msg::TrailsToItems))
+    );
+
+    ans.width(Shrink)
+}
+
 fn menu_item<'a>(content: impl Into<Element<'a, Msg>>, on_press: Msg) -> Element<'a, Msg> {
     menu_item_base(content, Some(on_press), MENU_ITEM_WIDTH)
 }
@@ -135,7 +162,9 @@ pub fn update(msg: Msg, client: Client) -> Task<Msg> {
 
         Msg::SetStatusMessage(_)
         | Msg::SetIdentityTransformation
-        | Msg::SetFitAllTransformation => {
+        | Msg::SetFitAllTransformation
+        | Msg::ClearTrails
+        | Msg::TrailsToItems => {
             unreachable!("should have been processed in parent widget")
         }
 
@@ -170,12 +199,43 @@ pub fn update(msg: Msg, client: Client) -> Task<Msg> {
                 .await
                 .ok_or(anyhow!("file was not selected"))?;
 
-            client.save_svg(file.path()).await?;
+            client
+                .save_svg(file.path(), ExportOptions::default())
+                .await?;
+
+            Ok(())
+        }),
+        Msg::ExportAsPng => perform_or_status!(async move {
+            let file = AsyncFileDialog::new()
+                .add_filter("PNG File", &["png"])
+                .set_file_name("drawing.png")
+                .save_file()
+                .await
+                .ok_or(anyhow!("file was not selected"))?;
+
+            client
+                .save_png(file.path(), ExportOptions::default())
+                .await?;
+
+            Ok(())
+        }),
+        Msg::ExportAsPdf => perform_or_status!(async move {
+            let file = AsyncFileDialog::new()
+                .add_filter("PDF File", &["pdf"])
+                .set_file_name("drawing.pdf")
+                .save_file()
+                .await
+                .ok_or(anyhow!("file was not selected"))?;
+
+            client.save_pdf(file.path(), PdfOptions::default()).await?;
 
             Ok(())
         }),
         Msg::Clear => perform_or_status!(async move { client.clear().await }),
 
+        Msg::ShowLabels => perform_or_status!(async move { client.show_labels(true).await }),
+        Msg::HideLabels => perform_or_status!(async move { client.show_labels(false).await }),
+
         Msg::Disconnect => unreachable!("should have been processed in parent widget"),
     }
 }
@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+
+use types::{
+    core::{Ident, Polyline, Pt, Value, ValueType},
+    lang::{Definition, Expr, ValueDefinition},
+};
+
+use crate::helpers::new_object_name_with_prefix;
+
+/// Trails of traced points, that are left on the canvas while the points move.
+///
+/// Trails are not items, so they are neither sent to the server nor saved,
+/// unless they are turned into polyline items.
+#[derive(Debug, Default)]
+pub struct Traces {
+    /// Past positions of every traced point, the oldest first.
+    trails: HashMap<Ident, Vec<Pt>>,
+}
+
+impl Traces {
+    pub fn toggle(&mut self, name: Ident) {
+        if self.trails.remove(&name).is_none() {
+            self.trails.insert(name, Vec::new());
+        }
+    }
+
+    pub fn is_traced(&self, name: &Ident) -> bool {
+        self.trails.contains_key(name)
+    }
+
+    /// Appends current positions of traced points, that have moved.
+    ///
+    /// Points, that were removed, stop being traced.
+    pub fn record(&mut self, vars: &HashMap<Ident, Value>) {
+        self.trails.retain(|name, _trail| vars.contains_key(name));
+
+        for (name, trail) in &mut self.trails {
+            if let Some(Value::Pt(Some(pt))) = vars.get(name) {
+                if trail.last() != Some(pt) {
+                    trail.push(*pt);
+                }
+            }
+        }
+    }
+
+    /// Forgets past positions, but keeps tracing.
+    pub fn clear(&mut self) {
+        for trail in self.trails.values_mut() {
+            trail.clear();
+        }
+    }
+
+    /// Trails, that are long enough to be drawn.
+    pub fn polylines(&self) -> impl Iterator<Item = (&Ident, Polyline)> {
+        self.trails
+            .iter()
+            .filter(|(_name, trail)| trail.len() >= 2)
+            .map(|(name, trail)| (name, Polyline::new(trail.clone())))
+    }
+
+    /// Definitions of polyline items for trails, e.g. `trail_A1` for point `A`.
+    ///
+    /// Names of items are not taken from `item_names`.
+    pub fn to_definitions<'a>(
+        &self,
+        item_names: impl Iterator<Item = &'a Ident>,
+    ) -> Vec<Definition> {
+        let mut names: Vec<_> = item_names.cloned().collect();
+        self.polylines()
+            .map(|(name, trail)| {
+                let name = new_object_name_with_prefix(&format!("trail_{name}"), names.iter());
+                names.push(name.clone());
+                Definition::ValueDefinition(ValueDefinition {
+                    name,
+                    value_type: Some(ValueType::Polyline),
+                    body: Expr::Value(trail.into()),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn record() {
+        let name = Ident::from("A");
+        let mut vars = HashMap::from([(name.clone(), Value::from(Pt::new(0.0, 0.0)))]);
+
+        let mut traces = Traces::default();
+        traces.record(&vars);
+        assert!(!traces.is_traced(&name));
+
+        traces.toggle(name.clone());
+        traces.record(&vars);
+        traces.record(&vars);
+        assert_eq!(traces.polylines().count(), 0);
+
+        vars.insert(name.clone(), Value::from(Pt::new(1.0, 0.0)));
+        traces.record(&vars);
+        let trails: Vec<_> = traces.polylines().collect();
+        assert_eq!(trails.len(), 1);
+        assert_eq!(trails[0].1.pts.len(), 2);
+
+        traces.clear();
+        assert!(traces.is_traced(&name));
+        assert_eq!(traces.polylines().count(), 0);
+
+        // Removed points are not traced
+        traces.record(&HashMap::new());
+        assert!(!traces.is_traced(&name));
+    }
+
+    #[test]
+    fn to_definitions() {
+        let (a, b) = (Ident::from("A"), Ident::from("B"));
+        let mut vars = HashMap::from([
+            (a.clone(), Value::from(Pt::new(0.0, 0.0))),
+            (b.clone(), Value::from(Pt::new(0.0, 1.0))),
+            (Ident::from("trail_A1"), Value::from(1)),
+        ]);
+
+        let mut traces = Traces::default();
+        traces.toggle(a.clone());
+        traces.toggle(b.clone());
+        traces.record(&vars);
+        vars.insert(a.clone(), Value::from(Pt::new(1.0, 0.0)));
+        traces.record(&vars);
+
+        // Only trails of points, that have moved, are defined; names of items are not reused
+        let defs = traces.to_definitions(vars.keys());
+        assert_eq!(
+            defs,
+            vec![Definition::ValueDefinition(ValueDefinition {
+                name: Ident::from("trail_A2"),
+                value_type: Some(ValueType::Polyline),
+                body: Expr::Value(Polyline::new(vec![Pt::new(0.0, 0.0), Pt::new(1.0, 0.0)]).into()),
+            })]
+        );
+
+        vars.insert(b.clone(), Value::from(Pt::new(1.0, 1.0)));
+        traces.record(&vars);
+        let mut names: Vec<_> = traces
+            .to_definitions(vars.keys())
+            .into_iter()
+            .map(|def| match def {
+                Definition::ValueDefinition(def) => def.name.0,
+                Definition::FunctionDefinition(def) => def.name.0,
+            })
+            .collect();
+        names.sort();
+        assert_eq!(names, ["trail_A2", "trail_B1"]);
+
+        assert!(Traces::default().to_definitions(vars.keys()).is_empty());
+    }
+}
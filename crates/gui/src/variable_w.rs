@@ -3,23 +3,56 @@ use client::Client;
 use iced::mouse;
 use iced::widget::text;
 use iced::{
-    widget::{column, container, mouse_area, scrollable, text_input},
+    widget::{button, column, container, mouse_area, row, scrollable, slider, text_input},
     Background, Element,
     Length::{Fill, Fixed},
     Task,
 };
 use iced_aw::{grid, grid_row, GridRow};
 use itertools::Itertools;
-use std::collections::HashMap;
-use types::core::{Ident, Value};
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+use types::{
+    core::{Ident, Value, ValueType},
+    export::Sweep,
+    lang::Expr,
+    layer::Layer,
+    slider::Slider,
+};
 
 use crate::{helpers::perform_or_status, mode::Mode, status_bar_w::StatusMessage};
 
+/// Time between frames of a playing slider.
+const PLAY_FRAME_TIME: Duration = Duration::from_millis(50);
+
+/// A slider, that sweeps it's item in a loop.
+#[derive(Debug)]
+struct Playing {
+    name: Ident,
+    sweep: Sweep,
+    frame: u32,
+
+    /// Ticks of a slider, that was paused, have a different one and are ignored.
+    id: u64,
+}
+
 #[derive(Debug)]
 pub struct State {
     new_def_text: String,
     hovered_item: Option<Ident>,
     currently_editing: Option<(/* var_name: */ Ident, /* var_value: */ String)>,
+
+    /// Layers, whose items are not listed.
+    collapsed_layers: HashSet<Ident>,
+
+    /// The item, whose slider is being dragged, with the slider's value,
+    /// as the item's value is only updated with the next fetch.
+    sliding: Option<(Ident, f64)>,
+
+    playing: Option<Playing>,
+    next_playing_id: u64,
 }
 
 #[derive(Debug, Clone)]
@@ -36,6 +69,19 @@ pub enum Msg {
 
     DefineNew,
     Remove(Ident),
+    ToggleTrace(Ident),
+
+    Slide(Ident, f64),
+    SlideReleased,
+    TogglePlaying(Ident, Sweep),
+    PlayingTick(u64),
+
+    ToggleLayerCollapsed(Ident),
+    UpdateLayer {
+        name: Ident,
+        hidden: Option<bool>,
+        locked: Option<bool>,
+    },
 }
 
 impl State {
@@ -44,31 +90,62 @@ impl State {
             new_def_text: "".to_string(),
             hovered_item: None,
             currently_editing: None,
+            collapsed_layers: HashSet::new(),
+            sliding: None,
+            playing: None,
+            next_playing_id: 0,
         }
     }
 
-    pub fn view<'a>(&'a self, vars: &'a HashMap<Ident, Value>, mode: &'a Mode) -> Element<'a, Msg> {
-        column![self.view_new_def(), self.view_variables(vars, mode)]
-            .spacing(5)
-            .padding(5)
-            .width(Fill)
-            .height(Fill)
-            .into()
+    pub fn view<'a>(
+        &'a self,
+        vars: &'a HashMap<Ident, Value>,
+        layers: &'a [Layer],
+        sliders: &'a HashMap<Ident, Slider>,
+        mode: &'a Mode,
+    ) -> Element<'a, Msg> {
+        column![
+            self.view_new_def(),
+            self.view_variables(vars, layers, sliders, mode)
+        ]
+        .spacing(5)
+        .padding(5)
+        .width(Fill)
+        .height(Fill)
+        .into()
     }
 
     fn view_variables<'a>(
         &'a self,
         vars: &'a HashMap<Ident, Value>,
+        layers: &'a [Layer],
+        sliders: &'a HashMap<Ident, Slider>,
         mode: &'a Mode,
     ) -> Element<'a, Msg> {
-        let rows = vars
+        // Items without a layer go first
+        let mut rows = vars
             .iter()
+            .filter(|(var_name, _)| !layers.iter().any(|layer| layer.contains(var_name)))
             .sorted_by(|(var_name_1, _), (var_name_2, _)| Ord::cmp(&var_name_1.0, &var_name_2.0))
-            .map(|(var_name, var_value)| self.view_grid_row(var_name, var_value, mode));
+            .map(|(var_name, var_value)| {
+                self.view_grid_row(var_name, var_value, sliders.get(var_name), mode)
+            })
+            .collect_vec();
+
+        for layer in layers {
+            rows.push(self.view_layer_row(layer));
+
+            if self.collapsed_layers.contains(&layer.name) {
+                continue;
+            }
+
+            rows.extend(layer.items.iter().filter_map(|var_name| {
+                let var_value = vars.get(var_name)?;
+                Some(self.view_grid_row(var_name, var_value, sliders.get(var_name), mode))
+            }));
+        }
 
-        let ans = grid(rows.collect_vec())
-            .column_widths(&[Fixed(100.0), Fill])
-            .width(Fill);
+        let ans = grid(rows).column_widths(&[Fixed(100.0), Fill]).width(Fill);
 
         let ans = scrollable(ans);
 
@@ -81,6 +158,7 @@ impl State {
         &'a self,
         var_name: &'a Ident,
         var_value: &'a Value,
+        var_slider: Option<&'a Slider>,
         mode: &'a Mode,
     ) -> GridRow<'a, Msg> {
         let name_cell_inner = text!("{var_name}").into();
@@ -94,7 +172,10 @@ impl State {
                     .on_submit(Msg::ApplyCurrentlyEditing)
                     .into()
             }
-            _ => text!("{var_value}").into(),
+            _ => match var_slider {
+                Some(var_slider) => self.view_slider(var_name, var_value, var_slider),
+                None => text!("{var_value}").into(),
+            },
         };
 
         grid_row![
@@ -103,6 +184,94 @@ impl State {
         ]
     }
 
+    /// Shows the value along with a slider, that sets it while being dragged.
+    fn view_slider<'a>(
+        &'a self,
+        var_name: &'a Ident,
+        var_value: &'a Value,
+        var_slider: &'a Slider,
+    ) -> Element<'a, Msg> {
+        let value = match (&self.sliding, var_value) {
+            (Some((sliding_name, value)), _) if sliding_name == var_name => *value,
+            (_, Value::Real(Some(value))) => value.to_f64(),
+            (_, Value::Int(Some(value))) => *value as f64,
+            _ => return text!("{var_value}").into(),
+        };
+
+        // Continuous sliders still need some step
+        let step = var_slider.step.unwrap_or(match var_value {
+            Value::Int(_) => 1.0,
+            _ => (var_slider.max - var_slider.min) / 1000.0,
+        });
+
+        let whole = matches!(var_value, Value::Int(_));
+        let playing = self
+            .playing
+            .as_ref()
+            .is_some_and(|playing| &playing.name == var_name);
+
+        row![
+            text!("{var_value}").width(Fixed(80.0)),
+            slider(var_slider.min..=var_slider.max, value, |value| {
+                Msg::Slide(var_name.clone(), value)
+            })
+            .step(step)
+            .on_release(Msg::SlideReleased),
+            button(text(if playing { "pause" } else { "play" }).size(12))
+                .padding([0, 5])
+                .on_press(Msg::TogglePlaying(
+                    var_name.clone(),
+                    var_slider.sweep(whole)
+                ))
+        ]
+        .spacing(5)
+        .into()
+    }
+
+    fn view_layer_row<'a>(&'a self, layer: &'a Layer) -> GridRow<'a, Msg> {
+        let collapsed = self.collapsed_layers.contains(&layer.name);
+
+        let name_cell = mouse_area(
+            container(text!(
+                "{} {} ({})",
+                if collapsed { "+" } else { "-" },
+                layer.name,
+                layer.items.len()
+            ))
+            .width(Fill)
+            .padding(2.5),
+        )
+        .on_press(Msg::ToggleLayerCollapsed(layer.name.clone()))
+        .interaction(mouse::Interaction::Pointer);
+
+        let flag_button = |label, hidden, locked| {
+            button(text(label).size(12))
+                .padding([0, 5])
+                .on_press(Msg::UpdateLayer {
+                    name: layer.name.clone(),
+                    hidden,
+                    locked,
+                })
+        };
+
+        let flags_cell = row![
+            flag_button(
+                if layer.hidden { "show" } else { "hide" },
+                Some(!layer.hidden),
+                None
+            ),
+            flag_button(
+                if layer.locked { "unlock" } else { "lock" },
+                None,
+                Some(!layer.locked)
+            ),
+        ]
+        .spacing(5)
+        .padding(2.5);
+
+        grid_row![name_cell, flags_cell]
+    }
+
     fn view_cell<'a>(
         &'a self,
         mode: &'a Mode,
@@ -138,6 +307,9 @@ impl State {
                     Msg::CurrentlyEditingChanged(Some((var_name.clone(), var_value.to_string())))
                 }
                 Mode::Delete => Msg::Remove(var_name.clone()),
+                Mode::Trace if var_value.value_type() == ValueType::Pt => {
+                    Msg::ToggleTrace(var_name.clone())
+                }
                 Mode::Function(func_mode)
                     if func_mode.next_arg_type() == var_value.value_type() =>
                 {
@@ -189,7 +361,7 @@ impl State {
         vars: &'a HashMap<Ident, Value>,
     ) -> Task<Msg> {
         match msg {
-            Msg::SetStatusMessage(_) | Msg::SetMode(_) => {
+            Msg::SetStatusMessage(_) | Msg::SetMode(_) | Msg::ToggleTrace(_) => {
                 unreachable!("should have been processed in parent widget")
             }
             Msg::None => Task::none(),
@@ -198,17 +370,95 @@ impl State {
                 Task::none()
             }
             Msg::DefineNew => {
-                let task = perform_or_status!({
-                    let def = self.new_def_text.trim().to_string();
+                let task = perform_or_status!(
+                    {
+                        let def = self.new_def_text.trim().to_string();
 
-                    // FIXME: this method technically allows defining functions,
-                    // which is not the intended behaviour
-                    async move { client.define_one(def.clone()).await }
-                });
+                        // FIXME: this method technically allows defining functions,
+                        // which is not the intended behaviour
+                        async move { client.define_one(def.clone()).await }
+                    },
+                    |warnings| StatusMessage::from_warnings(&warnings)
+                        .map_or(Msg::None, Msg::SetStatusMessage)
+                );
                 self.new_def_text = "".to_string();
                 task
             }
             Msg::Remove(var_name) => perform_or_status!(async move { client.rm(var_name).await }),
+            Msg::Slide(var_name, value) => {
+                self.sliding = Some((var_name.clone(), value));
+                let value = match vars.get(&var_name) {
+                    Some(Value::Int(_)) => Value::from(value.round() as i64),
+                    _ => Value::from(value),
+                };
+                perform_or_status!(async move { client.set(var_name, Expr::Value(value)).await })
+            }
+            Msg::SlideReleased => {
+                self.sliding = None;
+                Task::none()
+            }
+            Msg::TogglePlaying(var_name, sweep) => {
+                if self
+                    .playing
+                    .take()
+                    .is_some_and(|playing| playing.name == var_name)
+                {
+                    return Task::none();
+                }
+                if sweep.frames == 0 {
+                    return Task::none();
+                }
+
+                let id = self.next_playing_id;
+                self.next_playing_id += 1;
+                self.playing = Some(Playing {
+                    name: var_name,
+                    sweep,
+                    frame: 0,
+                    id,
+                });
+                Task::done(Msg::PlayingTick(id))
+            }
+            Msg::PlayingTick(id) => {
+                let Some(playing) = self.playing.as_mut().filter(|playing| playing.id == id) else {
+                    return Task::none();
+                };
+
+                // The item might have been removed meanwhile
+                let Some(var_value) = vars.get(&playing.name) else {
+                    self.playing = None;
+                    return Task::none();
+                };
+
+                let value = playing.sweep.value(playing.frame);
+                let value = match var_value {
+                    Value::Int(_) => Value::from(value.round() as i64),
+                    _ => Value::from(value),
+                };
+                playing.frame = (playing.frame + 1) % playing.sweep.frames;
+
+                let var_name = playing.name.clone();
+                Task::batch([
+                    perform_or_status!(
+                        async move { client.set(var_name, Expr::Value(value)).await }
+                    ),
+                    Task::future(async move {
+                        tokio::time::sleep(PLAY_FRAME_TIME).await;
+                        Msg::PlayingTick(id)
+                    }),
+                ])
+            }
+            Msg::ToggleLayerCollapsed(name) => {
+                if !self.collapsed_layers.remove(&name) {
+                    self.collapsed_layers.insert(name);
+                }
+                Task::none()
+            }
+            Msg::UpdateLayer {
+                name,
+                hidden,
+                locked,
+            } => perform_or_status!(async move { client.update_layer(name, hidden, locked).await }),
             Msg::HoveredItemChanged(ident) => {
                 self.hovered_item = ident;
                 Task::none()
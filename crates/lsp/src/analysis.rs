@@ -0,0 +1,267 @@
+use std::ops::Range;
+
+use executor::exec::ExecScope;
+use lsp_types::{Diagnostic, DiagnosticSeverity};
+use parser::script_recovering;
+use types::{
+    api::FunctionList,
+    core::{Ident, ValueType},
+    lang::{CommandArg, Definition, FunctionDefinitionArgument, Statement},
+};
+
+use crate::{
+    resolve::{self, Reference, Target},
+    text,
+};
+
+/// Everything, that is known about a document after parsing and compiling it.
+pub struct Analysis {
+    pub diagnostics: Vec<Diagnostic>,
+
+    /// Definitions in order of appearance.
+    pub symbols: Vec<Symbol>,
+
+    /// Functions available at the end of the document.
+    pub funcs: FunctionList,
+
+    comments: Vec<Range<usize>>,
+
+    /// Identifiers of parsed statements in order of appearance.
+    references: Vec<Reference>,
+}
+
+pub struct Symbol {
+    pub name: Ident,
+
+    /// Span of the whole definition.
+    pub span: Range<usize>,
+
+    /// Span of the name in definition.
+    pub name_span: Range<usize>,
+
+    pub kind: SymbolKind,
+}
+
+pub enum SymbolKind {
+    Value {
+        /// Is `None` if the definition failed to compile and has no explicit type.
+        value_type: Option<ValueType>,
+    },
+
+    Function {
+        args: Vec<FunctionDefinitionArgument>,
+        return_type: ValueType,
+    },
+}
+
+impl Symbol {
+    /// Short description like `x:pt` or `f a:int -> pt`.
+    pub fn detail(&self) -> String {
+        match &self.kind {
+            SymbolKind::Value {
+                value_type: Some(value_type),
+            } => format!("{}:{value_type}", self.name),
+            SymbolKind::Value { value_type: None } => self.name.to_string(),
+            SymbolKind::Function { args, return_type } => {
+                let args: String = args
+                    .iter()
+                    .map(|arg| format!(" {}:{}", arg.name, arg.value_type))
+                    .collect();
+                format!("{}{args} -> {return_type}", self.name)
+            }
+        }
+    }
+}
+
+impl Analysis {
+    /// Parses and compiles a document without evaluating anything.
+    pub fn new(text: &str) -> Self {
+        let mut diagnostics = Vec::new();
+        let mut symbols = Vec::new();
+        let mut references = Vec::new();
+
+        let parsed = script_recovering(text);
+
+        for err in parsed.errors {
+            let offset = err.location.offset;
+            diagnostics.push(diagnostic(
+                text,
+                offset..offset,
+                DiagnosticSeverity::ERROR,
+                format!("syntax error: expected {}", err.expected),
+            ));
+        }
+
+        let mut scope = ExecScope::new();
+        for stmt in parsed.statements {
+            let span = stmt.span;
+            references.extend(resolve::references(text, span.clone(), &stmt.value));
+
+            let res = match stmt.value {
+                Statement::Definition(def) => {
+                    let (name, explicit_type, kind) = match &def {
+                        Definition::ValueDefinition(def) => (
+                            def.name.clone(),
+                            def.value_type.clone(),
+                            SymbolKind::Value { value_type: None },
+                        ),
+                        Definition::FunctionDefinition(def) => (
+                            def.name.clone(),
+                            None,
+                            SymbolKind::Function {
+                                args: def.args.clone(),
+                                return_type: def.return_type.clone(),
+                            },
+                        ),
+                    };
+
+                    let res = scope.check_definition(def);
+
+                    let kind = match kind {
+                        SymbolKind::Value { .. } => SymbolKind::Value {
+                            value_type: res
+                                .as_ref()
+                                .ok()
+                                .and_then(|_| scope.get_item(&name))
+                                .map(|value| value.value_type())
+                                .or(explicit_type),
+                        },
+                        kind => kind,
+                    };
+
+                    symbols.push(Symbol {
+                        name_span: span.start..span.start + name.0.len(),
+                        name,
+                        span: span.clone(),
+                        kind,
+                    });
+
+                    res.map_err(anyhow::Error::from)
+                }
+
+                Statement::Command(cmd) if cmd.name.0 == "import" => {
+                    scope.import_command(cmd.args).map_err(anyhow::Error::from)
+                }
+
+                Statement::Command(cmd) => {
+                    if cmd.name.0 == "clear" {
+                        scope.clear();
+                    }
+
+                    cmd.args
+                        .into_iter()
+                        .filter_map(|arg| match arg {
+                            CommandArg::Expr(expr) => Some(expr),
+                            CommandArg::Ident(_) => None,
+                        })
+                        .try_for_each(|expr| scope.check_expr(expr).map(|_| ()))
+                        .map(|()| Vec::new())
+                        .map_err(anyhow::Error::from)
+                }
+            };
+
+            match res {
+                Ok(warnings) => {
+                    for warning in warnings {
+                        diagnostics.push(diagnostic(
+                            text,
+                            span.clone(),
+                            DiagnosticSeverity::WARNING,
+                            warning.to_string(),
+                        ));
+                    }
+                }
+                Err(err) => {
+                    diagnostics.push(diagnostic(
+                        text,
+                        span,
+                        DiagnosticSeverity::ERROR,
+                        format!("{err:#}"),
+                    ));
+                }
+            }
+        }
+
+        let comments = parser::trivia(text)
+            .map(|comments| comments.into_iter().map(|c| c.span).collect())
+            .unwrap_or_default();
+
+        Analysis {
+            diagnostics,
+            symbols,
+            funcs: scope.list_funcs(),
+            comments,
+            references,
+        }
+    }
+
+    /// Returns the identifier at offset, unless it is inside of a comment.
+    pub fn ident_at(&self, text: &str, offset: usize) -> Option<Ident> {
+        if self.comments.iter().any(|span| span.contains(&offset)) {
+            return None;
+        }
+
+        text::ident_at(text, offset).map(|span| Ident::from(&text[span]))
+    }
+
+    /// Type of a function argument, if offset is inside of the function's definition.
+    pub fn arg_type(&self, name: &Ident, offset: usize) -> Option<&ValueType> {
+        self.symbols
+            .iter()
+            .filter(|symbol| symbol.span.contains(&offset))
+            .find_map(|symbol| match &symbol.kind {
+                SymbolKind::Function { args, .. } => args
+                    .iter()
+                    .find(|arg| &arg.name == name)
+                    .map(|arg| &arg.value_type),
+                SymbolKind::Value { .. } => None,
+            })
+    }
+
+    pub fn symbols_named<'a>(&'a self, name: &'a Ident) -> impl Iterator<Item = &'a Symbol> {
+        self.symbols
+            .iter()
+            .filter(move |symbol| &symbol.name == name)
+    }
+
+    /// Spans of definitions of the identifier at offset.
+    ///
+    /// Function arguments and `let` bindings are resolved in their scopes,
+    /// other names are resolved to every top-level definition with the name.
+    pub fn definitions(&self, text: &str, offset: usize) -> Vec<Range<usize>> {
+        let target = self
+            .references
+            .iter()
+            .find(|reference| reference.span.start <= offset && offset <= reference.span.end)
+            .map(|reference| reference.target.clone());
+
+        // Statements with syntax errors are not parsed, so names are looked up as is
+        let name = match target {
+            Some(Target::Local(span)) => return vec![span],
+            Some(Target::Global(name)) => name,
+            None => match self.ident_at(text, offset) {
+                Some(name) => name,
+                None => return Vec::new(),
+            },
+        };
+
+        self.symbols_named(&name)
+            .map(|symbol| symbol.name_span.clone())
+            .collect()
+    }
+}
+
+fn diagnostic(
+    text: &str,
+    span: Range<usize>,
+    severity: DiagnosticSeverity,
+    message: String,
+) -> Diagnostic {
+    Diagnostic {
+        range: text::range(text, span),
+        severity: Some(severity),
+        source: Some("geometrica".to_string()),
+        message,
+        ..Default::default()
+    }
+}
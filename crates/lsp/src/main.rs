@@ -0,0 +1,300 @@
+//! Language server for the Geometrica language.
+//!
+//! Speaks lsp over stdio.
+
+use std::collections::HashMap;
+
+use analysis::{Analysis, SymbolKind};
+use anyhow::Context;
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _,
+        PublishDiagnostics,
+    },
+    request::{Completion, DocumentSymbolRequest, GotoDefinition, HoverRequest, Request as _},
+    CompletionItem, CompletionItemKind, CompletionOptions, CompletionParams, CompletionResponse,
+    DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+    DocumentSymbol, DocumentSymbolParams, DocumentSymbolResponse, GotoDefinitionParams,
+    GotoDefinitionResponse, Hover, HoverContents, HoverParams, HoverProviderCapability, Location,
+    MarkupContent, MarkupKind, OneOf, PublishDiagnosticsParams, ServerCapabilities,
+    TextDocumentPositionParams, TextDocumentSyncCapability, TextDocumentSyncKind, Uri,
+};
+
+mod analysis;
+mod resolve;
+mod text;
+
+const KEYWORDS: &[&str] = &[
+    "if", "then", "else", "let", "in", "as", "none", "true", "false",
+];
+
+fn main() -> anyhow::Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+
+    let capabilities = serde_json::to_value(ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        completion_provider: Some(CompletionOptions::default()),
+        definition_provider: Some(OneOf::Left(true)),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        ..Default::default()
+    })?;
+
+    connection
+        .initialize(capabilities)
+        .context("failed to initialize")?;
+
+    Server::default().run(&connection)?;
+
+    // IO threads stop only after the connection is dropped
+    drop(connection);
+    io_threads.join()?;
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct Server {
+    documents: HashMap<Uri, Document>,
+}
+
+struct Document {
+    text: String,
+    analysis: Analysis,
+}
+
+impl Document {
+    fn new(text: String) -> Self {
+        let analysis = Analysis::new(&text);
+        Document { text, analysis }
+    }
+}
+
+impl Server {
+    fn run(&mut self, connection: &Connection) -> anyhow::Result<()> {
+        for msg in &connection.receiver {
+            match msg {
+                Message::Request(req) => {
+                    if connection.handle_shutdown(&req)? {
+                        return Ok(());
+                    }
+
+                    connection.sender.send(self.handle_request(req).into())?;
+                }
+
+                Message::Notification(not) => {
+                    if let Some(diagnostics) = self.handle_notification(not)? {
+                        connection.sender.send(diagnostics.into())?;
+                    }
+                }
+
+                Message::Response(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn handle_request(&self, req: Request) -> Response {
+        let id = req.id.clone();
+
+        let res = match req.method.as_str() {
+            HoverRequest::METHOD => self.on::<HoverRequest>(req, Server::hover),
+            Completion::METHOD => self.on::<Completion>(req, Server::completion),
+            GotoDefinition::METHOD => self.on::<GotoDefinition>(req, Server::definition),
+            DocumentSymbolRequest::METHOD => {
+                self.on::<DocumentSymbolRequest>(req, Server::document_symbols)
+            }
+            method => {
+                return Response::new_err(
+                    id,
+                    ErrorCode::MethodNotFound as i32,
+                    format!("unknown method {method}"),
+                )
+            }
+        };
+
+        match res {
+            Ok(result) => Response::new_ok(id, result),
+            Err(err) => Response::new_err(id, ErrorCode::InvalidParams as i32, format!("{err:#}")),
+        }
+    }
+
+    fn on<R: lsp_types::request::Request>(
+        &self,
+        req: Request,
+        handler: fn(&Self, R::Params) -> R::Result,
+    ) -> anyhow::Result<serde_json::Value> {
+        let (_id, params) = req.extract::<R::Params>(R::METHOD)?;
+        Ok(serde_json::to_value(handler(self, params))?)
+    }
+
+    /// Returns diagnostics to publish, if the notification changed a document.
+    fn handle_notification(&mut self, not: Notification) -> anyhow::Result<Option<Notification>> {
+        let (uri, diagnostics) = match not.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params =
+                    not.extract::<DidOpenTextDocumentParams>(DidOpenTextDocument::METHOD)?;
+                let uri = params.text_document.uri;
+                let doc = Document::new(params.text_document.text);
+                let diagnostics = doc.analysis.diagnostics.clone();
+                self.documents.insert(uri.clone(), doc);
+                (uri, diagnostics)
+            }
+
+            DidChangeTextDocument::METHOD => {
+                let params =
+                    not.extract::<DidChangeTextDocumentParams>(DidChangeTextDocument::METHOD)?;
+                let uri = params.text_document.uri;
+
+                // Only full sync is supported, so the last change contains the whole text
+                let Some(change) = params.content_changes.into_iter().last() else {
+                    return Ok(None);
+                };
+                let doc = Document::new(change.text);
+                let diagnostics = doc.analysis.diagnostics.clone();
+                self.documents.insert(uri.clone(), doc);
+                (uri, diagnostics)
+            }
+
+            DidCloseTextDocument::METHOD => {
+                let params =
+                    not.extract::<DidCloseTextDocumentParams>(DidCloseTextDocument::METHOD)?;
+                let uri = params.text_document.uri;
+                self.documents.remove(&uri);
+                (uri, Vec::new())
+            }
+
+            _ => return Ok(None),
+        };
+
+        Ok(Some(Notification::new(
+            PublishDiagnostics::METHOD.to_string(),
+            PublishDiagnosticsParams {
+                uri,
+                diagnostics,
+                version: None,
+            },
+        )))
+    }
+
+    /// Returns the document and the byte offset of position.
+    fn locate(&self, params: &TextDocumentPositionParams) -> Option<(&Document, usize)> {
+        let doc = self.documents.get(&params.text_document.uri)?;
+        let offset = text::offset(&doc.text, params.position);
+        Some((doc, offset))
+    }
+
+    fn hover(&self, params: HoverParams) -> Option<Hover> {
+        let (doc, offset) = self.locate(&params.text_document_position_params)?;
+        let name = doc.analysis.ident_at(&doc.text, offset)?;
+
+        let mut lines = Vec::new();
+
+        if let Some(value_type) = doc.analysis.arg_type(&name, offset) {
+            lines.push(format!("{name}:{value_type}"));
+        } else {
+            for symbol in doc.analysis.symbols_named(&name) {
+                lines.push(symbol.detail());
+            }
+
+            for sign in &doc.analysis.funcs.normal_builtins {
+                if sign.name == name {
+                    lines.push(sign.to_string());
+                }
+            }
+        }
+
+        if lines.is_empty() {
+            return None;
+        }
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: format!("```geometrica\n{}\n```", lines.join("\n")),
+            }),
+            range: None,
+        })
+    }
+
+    fn completion(&self, params: CompletionParams) -> Option<CompletionResponse> {
+        let (doc, _offset) = self.locate(&params.text_document_position)?;
+
+        let mut items = Vec::new();
+
+        for symbol in &doc.analysis.symbols {
+            items.push(CompletionItem {
+                label: symbol.name.to_string(),
+                kind: Some(match symbol.kind {
+                    SymbolKind::Value { .. } => CompletionItemKind::VARIABLE,
+                    SymbolKind::Function { .. } => CompletionItemKind::FUNCTION,
+                }),
+                detail: Some(symbol.detail()),
+                ..Default::default()
+            });
+        }
+
+        for sign in &doc.analysis.funcs.normal_builtins {
+            items.push(CompletionItem {
+                label: sign.name.to_string(),
+                kind: Some(CompletionItemKind::FUNCTION),
+                detail: Some(sign.to_string()),
+                ..Default::default()
+            });
+        }
+
+        for keyword in KEYWORDS {
+            items.push(CompletionItem {
+                label: keyword.to_string(),
+                kind: Some(CompletionItemKind::KEYWORD),
+                ..Default::default()
+            });
+        }
+
+        Some(CompletionResponse::Array(items))
+    }
+
+    fn definition(&self, params: GotoDefinitionParams) -> Option<GotoDefinitionResponse> {
+        let uri = &params.text_document_position_params.text_document.uri;
+        let (doc, offset) = self.locate(&params.text_document_position_params)?;
+
+        let locations: Vec<_> = doc
+            .analysis
+            .definitions(&doc.text, offset)
+            .into_iter()
+            .map(|span| Location::new(uri.clone(), text::range(&doc.text, span)))
+            .collect();
+
+        (!locations.is_empty()).then_some(GotoDefinitionResponse::Array(locations))
+    }
+
+    fn document_symbols(&self, params: DocumentSymbolParams) -> Option<DocumentSymbolResponse> {
+        let doc = self.documents.get(&params.text_document.uri)?;
+
+        let symbols = doc
+            .analysis
+            .symbols
+            .iter()
+            .map(|symbol| {
+                #[allow(deprecated)]
+                DocumentSymbol {
+                    name: symbol.name.to_string(),
+                    detail: Some(symbol.detail()),
+                    kind: match symbol.kind {
+                        SymbolKind::Value { .. } => lsp_types::SymbolKind::VARIABLE,
+                        SymbolKind::Function { .. } => lsp_types::SymbolKind::FUNCTION,
+                    },
+                    tags: None,
+                    deprecated: None,
+                    range: text::range(&doc.text, symbol.span.clone()),
+                    selection_range: text::range(&doc.text, symbol.name_span.clone()),
+                    children: None,
+                }
+            })
+            .collect();
+
+        Some(DocumentSymbolResponse::Nested(symbols))
+    }
+}
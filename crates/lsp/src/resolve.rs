@@ -0,0 +1,327 @@
+//! Resolves identifiers to their definitions, taking function arguments and
+//! `let` bindings into account.
+//!
+//! The syntax tree has no spans, so identifiers are found in the source by
+//! walking the tree in source order and searching for every name after the previous one.
+
+use std::ops::Range;
+
+use types::{
+    core::Ident,
+    lang::{CommandArg, Definition, Expr, Statement},
+};
+
+/// An identifier in the source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reference {
+    pub span: Range<usize>,
+    pub target: Target,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Target {
+    /// A function argument or a `let` binding, defined at the span.
+    Local(Range<usize>),
+
+    /// An item, a function or a layer; it's definition is looked up by name.
+    Global(Ident),
+}
+
+/// Returns references of a statement, that is located at `span` of `text`.
+pub fn references(text: &str, span: Range<usize>, stmt: &Statement) -> Vec<Reference> {
+    let mut resolver = Resolver {
+        text,
+        cursor: span.start,
+        end: span.end,
+        locals: Vec::new(),
+        references: Vec::new(),
+    };
+    resolver.statement(stmt);
+    resolver.references
+}
+
+struct Resolver<'a> {
+    text: &'a str,
+
+    /// Identifiers are searched for after it.
+    cursor: usize,
+
+    /// End of the statement.
+    end: usize,
+
+    /// Bindings visible at the cursor, the innermost ones are the last.
+    locals: Vec<(Ident, Range<usize>)>,
+
+    references: Vec<Reference>,
+}
+
+impl Resolver<'_> {
+    fn statement(&mut self, stmt: &Statement) {
+        match stmt {
+            Statement::Definition(Definition::ValueDefinition(def)) => {
+                self.global(&def.name);
+                if def.value_type.is_some() {
+                    self.skip();
+                }
+                self.expr(&def.body);
+            }
+            Statement::Definition(Definition::FunctionDefinition(def)) => {
+                self.global(&def.name);
+                for arg in &def.args {
+                    self.local(&arg.name);
+                    self.skip();
+                }
+                self.skip();
+                self.expr(&def.body);
+            }
+            Statement::Command(cmd) => {
+                self.find(&cmd.name);
+                for arg in &cmd.args {
+                    match arg {
+                        CommandArg::Ident(name) => self.global(name),
+                        CommandArg::Expr(expr) => self.expr(expr),
+                    }
+                }
+            }
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Value(value) => {
+                // `none` is followed by a type
+                if value.is_none() {
+                    self.skip();
+                    self.skip();
+                }
+            }
+            Expr::Variable(name) => self.variable(name),
+            Expr::FuncCall(call) => {
+                // Names starting with `#` are not written in the source
+                if !call.name.0.starts_with('#') {
+                    self.global(&call.name);
+                }
+                for arg in &call.args {
+                    self.expr(arg);
+                }
+            }
+            Expr::If(if_expr) => {
+                for case in &if_expr.cases {
+                    self.expr(&case.cond);
+                    self.expr(&case.value);
+                }
+                if let Some(default_value) = &if_expr.default_value {
+                    self.expr(default_value);
+                }
+            }
+            Expr::Let(let_expr) => {
+                let locals_len = self.locals.len();
+                for def in &let_expr.defs {
+                    // A binding is visible after it's definition
+                    let span = self.find(&def.name);
+                    if let Some(span) = &span {
+                        self.push(span.clone(), Target::Local(span.clone()));
+                    }
+                    if def.value_type.is_some() {
+                        self.skip();
+                    }
+                    self.expr(&def.body);
+                    if let Some(span) = span {
+                        self.locals.push((def.name.clone(), span));
+                    }
+                }
+                self.expr(&let_expr.body);
+                self.locals.truncate(locals_len);
+            }
+            Expr::Infix(infix) => {
+                self.expr(&infix.lhs);
+                self.expr(&infix.rhs);
+            }
+            Expr::Unary(unary) => self.expr(&unary.body),
+            Expr::As(as_expr) => {
+                self.expr(&as_expr.body);
+                self.skip();
+            }
+            Expr::Dot(dot) => {
+                self.expr(&dot.body);
+                self.find(&dot.name);
+            }
+        }
+    }
+
+    /// A use of a variable.
+    fn variable(&mut self, name: &Ident) {
+        let Some(span) = self.find(name) else {
+            return;
+        };
+
+        let target = match self.locals.iter().rev().find(|(local, _)| local == name) {
+            Some((_, def_span)) => Target::Local(def_span.clone()),
+            None => Target::Global(name.clone()),
+        };
+        self.push(span, target);
+    }
+
+    /// A function argument; it is visible till the end of the statement.
+    fn local(&mut self, name: &Ident) {
+        if let Some(span) = self.find(name) {
+            self.push(span.clone(), Target::Local(span.clone()));
+            self.locals.push((name.clone(), span));
+        }
+    }
+
+    fn global(&mut self, name: &Ident) {
+        if let Some(span) = self.find(name) {
+            self.push(span, Target::Global(name.clone()));
+        }
+    }
+
+    fn push(&mut self, span: Range<usize>, target: Target) {
+        self.references.push(Reference { span, target });
+    }
+
+    /// Moves the cursor past the next occurrence of the name and returns it's span.
+    fn find(&mut self, name: &Ident) -> Option<Range<usize>> {
+        let mut cursor = self.cursor;
+        while let Some(span) = next_ident(self.text, cursor, self.end) {
+            cursor = span.end;
+            if self.text[span.clone()] == name.0 {
+                self.cursor = cursor;
+                return Some(span);
+            }
+        }
+        None
+    }
+
+    /// Moves the cursor past the next identifier, e.g. a type or a keyword.
+    fn skip(&mut self) {
+        if let Some(span) = next_ident(self.text, self.cursor, self.end) {
+            self.cursor = span.end;
+        }
+    }
+}
+
+/// Returns span of the first identifier in `start..end`,
+/// skipping numbers, string literals and comments.
+///
+/// Namespaced identifiers like `geo::perp` and predicates like `parallel?`
+/// are returned as a whole.
+fn next_ident(text: &str, start: usize, end: usize) -> Option<Range<usize>> {
+    let is_ident_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+
+    let mut chars = text[start..end]
+        .char_indices()
+        .map(|(i, c)| (start + i, c))
+        .peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let rest = &text[i..end];
+
+        if c == '"' {
+            while let Some((_, c)) = chars.next() {
+                match c {
+                    '\\' => {
+                        chars.next();
+                    }
+                    '"' => break,
+                    _ => {}
+                }
+            }
+        } else if rest.starts_with("//") {
+            chars.find(|&(_, c)| c == '\n');
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            let comment_end = comment.find("*/").map_or(end, |j| i + j + 4);
+            chars.find(|&(j, _)| j + 1 >= comment_end);
+        } else if c.is_ascii_digit() {
+            while chars.next_if(|&(_, c)| is_ident_char(c)).is_some() {}
+        } else if is_ident_char(c) {
+            let mut ident_end = i + c.len_utf8();
+            loop {
+                while let Some((j, c)) = chars.next_if(|&(_, c)| is_ident_char(c)) {
+                    ident_end = j + c.len_utf8();
+                }
+
+                let after = &text[ident_end..end];
+                let next_segment = after
+                    .strip_prefix("::")
+                    .and_then(|s| s.chars().next())
+                    .is_some_and(|c| c.is_ascii_alphabetic() || c == '_');
+                if !next_segment {
+                    break;
+                }
+                chars.next();
+                chars.next();
+            }
+
+            if text[ident_end..end].starts_with('?') {
+                ident_end += 1;
+            }
+            return Some(i..ident_end);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn resolve(text: &str) -> Vec<(&str, Target)> {
+        let stmt = parser::statement(text).unwrap();
+        references(text, 0..text.len(), &stmt)
+            .into_iter()
+            .map(|reference| (&text[reference.span], reference.target))
+            .collect()
+    }
+
+    #[test]
+    fn next_ident() {
+        let text = r#"a1 1e5 "b" /* c */ geo::perp parallel? // d
+            e"#;
+        let idents: Vec<_> =
+            std::iter::successors(super::next_ident(text, 0, text.len()), |span| {
+                super::next_ident(text, span.end, text.len())
+            })
+            .map(|span| &text[span])
+            .collect();
+        assert_eq!(idents, ["a1", "geo::perp", "parallel?", "e"]);
+    }
+
+    #[test]
+    fn shadowing() {
+        let global = |name: &str| Target::Global(Ident::from(name));
+
+        // Arguments shadow items, `let` bindings shadow arguments
+        let text = "f x:int -> int = x + (let x = x * 2, y = x in x + y)";
+        let local = |start: usize| Target::Local(start..start + 1);
+        assert_eq!(
+            resolve(text),
+            vec![
+                ("f", global("f")),
+                ("x", local(2)),
+                ("x", local(2)),
+                ("x", local(26)),
+                ("x", local(2)),
+                ("y", local(37)),
+                ("x", local(26)),
+                ("x", local(26)),
+                ("y", local(37)),
+            ]
+        );
+
+        // Fields and types are not references
+        let text = "x:pt = let pt:pt = pt (none real) 1.0 in pt.x";
+        let refs = resolve(text);
+        assert_eq!(refs[0], ("x", global("x")));
+        assert_eq!(refs[1], ("pt", Target::Local(11..13)));
+        assert_eq!(refs[2], ("pt", global("pt")));
+        assert_eq!(refs[3], ("pt", Target::Local(11..13)));
+        assert_eq!(refs.len(), 4);
+
+        assert_eq!(
+            resolve(r#"style! A B "A""#),
+            vec![("A", global("A")), ("B", global("B"))]
+        );
+    }
+}
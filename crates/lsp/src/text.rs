@@ -0,0 +1,124 @@
+//! Conversions between byte offsets and lsp positions, which count utf-16 code units.
+
+use std::ops::Range;
+
+use lsp_types::Position;
+
+pub fn position(text: &str, offset: usize) -> Position {
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+    Position::new(
+        before.matches('\n').count() as u32,
+        before[line_start..].encode_utf16().count() as u32,
+    )
+}
+
+pub fn range(text: &str, span: Range<usize>) -> lsp_types::Range {
+    lsp_types::Range::new(position(text, span.start), position(text, span.end))
+}
+
+/// Converts a position to a byte offset; positions past the end of line are clamped.
+pub fn offset(text: &str, position: Position) -> usize {
+    let line_start: usize = text
+        .split_inclusive('\n')
+        .take(position.line as usize)
+        .map(str::len)
+        .sum();
+
+    let mut character = 0;
+    for (i, c) in text[line_start..].char_indices() {
+        if character >= position.character || c == '\n' {
+            return line_start + i;
+        }
+        character += c.len_utf16() as u32;
+    }
+
+    text.len()
+}
+
+/// Returns span of an identifier, that contains or ends at offset.
+///
+/// Namespaced identifiers like `geo::perp` and predicates like `parallel?`
+/// are returned as a whole.
+pub fn ident_at(text: &str, offset: usize) -> Option<Range<usize>> {
+    let is_ident_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+
+    let offset = match text[..offset].strip_suffix('?') {
+        Some(before) => before.len(),
+        None => offset,
+    };
+
+    let mut start = offset;
+    loop {
+        start = text[..start]
+            .char_indices()
+            .rev()
+            .take_while(|&(_, c)| is_ident_char(c))
+            .last()
+            .map_or(start, |(i, _)| i);
+
+        match text[..start].strip_suffix("::") {
+            Some(before) if before.ends_with(is_ident_char) => start = before.len(),
+            _ => break,
+        }
+    }
+
+    let mut end = offset;
+    loop {
+        end = text[end..]
+            .char_indices()
+            .find(|&(_, c)| !is_ident_char(c))
+            .map_or(text.len(), |(i, _)| end + i);
+
+        match text[end..].strip_prefix("::") {
+            Some(after) if after.starts_with(is_ident_char) => end += 2,
+            _ => break,
+        }
+    }
+    if start < end && text[end..].starts_with('?') {
+        end += 1;
+    }
+
+    let starts_with_digit = text[start..].starts_with(|c: char| c.is_ascii_digit());
+
+    (start < end && !starts_with_digit).then_some(start..end)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn positions() {
+        let text = "ab\nпт = 1\n";
+
+        assert_eq!(position(text, 0), Position::new(0, 0));
+        assert_eq!(position(text, 3), Position::new(1, 0));
+        assert_eq!(position(text, 7), Position::new(1, 2));
+
+        assert_eq!(offset(text, Position::new(1, 2)), 7);
+        assert_eq!(offset(text, Position::new(0, 10)), 2);
+        assert_eq!(offset(text, Position::new(5, 0)), text.len());
+    }
+
+    #[test]
+    fn idents() {
+        let text = "a_1 = b + 12";
+
+        assert_eq!(ident_at(text, 0), Some(0..3));
+        assert_eq!(ident_at(text, 3), Some(0..3));
+        assert_eq!(ident_at(text, 6), Some(6..7));
+        assert_eq!(ident_at(text, 8), None);
+        assert_eq!(ident_at(text, 11), None);
+
+        let text = "ok = parallel? l m";
+        assert_eq!(ident_at(text, 7), Some(5..14));
+        assert_eq!(ident_at(text, 14), Some(5..14));
+
+        let text = "p = geo::perp a:pt";
+        assert_eq!(ident_at(text, 5), Some(4..13));
+        assert_eq!(ident_at(text, 10), Some(4..13));
+        assert_eq!(ident_at(text, 14), Some(14..15));
+    }
+}
@@ -0,0 +1,294 @@
+use std::{
+    io::BufReader,
+    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+};
+
+use lsp_server::{Message, Notification, Request, RequestId};
+use lsp_types::{
+    notification::{DidOpenTextDocument, Exit, Initialized, Notification as _, PublishDiagnostics},
+    request::{
+        Completion, DocumentSymbolRequest, GotoDefinition, HoverRequest, Initialize, Request as _,
+        Shutdown,
+    },
+    CompletionResponse, DiagnosticSeverity, DocumentSymbolResponse, GotoDefinitionResponse, Hover,
+    HoverContents, Position, PublishDiagnosticsParams, Range,
+};
+use serde_json::{json, Value};
+
+/// Talks to the language server binary over stdio.
+struct TestServer {
+    child: Child,
+    stdin: Option<ChildStdin>,
+    stdout: BufReader<ChildStdout>,
+    next_id: i32,
+}
+
+impl TestServer {
+    fn new() -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_lsp"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("failed to spawn lsp");
+
+        let stdin = child.stdin.take();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+
+        let mut server = TestServer {
+            child,
+            stdin,
+            stdout,
+            next_id: 0,
+        };
+
+        server.request(Initialize::METHOD, json!({ "capabilities": {} }));
+        server.notify(Initialized::METHOD, json!({}));
+
+        server
+    }
+
+    fn send(&mut self, msg: Message) {
+        msg.write(self.stdin.as_mut().unwrap()).unwrap();
+    }
+
+    fn recv(&mut self) -> Message {
+        Message::read(&mut self.stdout)
+            .unwrap()
+            .expect("lsp closed stdout")
+    }
+
+    fn notify(&mut self, method: &str, params: Value) {
+        self.send(Notification::new(method.to_string(), params).into());
+    }
+
+    /// Sends a request and waits for it's result, skipping notifications.
+    fn request(&mut self, method: &str, params: Value) -> Value {
+        self.next_id += 1;
+        let id = RequestId::from(self.next_id);
+
+        self.send(Request::new(id.clone(), method.to_string(), params).into());
+
+        loop {
+            if let Message::Response(resp) = self.recv() {
+                assert_eq!(resp.id, id);
+                assert!(resp.error.is_none(), "{:?}", resp.error);
+                return resp.result.unwrap_or(Value::Null);
+            }
+        }
+    }
+
+    fn open(&mut self, uri: &str, text: &str) -> PublishDiagnosticsParams {
+        self.notify(
+            DidOpenTextDocument::METHOD,
+            json!({
+                "textDocument": {
+                    "uri": uri,
+                    "languageId": "geometrica",
+                    "version": 1,
+                    "text": text,
+                }
+            }),
+        );
+
+        loop {
+            if let Message::Notification(not) = self.recv() {
+                if not.method == PublishDiagnostics::METHOD {
+                    return serde_json::from_value(not.params).unwrap();
+                }
+            }
+        }
+    }
+
+    fn at(&mut self, method: &str, uri: &str, line: u32, character: u32) -> Value {
+        self.request(
+            method,
+            json!({
+                "textDocument": { "uri": uri },
+                "position": { "line": line, "character": character },
+            }),
+        )
+    }
+
+    fn shutdown(mut self) {
+        self.request(Shutdown::METHOD, Value::Null);
+        self.notify(Exit::METHOD, Value::Null);
+
+        // The server waits for stdin to be closed
+        self.stdin.take();
+
+        assert!(self.child.wait().unwrap().success());
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        // Does nothing if the server has already exited
+        let _ = self.child.kill();
+    }
+}
+
+const URI: &str = "file:///test.geom";
+
+const SCRIPT: &str = r#"f x:int -> int = x + 1
+a = f 2
+b = a + "s"
+// a
+c = pt 1.0 2.0
+"#;
+
+#[test]
+fn diagnostics() {
+    let mut server = TestServer::new();
+
+    let diagnostics = server.open(URI, SCRIPT).diagnostics;
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::ERROR));
+    assert_eq!(
+        diagnostics[0].range,
+        Range::new(Position::new(2, 0), Position::new(2, 11))
+    );
+
+    let diagnostics = server
+        .open(URI, "x = (1 +\ny = 2\nf a:int -> int = 1")
+        .diagnostics;
+    assert_eq!(
+        diagnostics
+            .iter()
+            .map(|d| (d.range.start.line, d.severity.unwrap()))
+            .collect::<Vec<_>>(),
+        vec![
+            (0, DiagnosticSeverity::ERROR),
+            (2, DiagnosticSeverity::WARNING)
+        ]
+    );
+
+    server.shutdown();
+}
+
+#[test]
+fn hover() {
+    let mut server = TestServer::new();
+    server.open(URI, SCRIPT);
+
+    let hover_text = |server: &mut TestServer, line, character| {
+        let hover: Option<Hover> =
+            serde_json::from_value(server.at(HoverRequest::METHOD, URI, line, character)).unwrap();
+        hover.map(|hover| match hover.contents {
+            HoverContents::Markup(markup) => markup.value,
+            contents => panic!("unexpected hover contents {contents:?}"),
+        })
+    };
+
+    assert!(hover_text(&mut server, 1, 0).unwrap().contains("a:int"));
+    assert!(hover_text(&mut server, 0, 18).unwrap().contains("x:int"));
+    assert!(hover_text(&mut server, 1, 4)
+        .unwrap()
+        .contains("f x:int -> int"));
+    assert!(hover_text(&mut server, 4, 1).unwrap().contains("c:pt"));
+    assert!(hover_text(&mut server, 4, 4)
+        .unwrap()
+        .contains("pt real real"));
+
+    // Comments and literals
+    assert_eq!(hover_text(&mut server, 3, 3), None);
+    assert_eq!(hover_text(&mut server, 1, 6), None);
+
+    server.shutdown();
+}
+
+#[test]
+fn completion() {
+    let mut server = TestServer::new();
+    server.open(URI, SCRIPT);
+
+    let completion: CompletionResponse =
+        serde_json::from_value(server.at(Completion::METHOD, URI, 5, 0)).unwrap();
+    let CompletionResponse::Array(items) = completion else {
+        panic!("expected an array of completions");
+    };
+    let labels: Vec<_> = items.iter().map(|item| item.label.as_str()).collect();
+
+    for label in ["f", "a", "c", "pt", "circ", "let"] {
+        assert!(labels.contains(&label), "no {label} in {labels:?}");
+    }
+
+    server.shutdown();
+}
+
+#[test]
+fn definition() {
+    let mut server = TestServer::new();
+    server.open(URI, SCRIPT);
+
+    let def: GotoDefinitionResponse =
+        serde_json::from_value(server.at(GotoDefinition::METHOD, URI, 1, 4)).unwrap();
+    let GotoDefinitionResponse::Array(locations) = def else {
+        panic!("expected an array of locations");
+    };
+    assert_eq!(locations.len(), 1);
+    assert_eq!(
+        locations[0].range,
+        Range::new(Position::new(0, 0), Position::new(0, 1))
+    );
+
+    let def = server.at(GotoDefinition::METHOD, URI, 2, 4);
+    let def: GotoDefinitionResponse = serde_json::from_value(def).unwrap();
+    let GotoDefinitionResponse::Array(locations) = def else {
+        panic!("expected an array of locations");
+    };
+    assert_eq!(locations[0].range.start, Position::new(1, 0));
+
+    assert_eq!(server.at(GotoDefinition::METHOD, URI, 4, 4), Value::Null);
+
+    // Arguments and let bindings shadow items
+    server.open(
+        URI,
+        "x = 1\nf x:int -> int = let y = x in x + y\ng a:int -> int = let x = a in x\nh = x\n",
+    );
+    let def_start = |server: &mut TestServer, line, character| {
+        let def = server.at(GotoDefinition::METHOD, URI, line, character);
+        let def: GotoDefinitionResponse = serde_json::from_value(def).unwrap();
+        let GotoDefinitionResponse::Array(locations) = def else {
+            panic!("expected an array of locations");
+        };
+        assert_eq!(locations.len(), 1);
+        locations[0].range.start
+    };
+    assert_eq!(def_start(&mut server, 1, 25), Position::new(1, 2));
+    assert_eq!(def_start(&mut server, 1, 30), Position::new(1, 2));
+    assert_eq!(def_start(&mut server, 1, 34), Position::new(1, 21));
+    assert_eq!(def_start(&mut server, 2, 30), Position::new(2, 21));
+    assert_eq!(def_start(&mut server, 2, 21), Position::new(2, 21));
+    assert_eq!(def_start(&mut server, 3, 4), Position::new(0, 0));
+
+    server.shutdown();
+}
+
+#[test]
+fn document_symbols() {
+    let mut server = TestServer::new();
+    server.open(URI, SCRIPT);
+
+    let symbols = server.request(
+        DocumentSymbolRequest::METHOD,
+        json!({ "textDocument": { "uri": URI } }),
+    );
+    let DocumentSymbolResponse::Nested(symbols) = serde_json::from_value(symbols).unwrap() else {
+        panic!("expected nested symbols");
+    };
+
+    assert_eq!(
+        symbols
+            .iter()
+            .map(|s| (s.name.as_str(), s.detail.as_deref().unwrap()))
+            .collect::<Vec<_>>(),
+        vec![
+            ("f", "f x:int -> int"),
+            ("a", "a:int"),
+            ("b", "b"),
+            ("c", "c:pt"),
+        ]
+    );
+
+    server.shutdown();
+}
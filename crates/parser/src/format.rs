@@ -0,0 +1,441 @@
+//! Formatter for scripts.
+//!
+//! The script is parsed and printed back with normalized layout.
+//! Comments are preserved; the ones inside of a statement are moved right before it.
+
+use peg::{error::ParseError, str::LineCol};
+use types::{core::*, lang::*};
+
+use crate::{parser::lang, Comment, Spanned};
+
+const INDENT: usize = 4;
+
+/// `if` and `let` expressions up to this width are kept on a single line.
+const MAX_INLINE_WIDTH: usize = 60;
+
+/// Formats a script; fails if it has a syntax error.
+pub fn format(input: &str) -> Result<String, ParseError<LineCol>> {
+    let statements = lang::spanned_script(input)?;
+    let comments = lang::trivia(input)?;
+
+    let mut comments = comments.into_iter().peekable();
+    let mut items = Vec::new();
+
+    for stmt in statements {
+        while let Some(comment) = comments.next_if(|c| c.span.start < stmt.span.start) {
+            items.push(Spanned {
+                span: comment.span,
+                value: Item::Comment(comment.value),
+            });
+        }
+
+        let mut inner_comments = Vec::new();
+        while let Some(comment) = comments.next_if(|c| c.span.start < stmt.span.end) {
+            inner_comments.push(comment.value);
+        }
+
+        items.push(Spanned {
+            span: stmt.span,
+            value: Item::Statement(stmt.value, inner_comments),
+        });
+    }
+
+    items.extend(comments.map(|comment| Spanned {
+        span: comment.span,
+        value: Item::Comment(comment.value),
+    }));
+
+    let mut out = String::new();
+    let mut prev_end = None;
+
+    for Spanned { span, value } in items {
+        if let Some(prev_end) = prev_end {
+            let newlines = input[prev_end..span.start].matches('\n').count();
+
+            if newlines == 0 && matches!(value, Item::Comment(_)) {
+                // Keep trailing comments on the same line
+                out.push(' ');
+            } else {
+                out.push('\n');
+                if newlines > 1 {
+                    out.push('\n');
+                }
+            }
+        }
+
+        match value {
+            Item::Comment(comment) => out.push_str(&comment_to_string(&comment)),
+            Item::Statement(stmt, inner_comments) => {
+                for comment in inner_comments {
+                    out.push_str(&comment_to_string(&comment));
+                    out.push('\n');
+                }
+                out.push_str(&statement(&stmt));
+            }
+        }
+
+        prev_end = Some(span.end);
+    }
+
+    if !out.is_empty() {
+        out.push('\n');
+    }
+
+    Ok(out)
+}
+
+enum Item {
+    Comment(Comment),
+
+    /// A statement and the comments inside of it.
+    Statement(Statement, Vec<Comment>),
+}
+
+fn comment_to_string(comment: &Comment) -> String {
+    match comment {
+        Comment::Line(text) => Comment::Line(text.trim_end().to_string()).to_string(),
+        Comment::Block(_) => comment.to_string(),
+    }
+}
+
+fn statement(stmt: &Statement) -> String {
+    match stmt {
+        Statement::Definition(def) => definition(def),
+        Statement::Command(cmd) => command(cmd),
+    }
+}
+
+fn definition(def: &Definition) -> String {
+    match def {
+        Definition::ValueDefinition(ValueDefinition {
+            name,
+            value_type,
+            body,
+        }) => format!("{} = {}", typed_name(name, value_type), expr(body, 0)),
+
+        Definition::FunctionDefinition(FunctionDefinition {
+            name,
+            args,
+            return_type,
+            body,
+        }) => {
+            let args: String = args
+                .iter()
+                .map(|arg| format!(" {}:{}", arg.name, arg.value_type))
+                .collect();
+
+            format!("{name}{args} -> {return_type} = {}", expr(body, 0))
+        }
+    }
+}
+
+fn command(cmd: &Command) -> String {
+    let Command { name, args } = cmd;
+
+    let mut s = format!("{name}!");
+    for arg in args {
+        s.push(' ');
+        match arg {
+            CommandArg::Ident(ident) => s.push_str(&ident.0),
+            CommandArg::Expr(e) => s.push_str(&simple_expr(e, 0)),
+        }
+    }
+    s
+}
+
+fn typed_name(name: &Ident, value_type: &Option<ValueType>) -> String {
+    match value_type {
+        Some(value_type) => format!("{name}:{value_type}"),
+        None => name.to_string(),
+    }
+}
+
+/// How tightly an expression binds; mirrors precedence levels of the grammar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Prec {
+    /// `if` and `let`, which consume everything to the right.
+    Block,
+    Or,
+    And,
+    Cmp,
+    Add,
+    Mul,
+    Pow,
+    Unary,
+    Call,
+    Dot,
+    As,
+    Atom,
+}
+
+impl Prec {
+    fn of(e: &Expr) -> Prec {
+        match e {
+            Expr::Value(Value::Int(Some(v))) if *v < 0 => Prec::Unary,
+            Expr::Value(Value::Real(Some(v))) if v.to_f64() < 0.0 => Prec::Unary,
+            Expr::Value(_) | Expr::Variable(_) => Prec::Atom,
+            Expr::FuncCall(_) => Prec::Call,
+            Expr::If(_) | Expr::Let(_) => Prec::Block,
+            Expr::Infix(InfixExpr { op, .. }) => Prec::of_infix(op),
+            Expr::Unary(_) => Prec::Unary,
+            Expr::As(_) => Prec::As,
+            Expr::Dot(_) => Prec::Dot,
+        }
+    }
+
+    fn of_infix(op: &InfixOp) -> Prec {
+        match op {
+            InfixOp::OR => Prec::Or,
+            InfixOp::AND => Prec::And,
+            InfixOp::GR
+            | InfixOp::LE
+            | InfixOp::GEQ
+            | InfixOp::LEQ
+            | InfixOp::EQ
+            | InfixOp::NEQ => Prec::Cmp,
+            InfixOp::ADD | InfixOp::SUB => Prec::Add,
+            InfixOp::MUL | InfixOp::DIV | InfixOp::MOD => Prec::Mul,
+            InfixOp::POW => Prec::Pow,
+        }
+    }
+
+    /// The next tighter level.
+    fn next(self) -> Prec {
+        match self {
+            Prec::Block => Prec::Or,
+            Prec::Or => Prec::And,
+            Prec::And => Prec::Cmp,
+            Prec::Cmp => Prec::Add,
+            Prec::Add => Prec::Mul,
+            Prec::Mul => Prec::Pow,
+            Prec::Pow => Prec::Unary,
+            Prec::Unary => Prec::Call,
+            Prec::Call => Prec::Dot,
+            Prec::Dot => Prec::As,
+            Prec::As | Prec::Atom => Prec::Atom,
+        }
+    }
+}
+
+/// Formats an expression, braced if it binds weaker than `min`.
+fn operand(e: &Expr, min: Prec, indent: usize) -> String {
+    if Prec::of(e) >= min {
+        expr(e, indent)
+    } else {
+        format!("({})", expr(e, indent))
+    }
+}
+
+/// Formats a function call or command argument.
+fn simple_expr(e: &Expr, indent: usize) -> String {
+    match e {
+        Expr::Value(_) | Expr::Variable(_) => expr(e, indent),
+        _ => format!("({})", expr(e, indent)),
+    }
+}
+
+/// Formats an expression; `indent` is the indentation of it's first line.
+fn expr(e: &Expr, indent: usize) -> String {
+    match e {
+        Expr::Value(Value::Real(Some(v))) => format!("{:?}", v.to_f64()),
+        Expr::Value(value) => value.to_string(),
+        Expr::Variable(ident) => ident.to_string(),
+
+        Expr::FuncCall(FuncCallExpr { name, args }) => {
+            let mut s = name.to_string();
+            for arg in args {
+                s.push(' ');
+                s.push_str(&simple_expr(arg, indent));
+            }
+            s
+        }
+
+        Expr::If(if_expr) => format_if(if_expr, indent),
+        Expr::Let(let_expr) => format_let(let_expr, indent),
+
+        Expr::Infix(InfixExpr { lhs, op, rhs }) => {
+            let prec = Prec::of_infix(op);
+            if let InfixOp::POW = op {
+                // Right associative
+                format!(
+                    "{}{op}{}",
+                    operand(lhs, prec.next(), indent),
+                    operand(rhs, prec, indent)
+                )
+            } else {
+                format!(
+                    "{} {op} {}",
+                    operand(lhs, prec, indent),
+                    operand(rhs, prec.next(), indent)
+                )
+            }
+        }
+
+        Expr::Unary(UnaryExpr { op, body }) => {
+            format!("{op}{}", operand(body, Prec::Call, indent))
+        }
+
+        Expr::As(AsExpr { body, value_type }) => {
+            format!("{} as {value_type}", operand(body, Prec::As, indent))
+        }
+
+        Expr::Dot(DotExpr { name, body }) => {
+            format!("{}.{name}", operand(body, Prec::Dot, indent))
+        }
+    }
+}
+
+fn format_if(if_expr: &IfExpr, indent: usize) -> String {
+    let IfExpr {
+        cases,
+        default_value,
+    } = if_expr;
+
+    let case = |case: &IfExprCase, indent| {
+        format!(
+            "{} then {}",
+            operand(&case.cond, Prec::Or, indent),
+            operand(&case.value, Prec::Or, indent)
+        )
+    };
+
+    if let [single_case] = cases.as_slice() {
+        let mut s = format!("if {}", case(single_case, indent));
+        if let Some(default_value) = default_value {
+            s.push_str(&format!(" else {}", expr(default_value, indent)));
+        }
+
+        if fits_inline(&s) {
+            return s;
+        }
+    }
+
+    let inner_indent = indent + INDENT;
+
+    let mut s = "if".to_string();
+    for c in cases {
+        s.push('\n');
+        s.push_str(&" ".repeat(inner_indent));
+        s.push_str(&case(c, inner_indent));
+        s.push(',');
+    }
+
+    if let Some(default_value) = default_value {
+        s.push('\n');
+        s.push_str(&" ".repeat(indent));
+        s.push_str(&format!("else {}", expr(default_value, indent)));
+    }
+
+    s
+}
+
+fn format_let(let_expr: &LetExpr, indent: usize) -> String {
+    let LetExpr { defs, body } = let_expr;
+
+    let def = |def: &LetExprDefinition, indent| {
+        format!(
+            "{} = {}",
+            typed_name(&def.name, &def.value_type),
+            operand(&def.body, Prec::Or, indent)
+        )
+    };
+
+    if let [single_def] = defs.as_slice() {
+        let s = format!("let {} in {}", def(single_def, indent), expr(body, indent));
+
+        if fits_inline(&s) {
+            return s;
+        }
+    }
+
+    let inner_indent = indent + INDENT;
+
+    let mut s = "let".to_string();
+    for d in defs {
+        s.push('\n');
+        s.push_str(&" ".repeat(inner_indent));
+        s.push_str(&def(d, inner_indent));
+        s.push(',');
+    }
+
+    s.push('\n');
+    s.push_str(&" ".repeat(indent));
+    s.push_str(&format!("in {}", expr(body, indent)));
+
+    s
+}
+
+fn fits_inline(s: &str) -> bool {
+    !s.contains('\n') && s.chars().count() <= MAX_INLINE_WIDTH
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Checks, that formatting keeps the meaning and is idempotent.
+    fn check_roundtrip(src: &str) -> String {
+        let formatted = format(src).unwrap();
+
+        assert_eq!(
+            lang::script(&formatted).unwrap(),
+            lang::script(src).unwrap(),
+            "formatted:\n{formatted}"
+        );
+        assert_eq!(
+            lang::trivia(&formatted).unwrap().len(),
+            lang::trivia(src).unwrap().len()
+        );
+        assert_eq!(format(&formatted).unwrap(), formatted);
+
+        formatted
+    }
+
+    #[test]
+    fn examples() {
+        check_roundtrip(include_str!(
+            "../../../examples/geom/altitudes_theorem.geom"
+        ));
+        check_roundtrip(include_str!("../../../examples/geom/cat.geom"));
+        check_roundtrip(include_str!("../../../doc/language/code_example.geom"));
+    }
+
+    #[test]
+    fn spacing() {
+        assert_eq!(
+            check_roundtrip("x=1+2*3\ny:real   =(1+2)*-3\n\n\n\nz = (a^b)^c + a^(b^c)"),
+            "x = 1 + 2 * 3\ny:real = (1 + 2) * -3\n\nz = (a^b)^c + a^b^c\n"
+        );
+        assert_eq!(
+            check_roundtrip("f   a : int b:pt->pt=b*(a as real)\nset!   A  (pt 1.0 -2.0)"),
+            "f a:int b:pt -> pt = b * a as real\nset! A (pt 1.0 -2.0)\n"
+        );
+        assert_eq!(
+            check_roundtrip("a = (f x).y + (p.x).y - -(-z)"),
+            "a = (f x).y + p.x.y - -(-z)\n"
+        );
+    }
+
+    #[test]
+    fn if_let_layout() {
+        assert_eq!(
+            check_roundtrip("x = let a = 1, in if a > 0 then a, else 0"),
+            "x = let a = 1 in if a > 0 then a else 0\n"
+        );
+        assert_eq!(
+            check_roundtrip(
+                "f x:int -> int = let a = x, b = 2 in if a then 1, b then (let c = 3 in c) else 5"
+            ),
+            "f x:int -> int = let\n    a = x,\n    b = 2,\nin if\n    a then 1,\n    \
+             b then (let c = 3 in c),\nelse 5\n"
+        );
+    }
+
+    #[test]
+    fn comments() {
+        assert_eq!(
+            check_roundtrip("// a\nx = 1 // b\n\n\ny = let /* c */ a = 1 in a\n/* d */"),
+            "// a\nx = 1 // b\n\n/* c */\ny = let a = 1 in a\n/* d */\n"
+        );
+    }
+}
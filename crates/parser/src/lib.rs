@@ -1,10 +1,57 @@
+pub use format::format;
 pub use parse_into::ParseInto;
-pub use parser::lang::{command, definition, definitions, expr, script, statement};
+pub use parser::lang::{
+    command, definition, definitions, expr, script, spanned_script, statement, trivia,
+};
+pub use recovery::{line_col, script_recovering, RecoveredScript};
+
+use std::{
+    fmt::{self, Display, Formatter},
+    ops::Range,
+};
 
 use types::lang::*;
 
+mod format;
 mod parse_into;
 mod parser;
+mod recovery;
+
+/// A comment; it is dropped from the syntax tree, but tools like formatter preserve it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Comment {
+    /// Text after `//` till the end of line.
+    Line(String),
+
+    /// Text between `/*` and `*/`.
+    Block(String),
+}
+
+impl Comment {
+    /// Length of the comment in source, including delimiters.
+    pub fn source_len(&self) -> usize {
+        match self {
+            Comment::Line(text) => text.len() + 2,
+            Comment::Block(text) => text.len() + 4,
+        }
+    }
+}
+
+impl Display for Comment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Comment::Line(text) => write!(f, "//{text}"),
+            Comment::Block(text) => write!(f, "/*{text}*/"),
+        }
+    }
+}
+
+/// A value together with the byte range of source, it was parsed from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spanned<T> {
+    pub span: Range<usize>,
+    pub value: T,
+}
 
 fn unary(op: UnaryOp, body: impl Into<Expr>) -> UnaryExpr {
     UnaryExpr {
@@ -2,7 +2,7 @@
 
 use types::{core::*, lang::*};
 
-use super::{infix, unary};
+use super::{infix, unary, Comment, Spanned};
 
 #[cfg(test)]
 mod test;
@@ -16,6 +16,19 @@ peg::parser! {
             stmts
         }
 
+        // Same as `script`, but also records byte spans of statements
+        pub rule spanned_script() -> Vec<Spanned<Statement>>
+            = _ stmts:(spanned_statement() ** __) _
+        {
+            stmts
+        }
+
+        rule spanned_statement() -> Spanned<Statement>
+            = start:position!() value:statement() end:position!()
+        {
+            Spanned { span: start..end, value }
+        }
+
         pub rule statement() -> Statement
             = (def:definition() { def.into() })
             / (cmd:command() { cmd.into() })
@@ -179,9 +192,11 @@ peg::parser! {
         }
 
         // -------------------- Ident --------------------
+        // Namespaced names like `geo::perp` are single idents
+        // Predicates are named with a trailing `?` like `parallel?`
         pub rule ident() -> Ident
             = !keyword()
-                v:$(ident_first_char() ident_char()*)
+                v:$(ident_segment() ("::" ident_segment())* "?"?)
         {
             Ident(v.to_string())
         }
@@ -192,6 +207,9 @@ peg::parser! {
 
         rule eof() = ![_]
 
+        rule ident_segment()
+            = ident_first_char() ident_char()*
+
         rule ident_char() -> char
             = c:['0'..='9'] { c } / ident_first_char()
 
@@ -210,7 +228,9 @@ peg::parser! {
             / "str"
             / "pt"
             / "line"
-            / "circ")
+            / "circ"
+            / "polyline"
+            / "transform")
         {
             match value_type {
                 "bool" => ValueType::Bool,
@@ -220,6 +240,8 @@ peg::parser! {
                 "pt" => ValueType::Pt,
                 "line" => ValueType::Line,
                 "circ" => ValueType::Circ,
+                "polyline" => ValueType::Polyline,
+                "transform" => ValueType::Transform,
                 _ => unreachable!()
             }
         }
@@ -241,9 +263,12 @@ peg::parser! {
                 ("e" ['+'|'-']? ['0'..='9']+)? // exponent
             )
         {?
-            n.parse::<f64>()
-                .map(Value::from)
-                .or(Err("failed to parse real"))
+            match exact_decimal(n) {
+                Some(v) => Ok(Value::from(Real::Exact(v))),
+                None => n.parse::<f64>()
+                    .map(Value::from)
+                    .or(Err("failed to parse real")),
+            }
         }
 
         pub rule _bool() -> Value
@@ -273,19 +298,49 @@ peg::parser! {
 
         // -------------------- Whitespace & Comments --------------------
         // Optional whitespace
-        rule _ = quiet!{(comment() / whitespace())*}
+        rule _ = quiet!{((comment() {}) / whitespace())*}
 
         // Mandatory whitespace
-        rule __ = quiet!{(comment() / whitespace())+}
+        rule __ = quiet!{((comment() {}) / whitespace())+}
 
         // Just for testing `_` rule as it cannot be `pub`
         pub rule empty() = _
 
-        pub rule comment()
-            = "/*" (!"*/" [_])* "*/"
-            / "//" [^ '\n']* "\n"
+        pub rule comment() -> Comment
+            = "/*" text:$((!"*/" [_])*) "*/" { Comment::Block(text.to_string()) }
+            / "//" text:$([^ '\n']*) ("\n" / eof()) { Comment::Line(text.to_string()) }
+
+        // All comments of the input, skipping the ones inside of string literals.
+        // Spans don't include the newline, that ends a line comment.
+        pub rule trivia() -> Vec<Spanned<Comment>>
+            = items:(
+                (start:position!() c:comment() { Some(Spanned { span: start..start + c.source_len(), value: c }) })
+                / (_str() { None })
+                / ([_] { None })
+            )*
+        {
+            items.into_iter().flatten().collect()
+        }
 
         pub rule whitespace()
             = "\n" / " " / "\t"
     }
 }
+
+/// Parses a real literal as a rational, if it fits.
+fn exact_decimal(n: &str) -> Option<Rational> {
+    let (mantissa, exp) = match n.split_once('e') {
+        Some((mantissa, exp)) => (mantissa, exp.parse::<i64>().ok()?),
+        None => (n, 0),
+    };
+    let (int_part, frac_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+
+    let num = format!("{int_part}{frac_part}").parse::<i64>().ok()?;
+    let exp = exp.checked_sub(frac_part.len().try_into().ok()?)?;
+    let pow10 = 10i64.checked_pow(exp.unsigned_abs().try_into().ok()?)?;
+    if exp >= 0 {
+        Rational::new(num.checked_mul(pow10)?, 1)
+    } else {
+        Rational::new(num, pow10)
+    }
+}
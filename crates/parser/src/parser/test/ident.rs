@@ -6,3 +6,24 @@ fn ident() {
     assert_eq!(lang::ident("_"), Ok(Ident("_".to_string())));
     assert_eq!(lang::ident("p1"), Ok(Ident("p1".to_string())));
 }
+
+#[test]
+fn namespaced() {
+    assert_eq!(lang::ident("geo::perp"), Ok(Ident("geo::perp".to_string())));
+    assert_eq!(lang::ident("a::b1::_c"), Ok(Ident("a::b1::_c".to_string())));
+
+    assert!(lang::ident("geo::").is_err());
+    assert!(lang::ident("::perp").is_err());
+    assert!(lang::ident("geo:perp").is_err());
+    assert!(lang::ident("geo::1").is_err());
+}
+
+#[test]
+fn predicate() {
+    assert_eq!(lang::ident("parallel?"), Ok(Ident("parallel?".to_string())));
+    assert_eq!(lang::ident("geo::on?"), Ok(Ident("geo::on?".to_string())));
+
+    assert!(lang::ident("?").is_err());
+    assert!(lang::ident("a??").is_err());
+    assert!(lang::ident("a?::b").is_err());
+}
@@ -20,6 +20,35 @@ fn int() {
 #[test]
 fn real() {
     assert_eq!(lang::real("-12.13e-3"), Ok((-12.13e-3).into()));
+
+    let exact = |num, den| Ok(Value::from(Real::Exact(Rational::new(num, den).unwrap())));
+    assert_eq!(lang::real("0.1"), exact(1, 10));
+    assert!(matches!(
+        lang::real("0.1"),
+        Ok(Value::Real(Some(Real::Exact(_))))
+    ));
+    assert_eq!(lang::real("-12.13e-3"), exact(-1213, 100000));
+    assert_eq!(lang::real("2.5e3"), exact(2500, 1));
+    assert_eq!(lang::real("1e30"), Ok(Value::from(1e30)));
+
+    // Exact literals are converted to the same floats as parsed ones
+    for n in [
+        "0.310299487747164692",
+        "0.1000000000000000055511151231257827",
+        "9007199254740993.0",
+        "-1234567890.123456789",
+        "3.14159265358979323",
+        "2.2250738585072014e-10",
+    ] {
+        match lang::real(n) {
+            Ok(Value::Real(Some(v))) => assert_eq!(v.to_f64(), n.parse::<f64>().unwrap(), "{n}"),
+            v => panic!("{n}: expected real, got {v:?}"),
+        }
+    }
+    assert!(matches!(
+        lang::real("1e30"),
+        Ok(Value::Real(Some(Real::Float(_))))
+    ));
 }
 
 #[test]
@@ -68,4 +97,6 @@ fn value_type() {
     assert_eq!(lang::value_type("pt"), Ok(ValueType::Pt));
     assert_eq!(lang::value_type("line"), Ok(ValueType::Line));
     assert_eq!(lang::value_type("circ"), Ok(ValueType::Circ));
+    assert_eq!(lang::value_type("polyline"), Ok(ValueType::Polyline));
+    assert_eq!(lang::value_type("transform"), Ok(ValueType::Transform));
 }
@@ -8,6 +8,28 @@ fn empty() {
 
 #[test]
 fn comment() {
-    assert_eq!(lang::comment("/* abc */"), Ok(()));
-    assert_eq!(lang::comment("// abc\n"), Ok(()));
+    assert_eq!(
+        lang::comment("/* abc */"),
+        Ok(Comment::Block(" abc ".into()))
+    );
+    assert_eq!(lang::comment("// abc\n"), Ok(Comment::Line(" abc".into())));
+    assert_eq!(lang::comment("// abc"), Ok(Comment::Line(" abc".into())));
+}
+
+#[test]
+fn trivia() {
+    let src = "x = \"// not a comment\" // a\n/* b */ y = 1";
+    assert_eq!(
+        lang::trivia(src),
+        Ok(vec![
+            Spanned {
+                span: 23..27,
+                value: Comment::Line(" a".into())
+            },
+            Spanned {
+                span: 28..35,
+                value: Comment::Block(" b ".into())
+            },
+        ])
+    );
 }
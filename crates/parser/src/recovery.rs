@@ -0,0 +1,223 @@
+use peg::{error::ParseError, str::LineCol};
+use types::lang::Statement;
+
+use crate::{parser::lang, Spanned};
+
+/// Result of [script_recovering].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecoveredScript {
+    /// Statements, that were parsed successfully.
+    pub statements: Vec<Spanned<Statement>>,
+
+    /// Every syntax error found; positions are relative to the whole script.
+    pub errors: Vec<ParseError<LineCol>>,
+}
+
+/// Parses a script, not stopping at the first syntax error.
+///
+/// On an error the parser skips to the next statement boundary,
+/// which is a line starting with an identifier at the very first column.
+/// Continuation lines of a statement are expected to be indented.
+pub fn script_recovering(input: &str) -> RecoveredScript {
+    if let Ok(statements) = lang::spanned_script(input) {
+        return RecoveredScript {
+            statements,
+            errors: Vec::new(),
+        };
+    }
+
+    let mut statements = Vec::new();
+    let mut errors = Vec::new();
+
+    let bounds = statement_boundaries(input);
+    for (i, &start) in bounds.iter().enumerate() {
+        let end = bounds.get(i + 1).copied().unwrap_or(input.len());
+        let chunk = &input[start..end];
+
+        match lang::spanned_script(chunk) {
+            Ok(stmts) => statements.extend(stmts.into_iter().map(|stmt| Spanned {
+                span: stmt.span.start + start..stmt.span.end + start,
+                value: stmt.value,
+            })),
+            Err(mut err) => {
+                // Report unexpected end of statement right after it's last token
+                let offset = err.location.offset.min(chunk.trim_end().len());
+                err.location = line_col(input, offset + start);
+                errors.push(err);
+            }
+        }
+    }
+
+    RecoveredScript { statements, errors }
+}
+
+/// Converts byte offset into [LineCol] the same way peg does.
+pub fn line_col(input: &str, offset: usize) -> LineCol {
+    let before = &input[..offset];
+    LineCol {
+        line: before.bytes().filter(|&c| c == b'\n').count() + 1,
+        column: before.chars().rev().take_while(|&c| c != '\n').count() + 1,
+        offset,
+    }
+}
+
+/// Returns byte offsets of lines, that may start a new statement.
+/// The first returned offset is always 0.
+fn statement_boundaries(input: &str) -> Vec<usize> {
+    let mut bounds = vec![0];
+
+    let mut in_str = false;
+    let mut in_line_comment = false;
+    let mut in_block_comment = false;
+
+    let mut chars = input.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let next = chars.peek().map(|&(_, c)| c);
+
+        if in_str {
+            match c {
+                '\\' => {
+                    chars.next();
+                }
+                '"' => in_str = false,
+                _ => {}
+            }
+        } else if in_line_comment {
+            in_line_comment = c != '\n';
+        } else if in_block_comment {
+            if c == '*' && next == Some('/') {
+                chars.next();
+                in_block_comment = false;
+            }
+        } else {
+            match (c, next) {
+                ('"', _) => in_str = true,
+                ('/', Some('/')) => in_line_comment = true,
+                ('/', Some('*')) => {
+                    chars.next();
+                    in_block_comment = true;
+                }
+                _ => {}
+            }
+        }
+
+        let line_start = i + c.len_utf8();
+        if c == '\n' && !in_str && !in_block_comment && starts_statement(&input[line_start..]) {
+            bounds.push(line_start);
+        }
+    }
+
+    bounds
+}
+
+fn starts_statement(line: &str) -> bool {
+    let word: String = line
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+        .collect();
+
+    let is_ident = word
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_');
+
+    let is_keyword = matches!(
+        word.as_str(),
+        "if" | "let" | "in" | "is" | "as" | "then" | "else" | "none"
+    );
+
+    is_ident && !is_keyword
+}
+
+#[cfg(test)]
+mod test {
+    use types::{
+        core::Ident,
+        lang::{Definition, Statement},
+    };
+
+    use super::*;
+
+    fn names(script: &RecoveredScript) -> Vec<String> {
+        script
+            .statements
+            .iter()
+            .map(|stmt| match &stmt.value {
+                Statement::Definition(Definition::ValueDefinition(def)) => def.name.0.clone(),
+                Statement::Definition(Definition::FunctionDefinition(def)) => def.name.0.clone(),
+                Statement::Command(cmd) => format!("{}!", cmd.name.0),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn no_errors() {
+        let src = "x = 1\ny = 2\nclear!\n";
+        let script = script_recovering(src);
+        assert!(script.errors.is_empty());
+        assert_eq!(names(&script), vec!["x", "y", "clear!"]);
+        assert_eq!(script.statements[1].span, 6..11);
+    }
+
+    #[test]
+    fn skips_broken_statements() {
+        let src = indoc(
+            "
+            a = 1
+            b = (1 +
+                * 2)
+            c = 3
+            d = 4 +
+            /* comment
+            e = 5
+            */
+            f x:int -> int = let
+                y = x,
+            in y
+        ",
+        );
+        let script = script_recovering(&src);
+
+        assert_eq!(names(&script), vec!["a", "c", "f"]);
+        assert_eq!(
+            script
+                .errors
+                .iter()
+                .map(|e| e.location.line)
+                .collect::<Vec<_>>(),
+            vec![4, 9]
+        );
+    }
+
+    #[test]
+    fn spans_are_global() {
+        let src = "a = +\nb = 2";
+        let script = script_recovering(src);
+        assert_eq!(script.errors.len(), 1);
+        assert_eq!(script.errors[0].location.line, 1);
+
+        let b = &script.statements[0];
+        assert_eq!(&src[b.span.clone()], "b = 2");
+        assert_eq!(line_col(src, b.span.start).line, 2);
+        assert!(matches!(
+            &b.value,
+            Statement::Definition(Definition::ValueDefinition(def))
+                if def.name == Ident::from("b")
+        ));
+    }
+
+    /// Removes common indentation, as lines at the first column start statements.
+    fn indoc(s: &str) -> String {
+        let indent = s
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.len() - line.trim_start().len())
+            .min()
+            .unwrap_or(0);
+
+        s.lines()
+            .map(|line| line.get(indent..).unwrap_or(""))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
@@ -21,6 +21,10 @@ struct Cli {
     /// Write listener port to <FILE>. Is usefull when binding to port 0.
     #[arg(long, value_name = "FILE")]
     port_file: Option<PathBuf>,
+
+    /// Look for libraries of `import! <name>` in <DIR>
+    #[arg(long, value_name = "DIR")]
+    lib_dir: Option<PathBuf>,
 }
 
 #[tokio::main]
@@ -46,7 +50,7 @@ async fn main() -> anyhow::Result<()> {
     println!("Welcome to Geometrica Server!");
     println!("Listening on {}...", local_addr);
 
-    axum::serve(listener, router()).await?;
+    axum::serve(listener, router(cli.lib_dir)).await?;
 
     Ok(())
 }
@@ -75,9 +79,9 @@ fn write_port(local_addr: SocketAddr, port_file: PathBuf) -> anyhow::Result<()>
     Ok(())
 }
 
-fn router() -> Router {
+fn router(lib_dir: Option<PathBuf>) -> Router {
     let app = App {
-        scope: Arc::new(Mutex::new(ExecScope::new())),
+        scope: Arc::new(Mutex::new(ExecScope::new().with_lib_dir(lib_dir))),
     };
 
     routes::router().with_state(app)
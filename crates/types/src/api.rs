@@ -1,9 +1,21 @@
 use crate::{
-    core::{Ident, Value},
-    lang::{Definition, Expr, FunctionSignature},
+    check::{CheckOptions, CheckReport},
+    core::{Ident, PtOn, Value},
+    export::{
+        AnimationOptions, DrawingFormat, ExportOptions, FrameFormat, ImportOptions, PdfOptions,
+        Sweep, TikzOptions,
+    },
+    lang::{Definition, Expr, FunctionSignature, Warning},
+    layer::Layer,
+    slider::Slider,
+    style::Style,
 };
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use std::{collections::HashMap, fmt::Display};
+use std::{
+    collections::HashMap,
+    fmt::{Display, Write},
+    path::PathBuf,
+};
 
 pub mod eval {
     use super::*;
@@ -27,7 +39,9 @@ pub mod exec {
         REQUEST {
             defs: Vec<Definition>,
         }
-        RESPONSE {}
+        RESPONSE {
+            warnings: Vec<Warning>,
+        }
     }
 }
 
@@ -86,6 +100,177 @@ pub mod rm {
     }
 }
 
+pub mod style {
+    use super::*;
+
+    pub mod get {
+        use super::*;
+
+        route! {
+            ROUTE "/style/get"
+            REQUEST {
+                name: Ident,
+            }
+            RESPONSE {
+                style: Style,
+            }
+        }
+    }
+
+    pub mod get_all {
+        use super::*;
+
+        route! {
+            ROUTE "/style/get_all"
+            REQUEST {}
+            RESPONSE {
+                styles: HashMap<Ident, Style>,
+            }
+        }
+    }
+
+    pub mod set {
+        use super::*;
+
+        route! {
+            ROUTE "/style/set"
+            REQUEST {
+                name: Ident,
+                style: Style,
+            }
+            RESPONSE {}
+        }
+    }
+}
+
+pub mod slider {
+    use super::*;
+
+    pub mod get_all {
+        use super::*;
+
+        route! {
+            ROUTE "/slider/get_all"
+            REQUEST {}
+            RESPONSE {
+                sliders: HashMap<Ident, Slider>,
+            }
+        }
+    }
+
+    pub mod set {
+        use super::*;
+
+        route! {
+            ROUTE "/slider/set"
+            REQUEST {
+                name: Ident,
+                slider: Option<Slider>,
+            }
+            RESPONSE {}
+        }
+    }
+}
+
+pub mod pt_on {
+    use super::*;
+
+    pub mod get_all {
+        use super::*;
+
+        route! {
+            ROUTE "/pt_on/get_all"
+            REQUEST {}
+            RESPONSE {
+                pt_ons: HashMap<Ident, PtOn>,
+            }
+        }
+    }
+}
+
+pub mod labels {
+    use super::*;
+
+    pub mod get_all {
+        use super::*;
+
+        route! {
+            ROUTE "/labels/get_all"
+            REQUEST {}
+            RESPONSE {
+                labels: HashMap<Ident, String>,
+            }
+        }
+    }
+
+    pub mod show {
+        use super::*;
+
+        route! {
+            ROUTE "/labels/show"
+            REQUEST {
+                show: bool,
+            }
+            RESPONSE {}
+        }
+    }
+}
+
+pub mod layer {
+    use super::*;
+
+    pub mod get_all {
+        use super::*;
+
+        route! {
+            ROUTE "/layer/get_all"
+            REQUEST {}
+            RESPONSE {
+                layers: Vec<Layer>,
+            }
+        }
+    }
+
+    pub mod add {
+        use super::*;
+
+        route! {
+            ROUTE "/layer/add"
+            REQUEST {
+                name: Ident,
+                items: Vec<Ident>,
+            }
+            RESPONSE {}
+        }
+    }
+
+    pub mod update {
+        use super::*;
+
+        route! {
+            ROUTE "/layer/update"
+            REQUEST {
+                name: Ident,
+                hidden: Option<bool>,
+                locked: Option<bool>,
+            }
+            RESPONSE {}
+        }
+    }
+
+    pub mod rm {
+        use super::*;
+
+        route! {
+            ROUTE "/layer/rm"
+            REQUEST {
+                name: Ident,
+            }
+            RESPONSE {}
+        }
+    }
+}
+
 pub mod func {
     use super::*;
 
@@ -112,6 +297,41 @@ pub mod clear {
     }
 }
 
+pub mod import {
+    use super::*;
+
+    route! {
+        ROUTE "/import"
+        REQUEST {
+            source: LibrarySource,
+            namespace: Option<Ident>,
+        }
+        RESPONSE {
+            warnings: Vec<Warning>,
+        }
+    }
+}
+
+pub mod drawing {
+    use super::*;
+
+    pub mod import {
+        use super::*;
+
+        route! {
+            ROUTE "/drawing/import"
+            REQUEST {
+                format: DrawingFormat,
+                content: String,
+                options: ImportOptions,
+            }
+            RESPONSE {
+                items: Vec<Ident>,
+            }
+        }
+    }
+}
+
 pub mod json {
     use super::*;
 
@@ -148,7 +368,9 @@ pub mod svg {
 
         route! {
             ROUTE "/svg/dump"
-            REQUEST {}
+            REQUEST {
+                options: ExportOptions,
+            }
             RESPONSE {
                 svg: String,
             }
@@ -156,6 +378,168 @@ pub mod svg {
     }
 }
 
+pub mod png {
+    use super::*;
+
+    pub mod dump {
+        use super::*;
+
+        route! {
+            ROUTE "/png/dump"
+            REQUEST {
+                options: ExportOptions,
+            }
+            RESPONSE {
+                png: Vec<u8>,
+            }
+        }
+    }
+}
+
+pub mod tikz {
+    use super::*;
+
+    pub mod dump {
+        use super::*;
+
+        route! {
+            ROUTE "/tikz/dump"
+            REQUEST {
+                options: TikzOptions,
+            }
+            RESPONSE {
+                tikz: String,
+            }
+        }
+    }
+}
+
+pub mod pdf {
+    use super::*;
+
+    pub mod dump {
+        use super::*;
+
+        route! {
+            ROUTE "/pdf/dump"
+            REQUEST {
+                options: PdfOptions,
+            }
+            RESPONSE {
+                pdf: Vec<u8>,
+            }
+        }
+    }
+}
+
+pub mod animation {
+    use super::*;
+
+    pub mod svg {
+        use super::*;
+
+        route! {
+            ROUTE "/animation/svg"
+            REQUEST {
+                name: Ident,
+                sweep: Sweep,
+                options: AnimationOptions,
+            }
+            RESPONSE {
+                svg: String,
+            }
+        }
+    }
+
+    pub mod frames {
+        use super::*;
+
+        route! {
+            ROUTE "/animation/frames"
+            REQUEST {
+                name: Ident,
+                sweep: Sweep,
+                format: FrameFormat,
+                options: AnimationOptions,
+            }
+            RESPONSE {
+                frames: Vec<Vec<u8>>,
+            }
+        }
+    }
+}
+
+pub mod tolerance {
+    use super::*;
+
+    pub mod get {
+        use super::*;
+
+        route! {
+            ROUTE "/tolerance/get"
+            REQUEST {}
+            RESPONSE {
+                tolerance: f64,
+            }
+        }
+    }
+
+    pub mod set {
+        use super::*;
+
+        route! {
+            ROUTE "/tolerance/set"
+            REQUEST {
+                tolerance: f64,
+            }
+            RESPONSE {}
+        }
+    }
+}
+
+pub mod exact {
+    use super::*;
+
+    pub mod get {
+        use super::*;
+
+        route! {
+            ROUTE "/exact/get"
+            REQUEST {}
+            RESPONSE {
+                exact: bool,
+            }
+        }
+    }
+
+    pub mod set {
+        use super::*;
+
+        route! {
+            ROUTE "/exact/set"
+            REQUEST {
+                exact: bool,
+            }
+            RESPONSE {}
+        }
+    }
+}
+
+pub mod check {
+    use super::*;
+
+    route! {
+        ROUTE "/check"
+        REQUEST {
+            expr: Expr,
+            options: CheckOptions,
+        }
+        RESPONSE {
+            report: CheckReport,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Error {
     pub msg: String,
@@ -169,6 +553,26 @@ impl Display for Error {
 
 impl std::error::Error for Error {}
 
+/// Converts any error into [Error], keeping messages of the whole source chain.
+pub trait IntoError: Sized {
+    fn into_error(self) -> Error;
+}
+
+impl<T: std::error::Error> IntoError for T {
+    fn into_error(self) -> Error {
+        let mut msg = String::new();
+        let mut error: Option<&dyn std::error::Error> = Some(&self);
+        while let Some(err) = error {
+            write!(msg, "{}", err).unwrap();
+            if err.source().is_some() {
+                write!(msg, ": ").unwrap();
+            }
+            error = err.source();
+        }
+        Error { msg }
+    }
+}
+
 pub trait Request: Serialize + DeserializeOwned {
     const ROUTE: &str;
     type Response: Response;
@@ -230,3 +634,15 @@ pub struct FunctionList {
     /// User-defined functions
     pub user_defined: Vec<FunctionSignature>,
 }
+
+/// Where a library of functions is imported from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LibrarySource {
+    /// A `.geom` file; relative paths are resolved by the server.
+    File(PathBuf),
+
+    /// A library from the server's library directory.
+    ///
+    /// `geo` is read from `<lib_dir>/geo.geom` and `geo::shapes` from `<lib_dir>/geo/shapes.geom`.
+    Lib(Ident),
+}
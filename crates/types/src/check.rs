@@ -0,0 +1,209 @@
+use std::fmt::{self, Display, Formatter};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::core::{Ident, Value};
+
+/// How a bool expression is checked under random perturbations of free items.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct CheckOptions {
+    /// Number of random configurations, that the expression is evaluated in.
+    pub trials: u32,
+
+    /// Size of perturbations relative to the largest coordinate of free items.
+    pub spread: f64,
+
+    /// Part of trials, that may fail without failing the check,
+    /// e.g. because of rounding errors in nearly degenerate configurations.
+    pub max_fail_rate: f64,
+
+    /// Tolerance of comparisons during the trials; the scope's one by default.
+    pub eps: Option<f64>,
+
+    /// Makes perturbations reproducible; they are different every time by default.
+    pub seed: Option<u64>,
+}
+
+impl Default for CheckOptions {
+    fn default() -> Self {
+        CheckOptions {
+            trials: 100,
+            spread: 0.1,
+            max_fail_rate: 0.0,
+            eps: None,
+            seed: None,
+        }
+    }
+}
+
+impl CheckOptions {
+    /// Updates the options with css-like declarations,
+    /// e.g. `trials: 1000; spread: 0.5; max_fail_rate: 0.01; eps: 1e-6; seed: 42`.
+    ///
+    /// `<property>: auto` resets a property.
+    pub fn apply(&mut self, decls: &str) -> Result<(), CheckOptionsError> {
+        for decl in decls
+            .split(';')
+            .map(str::trim)
+            .filter(|decl| !decl.is_empty())
+        {
+            let err = || CheckOptionsError(format!("bad declaration '{decl}'"));
+            let (property, value) = decl.split_once(':').ok_or_else(err)?;
+            let (property, value) = (property.trim(), value.trim());
+            let default = CheckOptions::default();
+
+            match (property, value) {
+                ("trials", "auto") => self.trials = default.trials,
+                ("trials", value) => {
+                    self.trials = value.parse().ok().filter(|v| *v > 0).ok_or_else(err)?
+                }
+
+                ("spread", "auto") => self.spread = default.spread,
+                ("spread", value) => {
+                    self.spread = value
+                        .parse()
+                        .ok()
+                        .filter(|v: &f64| v.is_finite() && *v > 0.0)
+                        .ok_or_else(err)?
+                }
+
+                ("max_fail_rate", "auto") => self.max_fail_rate = default.max_fail_rate,
+                ("max_fail_rate", value) => {
+                    self.max_fail_rate = value
+                        .parse()
+                        .ok()
+                        .filter(|v| (0.0..=1.0).contains(v))
+                        .ok_or_else(err)?
+                }
+
+                ("eps", "auto") => self.eps = default.eps,
+                ("eps", value) => {
+                    self.eps = Some(
+                        value
+                            .parse()
+                            .ok()
+                            .filter(|v: &f64| v.is_finite() && *v >= 0.0)
+                            .ok_or_else(err)?,
+                    )
+                }
+
+                ("seed", "auto") => self.seed = default.seed,
+                ("seed", value) => self.seed = Some(value.parse().map_err(|_| err())?),
+
+                _ => return Err(err()),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Outcome of checking a bool expression.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CheckReport {
+    pub passed: u32,
+    pub failed: u32,
+
+    /// Trials, where the expression is `none` or can not be evaluated,
+    /// e.g. when lines, that should intersect, become parallel.
+    pub skipped: u32,
+
+    /// Values of the perturbed items in the first failed trial.
+    pub counterexample: Option<Vec<(Ident, Value)>>,
+}
+
+impl CheckReport {
+    /// Whether at most `max_fail_rate` of evaluated trials failed.
+    ///
+    /// At least one trial should not be skipped.
+    pub fn holds(&self, max_fail_rate: f64) -> bool {
+        let evaluated = self.passed + self.failed;
+        evaluated != 0 && self.failed as f64 <= max_fail_rate * evaluated as f64
+    }
+
+    /// Part of evaluated trials, that passed.
+    pub fn pass_rate(&self) -> f64 {
+        let evaluated = self.passed + self.failed;
+        if evaluated == 0 {
+            return 0.0;
+        }
+        self.passed as f64 / evaluated as f64
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckOptionsError(pub String);
+
+impl Display for CheckOptionsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for CheckOptionsError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn apply() {
+        let mut options = CheckOptions::default();
+        options
+            .apply("trials: 10; spread: 0.5; max_fail_rate: 0.1; eps: 1e-6; seed: 42")
+            .unwrap();
+        assert_eq!(
+            options,
+            CheckOptions {
+                trials: 10,
+                spread: 0.5,
+                max_fail_rate: 0.1,
+                eps: Some(1e-6),
+                seed: Some(42),
+            }
+        );
+
+        options
+            .apply("trials: auto; spread: auto; max_fail_rate: auto; eps: auto; seed: auto")
+            .unwrap();
+        assert_eq!(options, CheckOptions::default());
+
+        for bad in [
+            "trials: 0",
+            "trials: -1",
+            "spread: 0",
+            "max_fail_rate: 2",
+            "eps: -1",
+            "tolerance: 0.1",
+            "seed: x",
+            "width: 1",
+        ] {
+            assert!(CheckOptions::default().apply(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn holds() {
+        let report = CheckReport {
+            passed: 9,
+            failed: 1,
+            skipped: 5,
+            counterexample: None,
+        };
+        assert_eq!(report.pass_rate(), 0.9);
+        assert!(!report.holds(0.0));
+        assert!(report.holds(0.1));
+
+        let skipped = CheckReport {
+            passed: 0,
+            failed: 0,
+            skipped: 10,
+            counterexample: None,
+        };
+        assert!(!skipped.holds(1.0));
+    }
+}
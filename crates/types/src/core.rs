@@ -25,11 +25,13 @@ impl From<&str> for Ident {
 pub enum Value {
     Bool(Option<bool>),
     Int(Option<i64>),
-    Real(Option<f64>),
+    Real(Option<Real>),
     Str(Option<String>),
     Pt(Option<Pt>),
     Line(Option<Line>),
     Circ(Option<Circ>),
+    Polyline(Option<Polyline>),
+    Transform(Option<Transform>),
 }
 
 macro_rules! value_from {
@@ -52,11 +54,27 @@ macro_rules! value_from {
 
 value_from!(Bool, bool);
 value_from!(Int, i64);
-value_from!(Real, f64);
+value_from!(Real, Real);
 value_from!(Str, String);
 value_from!(Pt, Pt);
 value_from!(Line, Line);
 value_from!(Circ, Circ);
+value_from!(Polyline, Polyline);
+value_from!(Transform, Transform);
+
+// f64 -> Value
+impl From<f64> for Value {
+    fn from(v: f64) -> Self {
+        Value::Real(Some(v.into()))
+    }
+}
+
+// Option<f64> -> Value
+impl From<Option<f64>> for Value {
+    fn from(opt_v: Option<f64>) -> Self {
+        Value::Real(opt_v.map(Real::from))
+    }
+}
 
 impl Value {
     pub fn value_type(&self) -> ValueType {
@@ -68,6 +86,8 @@ impl Value {
             Value::Pt(_) => ValueType::Pt,
             Value::Line(_) => ValueType::Line,
             Value::Circ(_) => ValueType::Circ,
+            Value::Polyline(_) => ValueType::Polyline,
+            Value::Transform(_) => ValueType::Transform,
         }
     }
 
@@ -80,6 +100,8 @@ impl Value {
             Value::Pt(v) => v.is_none(),
             Value::Line(v) => v.is_none(),
             Value::Circ(v) => v.is_none(),
+            Value::Polyline(v) => v.is_none(),
+            Value::Transform(v) => v.is_none(),
         }
     }
 
@@ -92,10 +114,171 @@ impl Value {
             ValueType::Pt => Value::Pt(None),
             ValueType::Line => Value::Line(None),
             ValueType::Circ => Value::Circ(None),
+            ValueType::Polyline => Value::Polyline(None),
+            ValueType::Transform => Value::Transform(None),
+        }
+    }
+}
+
+/// A real number.
+///
+/// Reals are exact rationals in scopes with exact arithmetic, as long as
+/// only rational operations are applied to them. Irrational operations
+/// (e.g. `^` with a non-integer exponent) and overflows fall back to
+/// floating point.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
+pub enum Real {
+    Float(f64),
+    Exact(Rational),
+}
+
+impl Real {
+    pub fn to_f64(self) -> f64 {
+        match self {
+            Real::Float(v) => v,
+            Real::Exact(v) => v.to_f64(),
+        }
+    }
+
+    pub fn is_exact(self) -> bool {
+        matches!(self, Real::Exact(_))
+    }
+
+    /// Drops exactness.
+    pub fn to_float(self) -> Real {
+        Real::Float(self.to_f64())
+    }
+}
+
+impl PartialEq for Real {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Real::Exact(a), Real::Exact(b)) => a == b,
+            _ => self.to_f64() == other.to_f64(),
+        }
+    }
+}
+
+impl From<f64> for Real {
+    fn from(v: f64) -> Self {
+        Real::Float(v)
+    }
+}
+
+impl From<i64> for Real {
+    fn from(v: i64) -> Self {
+        Real::Exact(v.into())
+    }
+}
+
+impl From<Rational> for Real {
+    fn from(v: Rational) -> Self {
+        Real::Exact(v)
+    }
+}
+
+impl From<Real> for f64 {
+    fn from(v: Real) -> Self {
+        v.to_f64()
+    }
+}
+
+/// A reduced fraction with a positive denominator.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "(i64, i64)", into = "(i64, i64)"))]
+pub struct Rational {
+    num: i64,
+    den: i64,
+}
+
+impl Rational {
+    /// Returns `None` if `den` is zero or the reduced fraction doesn't fit into i64.
+    pub fn new(num: i64, den: i64) -> Option<Rational> {
+        Rational::from_i128(num.into(), den.into())
+    }
+
+    pub(crate) fn from_i128(mut num: i128, mut den: i128) -> Option<Rational> {
+        if den == 0 {
+            return None;
+        }
+        if den < 0 {
+            num = -num;
+            den = -den;
+        }
+
+        let gcd = gcd(num.unsigned_abs(), den.unsigned_abs()) as i128;
+        Some(Rational {
+            num: (num / gcd).try_into().ok()?,
+            den: (den / gcd).try_into().ok()?,
+        })
+    }
+
+    pub fn num(self) -> i64 {
+        self.num
+    }
+
+    pub fn den(self) -> i64 {
+        self.den
+    }
+
+    pub fn is_integer(self) -> bool {
+        self.den == 1
+    }
+
+    /// Returns the nearest f64, e.g. the same one as `str::parse` for a decimal literal.
+    pub fn to_f64(self) -> f64 {
+        // Both are exact in f64, so the division is rounded once
+        const EXACT: u64 = 1 << f64::MANTISSA_DIGITS;
+        let (num, den) = (self.num.unsigned_abs(), self.den as u64);
+        if num <= EXACT && den <= EXACT {
+            return self.num as f64 / self.den as f64;
+        }
+
+        // Otherwise the quotient is computed with at least 64 significant bits
+        // and the remainder is kept in the lowest bit, so that it's rounded once
+        // when it's cast to f64. Scaling by a power of two is exact.
+        let shift = num.leading_zeros() + 63;
+        let scaled = (num as u128) << shift;
+        let (q, r) = (scaled / den as u128, scaled % den as u128);
+        let abs = (q | (r != 0) as u128) as f64 * 2f64.powi(-(shift as i32));
+        if self.num < 0 {
+            -abs
+        } else {
+            abs
         }
     }
 }
 
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+impl From<i64> for Rational {
+    fn from(v: i64) -> Self {
+        Rational { num: v, den: 1 }
+    }
+}
+
+impl TryFrom<(i64, i64)> for Rational {
+    type Error = &'static str;
+
+    fn try_from((num, den): (i64, i64)) -> Result<Self, Self::Error> {
+        Rational::new(num, den).ok_or("bad rational")
+    }
+}
+
+impl From<Rational> for (i64, i64) {
+    fn from(v: Rational) -> Self {
+        (v.num, v.den)
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ValueType {
@@ -106,18 +289,28 @@ pub enum ValueType {
     Pt,
     Line,
     Circ,
+    Polyline,
+    Transform,
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Pt {
-    pub x: f64,
-    pub y: f64,
+    pub x: Real,
+    pub y: Real,
 }
 
 impl Pt {
-    pub fn new(x: f64, y: f64) -> Self {
-        Self { x, y }
+    pub fn new(x: impl Into<Real>, y: impl Into<Real>) -> Self {
+        Self {
+            x: x.into(),
+            y: y.into(),
+        }
+    }
+
+    /// Coordinates as floats, e.g. for drawing.
+    pub fn to_f64(self) -> (f64, f64) {
+        (self.x.to_f64(), self.y.to_f64())
     }
 }
 
@@ -134,21 +327,63 @@ impl Line {
     }
 }
 
+/// A point, that slides along an object, i.e. it is defined as `pt_on <object> <param>`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PtOn {
+    /// Line or circle, that the point is on.
+    pub object: Value,
+
+    /// Real variable, that is the position of the point on the object.
+    pub param: Ident,
+}
+
 #[derive(Debug, PartialEq, Clone, Copy)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Circ {
     /// Center
     pub o: Pt,
     /// Radius
-    pub r: f64,
+    pub r: Real,
 }
 
 impl Circ {
-    pub fn new(o: Pt, r: f64) -> Self {
-        Self { o, r }
+    pub fn new(o: Pt, r: impl Into<Real>) -> Self {
+        Self { o, r: r.into() }
     }
 }
 
+/// Connected segments, e.g. a sampled locus.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Polyline {
+    pub pts: Vec<Pt>,
+}
+
+impl Polyline {
+    pub fn new(pts: Vec<Pt>) -> Self {
+        Self { pts }
+    }
+}
+
+/// Affine transformation of the plane, same as svg's `matrix(a, b, c, d, e, f)`:
+///
+/// * x -> a * x + c * y + e
+/// * y -> b * x + d * y + f
+///
+/// Entries are exact, as long as the transformation is built from exact values
+/// by rational operations, e.g. translations, homotheties and reflections.
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Transform {
+    pub a: Real,
+    pub b: Real,
+    pub c: Real,
+    pub d: Real,
+    pub e: Real,
+    pub f: Real,
+}
+
 #[cfg(test)]
 mod text {
     use super::*;
@@ -165,7 +400,23 @@ mod text {
 
     #[test]
     fn value_from_real() {
-        assert_eq!(Value::Real(Some(1.23)), 1.23.into());
+        assert_eq!(Value::Real(Some(Real::Float(1.23))), 1.23.into());
+    }
+
+    #[test]
+    fn rational_new() {
+        let r = Rational::new(6, -4).unwrap();
+        assert_eq!((r.num(), r.den()), (-3, 2));
+        assert_eq!(Rational::new(0, 5), Some(Rational::from(0)));
+        assert_eq!(Rational::new(1, 0), None);
+        assert_eq!(Rational::new(i64::MIN, -1), None);
+    }
+
+    #[test]
+    fn real_eq() {
+        let half = Real::Exact(Rational::new(1, 2).unwrap());
+        assert_eq!(half, Real::Float(0.5));
+        assert_ne!(half, Real::Exact(Rational::new(1, 3).unwrap()));
     }
 
     #[test]
@@ -178,25 +429,47 @@ mod text {
 
     #[test]
     fn value_from_pt() {
-        let pt = Pt { x: 1., y: 2. };
+        let pt = Pt::new(1., 2.);
         assert_eq!(Value::Pt(Some(pt)), pt.into());
     }
 
     #[test]
     fn value_from_line() {
-        let p1 = Pt { x: 1., y: 2. };
-        let p2 = Pt { x: 3., y: 4. };
+        let p1 = Pt::new(1., 2.);
+        let p2 = Pt::new(3., 4.);
         let l = Line { p1, p2 };
         assert_eq!(Value::Line(Some(l)), l.into());
     }
 
     #[test]
     fn value_from_circ() {
-        let p = Pt { x: 1., y: 2. };
-        let c = Circ { o: p, r: 3. };
+        let p = Pt::new(1., 2.);
+        let c = Circ {
+            o: p,
+            r: 3.0.into(),
+        };
         assert_eq!(Value::Circ(Some(c)), c.into());
     }
 
+    #[test]
+    fn value_from_polyline() {
+        let p = Polyline::new(vec![Pt::new(1., 2.), Pt::new(3., 4.)]);
+        assert_eq!(Value::Polyline(Some(p.clone())), p.into());
+    }
+
+    #[test]
+    fn value_from_transform() {
+        let t = Transform {
+            a: 1.0.into(),
+            b: 0.0.into(),
+            c: 0.0.into(),
+            d: 1.0.into(),
+            e: 2.0.into(),
+            f: 3.0.into(),
+        };
+        assert_eq!(Value::Transform(Some(t)), t.into());
+    }
+
     #[test]
     fn value_from_option() {
         assert_eq!(Value::none(ValueType::Int), Option::<i64>::None.into());
@@ -4,16 +4,20 @@ use std::{
 };
 
 use crate::{
-    core::{Circ, Line, Pt, Value, ValueType},
+    core::{Circ, Line, Polyline, Pt, Real, Transform, Value, ValueType},
     lang::{
         AsExpr, DotExpr, Expr, FuncCallExpr, FunctionSignature, IfExpr, IfExprCase, InfixExpr,
-        InfixOp, LetExpr, LetExprDefinition, UnaryExpr, UnaryOp,
+        InfixOp, LetExpr, LetExprDefinition, UnaryExpr, UnaryOp, Warning,
     },
 };
 
 impl Display for Expr {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
+            // literals are shown as written, even if they are parsed as exact reals
+            Expr::Value(Value::Real(Some(v))) => {
+                write!(f, "{}", f64_to_string_g(v.to_f64(), f.precision()))
+            }
             Expr::Value(value) => write!(f, "{value}"),
             Expr::Variable(ident) => write!(f, "{ident}"),
             Expr::FuncCall(func_call_expr) => write!(f, "{func_call_expr}"),
@@ -32,26 +36,44 @@ impl Display for Value {
         match self {
             Value::Bool(Some(v)) => write!(f, "{v}"),
             Value::Int(Some(v)) => write!(f, "{v}"),
-            Value::Real(Some(v)) => {
-                write!(f, "{}", f64_to_string_g(*v, f.precision()))
-            }
+            Value::Real(Some(v)) => Display::fmt(v, f),
             Value::Str(Some(v)) => {
                 write!(f, "\"{}\"", v.replace("\\", "\\\\").replace("\"", "\\\""))
             }
             Value::Pt(Some(v)) => write!(f, "{v}"),
             Value::Line(Some(v)) => write!(f, "{v}"),
             Value::Circ(Some(v)) => write!(f, "{v}"),
+            Value::Polyline(Some(v)) => write!(f, "{v}"),
+            Value::Transform(Some(v)) => write!(f, "{v}"),
             Value::Bool(None)
             | Value::Int(None)
             | Value::Real(None)
             | Value::Str(None)
             | Value::Pt(None)
             | Value::Line(None)
-            | Value::Circ(None) => write!(f, "none {}", self.value_type()),
+            | Value::Circ(None)
+            | Value::Polyline(None)
+            | Value::Transform(None) => write!(f, "none {}", self.value_type()),
         }
     }
 }
 
+impl Display for Real {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", real_to_string(*self, f.precision()))
+    }
+}
+
+/// Exact reals are shown as real literals or their quotients, e.g. `(1.0 / 3.0)`,
+/// so that they are parsed back exactly; precision only applies to floats.
+fn real_to_string(v: Real, precision: Option<usize>) -> String {
+    match v {
+        Real::Float(v) => f64_to_string_g(v, precision),
+        Real::Exact(v) if v.is_integer() => format!("{}.0", v.num()),
+        Real::Exact(v) => format!("({}.0 / {}.0)", v.num(), v.den()),
+    }
+}
+
 impl Display for ValueType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let s = match self {
@@ -62,6 +84,8 @@ impl Display for ValueType {
             ValueType::Pt => "pt",
             ValueType::Line => "line",
             ValueType::Circ => "circ",
+            ValueType::Polyline => "polyline",
+            ValueType::Transform => "transform",
         };
         write!(f, "{}", s)
     }
@@ -72,8 +96,8 @@ impl Display for Pt {
         write!(
             f,
             "pt {x} {y}",
-            x = f64_to_string_g(self.x, f.precision()),
-            y = f64_to_string_g(self.y, f.precision())
+            x = real_to_string(self.x, f.precision()),
+            y = real_to_string(self.y, f.precision())
         )
     }
 }
@@ -90,11 +114,27 @@ impl Display for Circ {
             f,
             "circ ({o}) {r}",
             o = self.o,
-            r = f64_to_string_g(self.r, f.precision())
+            r = real_to_string(self.r, f.precision())
         )
     }
 }
 
+impl Display for Polyline {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // Polylines have no literal syntax, and loci have too many points to list
+        write!(f, "polyline of {} pts", self.pts.len())
+    }
+}
+
+impl Display for Transform {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // Transforms have no literal syntax, so they are shown as matrices of floats
+        let [a, b, c, d, e, g] = [self.a, self.b, self.c, self.d, self.e, self.f]
+            .map(|v| f64_to_string_g(v.to_f64(), f.precision()));
+        write!(f, "transform [{a} {c} {e}; {b} {d} {g}]")
+    }
+}
+
 impl Display for FuncCallExpr {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         let FuncCallExpr { name, args } = self;
@@ -249,6 +289,21 @@ impl Display for FunctionSignature {
     }
 }
 
+impl Display for Warning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::UnusedArgument { func, arg } => {
+                write!(f, "unused argument '{arg}' in function '{func}'")
+            }
+            Warning::ShadowedLetBinding(name) => {
+                write!(f, "let binding '{name}' shadows another variable")
+            }
+            Warning::ConstantIfCondition(value) => write!(f, "if condition is always {value}"),
+            Warning::UnreachableIfCase => write!(f, "unreachable if case"),
+        }
+    }
+}
+
 /// Converts a f64 to a String in a way similar to C's printf's `g` modifier.
 fn f64_to_string_g(num: f64, precision: Option<usize>) -> String {
     const DEFAULT_PRECISION: usize = 3;
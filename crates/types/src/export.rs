@@ -0,0 +1,680 @@
+use std::fmt::{self, Display, Formatter};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{core::Pt, style::Color};
+
+/// How the drawing is exported to an image.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ExportOptions {
+    /// Part of the plane to export; fits all visible items by default.
+    pub viewport: Option<Rect>,
+
+    /// Output width in pixels.
+    ///
+    /// If only one of width and height is set, the other one keeps the viewport's aspect ratio.
+    pub width: Option<f64>,
+
+    /// Output height in pixels.
+    pub height: Option<f64>,
+
+    /// Pixel density of raster images; at 96 a pixel of the output size is a pixel of the image.
+    pub dpi: f64,
+
+    /// Whether y axis points up, as it does on the canvas.
+    pub y_up: bool,
+
+    /// Transparent by default.
+    pub background: Option<Color>,
+
+    /// Whether lines are drawn infinite, clipped to the viewport, as on the canvas,
+    /// rather than as segments between their points.
+    pub clip_lines: bool,
+}
+
+pub const DEFAULT_DPI: f64 = 96.0;
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        ExportOptions {
+            viewport: None,
+            width: None,
+            height: None,
+            dpi: DEFAULT_DPI,
+            y_up: true,
+            background: None,
+            clip_lines: true,
+        }
+    }
+}
+
+impl ExportOptions {
+    /// Updates the options with css-like declarations,
+    /// e.g. `viewport: -1 -1 5 5; width: 800; y-axis: down; background: white; lines: segments`.
+    ///
+    /// `<property>: auto` resets a property.
+    pub fn apply(&mut self, decls: &str) -> Result<(), ExportOptionsError> {
+        for (decl, property, value) in declarations(decls)? {
+            let err = || ExportOptionsError(format!("bad declaration '{decl}'"));
+
+            match (property, value) {
+                ("viewport", "auto") => self.viewport = None,
+                ("viewport", value) => self.viewport = Some(parse_viewport(value).ok_or_else(err)?),
+
+                ("width", "auto") => self.width = None,
+                ("width", value) => self.width = Some(parse_size(value).ok_or_else(err)?),
+
+                ("height", "auto") => self.height = None,
+                ("height", value) => self.height = Some(parse_size(value).ok_or_else(err)?),
+
+                ("dpi", "auto") => self.dpi = DEFAULT_DPI,
+                ("dpi", value) => self.dpi = parse_size(value).ok_or_else(err)?,
+
+                ("y-axis", "up") => self.y_up = true,
+                ("y-axis", "down") => self.y_up = false,
+
+                ("background", "auto" | "none") => self.background = None,
+                ("background", value) => {
+                    self.background = Some(Color::parse(value).ok_or_else(err)?)
+                }
+
+                ("lines", "infinite" | "auto") => self.clip_lines = true,
+                ("lines", "segments") => self.clip_lines = false,
+
+                _ => return Err(err()),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// How the drawing is exported to TikZ.
+///
+/// Coordinates are exported as is, so there is no pixel size.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct TikzOptions {
+    /// Length of a unit in centimeters, the `scale` of the `tikzpicture`.
+    pub scale: f64,
+
+    /// Part of the plane to export; the picture is clipped to it.
+    pub viewport: Option<Rect>,
+
+    /// As in [ExportOptions::clip_lines].
+    pub clip_lines: bool,
+}
+
+impl Default for TikzOptions {
+    fn default() -> Self {
+        TikzOptions {
+            scale: 1.0,
+            viewport: None,
+            clip_lines: true,
+        }
+    }
+}
+
+impl TikzOptions {
+    /// Updates the options with css-like declarations,
+    /// e.g. `scale: 0.5; viewport: -1 -1 5 5; lines: segments`.
+    ///
+    /// `<property>: auto` resets a property.
+    pub fn apply(&mut self, decls: &str) -> Result<(), ExportOptionsError> {
+        for (decl, property, value) in declarations(decls)? {
+            let err = || ExportOptionsError(format!("bad declaration '{decl}'"));
+
+            match (property, value) {
+                ("scale", "auto") => self.scale = 1.0,
+                ("scale", value) => self.scale = parse_size(value).ok_or_else(err)?,
+
+                ("viewport", "auto") => self.viewport = None,
+                ("viewport", value) => self.viewport = Some(parse_viewport(value).ok_or_else(err)?),
+
+                ("lines", "infinite" | "auto") => self.clip_lines = true,
+                ("lines", "segments") => self.clip_lines = false,
+
+                _ => return Err(err()),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Options for the parts, that are shared with other formats.
+    ///
+    /// Y axis points up both in TikZ and in the plane, so nothing is flipped.
+    pub fn to_export_options(&self) -> ExportOptions {
+        ExportOptions {
+            viewport: self.viewport,
+            y_up: false,
+            clip_lines: self.clip_lines,
+            ..Default::default()
+        }
+    }
+}
+
+/// How the drawing is exported to pdf.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct PdfOptions {
+    /// Options, that are shared with svg.
+    ///
+    /// A pixel is 1/96 inch, as in css.
+    pub export: ExportOptions,
+
+    /// Page size in millimeters.
+    pub page: PageSize,
+
+    /// Page margin in millimeters.
+    pub margin: f64,
+
+    /// Whether the drawing is scaled to fill the page inside the margins,
+    /// rather than sized with the export width and height.
+    pub fit: bool,
+}
+
+impl Default for PdfOptions {
+    fn default() -> Self {
+        PdfOptions {
+            export: ExportOptions::default(),
+            page: PageSize::A4,
+            margin: 15.0,
+            fit: true,
+        }
+    }
+}
+
+impl PdfOptions {
+    /// Updates the options with css-like declarations,
+    /// e.g. `page: a4 landscape; margin: 10; fit: none; width: 400`.
+    ///
+    /// Other declarations are the ones of [ExportOptions::apply].
+    pub fn apply(&mut self, decls: &str) -> Result<(), ExportOptionsError> {
+        for (decl, property, value) in declarations(decls)? {
+            let err = || ExportOptionsError(format!("bad declaration '{decl}'"));
+
+            match (property, value) {
+                ("page", "auto") => self.page = PageSize::A4,
+                ("page", value) => self.page = PageSize::parse(value).ok_or_else(err)?,
+
+                ("margin", "auto") => self.margin = 15.0,
+                ("margin", value) => {
+                    self.margin = value
+                        .parse()
+                        .ok()
+                        .filter(|margin: &f64| *margin >= 0.0)
+                        .ok_or_else(err)?
+                }
+
+                ("fit", "page" | "auto") => self.fit = true,
+                ("fit", "none") => self.fit = false,
+
+                _ => self.export.apply(decl)?,
+            }
+        }
+
+        if 2.0 * self.margin >= self.page.width.min(self.page.height) {
+            return Err(ExportOptionsError(
+                "margins do not leave space on the page".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// How a variable is swept to export an animation.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct AnimationOptions {
+    /// Options of every frame; the viewport fits all frames by default.
+    pub export: ExportOptions,
+
+    /// Duration of the whole animation in seconds, used by animated svg.
+    pub duration: f64,
+
+    /// Whether svg frames are put into a single animated svg rather than into separate files.
+    ///
+    /// Png frames are always separate.
+    pub animated: bool,
+}
+
+impl Default for AnimationOptions {
+    fn default() -> Self {
+        AnimationOptions {
+            export: ExportOptions::default(),
+            duration: 2.0,
+            animated: true,
+        }
+    }
+}
+
+impl AnimationOptions {
+    /// Updates the options with css-like declarations,
+    /// e.g. `duration: 5; output: frames; width: 400`.
+    ///
+    /// Other declarations are the ones of [ExportOptions::apply].
+    pub fn apply(&mut self, decls: &str) -> Result<(), ExportOptionsError> {
+        for (decl, property, value) in declarations(decls)? {
+            let err = || ExportOptionsError(format!("bad declaration '{decl}'"));
+
+            match (property, value) {
+                ("duration", "auto") => self.duration = 2.0,
+                ("duration", value) => self.duration = parse_size(value).ok_or_else(err)?,
+
+                ("output", "animated" | "auto") => self.animated = true,
+                ("output", "frames") => self.animated = false,
+
+                _ => self.export.apply(decl)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Sweeps have at most that many frames, as every frame is kept in memory while exporting.
+pub const MAX_SWEEP_FRAMES: u32 = 100_000;
+
+/// Values, that a variable takes in the frames of an animation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Sweep {
+    pub from: f64,
+    pub to: f64,
+    pub frames: u32,
+}
+
+impl Sweep {
+    /// The first frame is at `from` and the last one is at `to`.
+    pub fn value(&self, frame: u32) -> f64 {
+        if self.frames <= 1 {
+            return self.from;
+        }
+        self.from + (self.to - self.from) * frame as f64 / (self.frames - 1) as f64
+    }
+}
+
+/// Format of separate frames of an animation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FrameFormat {
+    Svg,
+    Png,
+}
+
+/// Width and height in millimeters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PageSize {
+    pub width: f64,
+    pub height: f64,
+}
+
+impl PageSize {
+    pub const A3: PageSize = PageSize::new(297.0, 420.0);
+    pub const A4: PageSize = PageSize::new(210.0, 297.0);
+    pub const A5: PageSize = PageSize::new(148.0, 210.0);
+    pub const LETTER: PageSize = PageSize::new(215.9, 279.4);
+    pub const LEGAL: PageSize = PageSize::new(215.9, 355.6);
+
+    pub const fn new(width: f64, height: f64) -> Self {
+        PageSize { width, height }
+    }
+
+    /// Parses a named size (`a4`, `letter`, ...) or `<width> <height>` in millimeters,
+    /// optionally followed by `portrait` or `landscape`.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut words: Vec<_> = s.split_whitespace().collect();
+
+        let landscape = match words.last() {
+            Some(&"landscape") => {
+                words.pop();
+                Some(true)
+            }
+            Some(&"portrait") => {
+                words.pop();
+                Some(false)
+            }
+            _ => None,
+        };
+
+        let page = match words.as_slice() {
+            [name] => match name.to_lowercase().as_str() {
+                "a3" => PageSize::A3,
+                "a4" => PageSize::A4,
+                "a5" => PageSize::A5,
+                "letter" => PageSize::LETTER,
+                "legal" => PageSize::LEGAL,
+                _ => return None,
+            },
+            [width, height] => {
+                let width = parse_size(width)?;
+                let height = parse_size(height)?;
+                PageSize::new(width, height)
+            }
+            _ => return None,
+        };
+
+        let (short, long) = (page.width.min(page.height), page.width.max(page.height));
+        Some(match landscape {
+            Some(true) => PageSize::new(long, short),
+            Some(false) => PageSize::new(short, long),
+            None => page,
+        })
+    }
+}
+
+/// Format of a vector drawing, that is imported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DrawingFormat {
+    Svg,
+    Dxf,
+}
+
+/// How primitives of a vector drawing are imported as items.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ImportOptions {
+    /// Drawing units per unit of the plane.
+    ///
+    /// By default it's the scale of the svg export, if the svg was exported by us, or 1.
+    pub scale: Option<f64>,
+
+    /// Whether y coordinates are negated.
+    ///
+    /// By default svg is flipped, unless it was exported with `y-axis: down`,
+    /// and dxf is not, as it's y axis points up.
+    pub flip_y: Option<bool>,
+}
+
+impl ImportOptions {
+    /// Updates the options with css-like declarations, e.g. `scale: 100; y-axis: keep`.
+    ///
+    /// `<property>: auto` resets a property.
+    pub fn apply(&mut self, decls: &str) -> Result<(), ExportOptionsError> {
+        for (decl, property, value) in declarations(decls)? {
+            let err = || ExportOptionsError(format!("bad declaration '{decl}'"));
+
+            match (property, value) {
+                ("scale", "auto") => self.scale = None,
+                ("scale", value) => self.scale = Some(parse_size(value).ok_or_else(err)?),
+
+                ("y-axis", "auto") => self.flip_y = None,
+                ("y-axis", "flip") => self.flip_y = Some(true),
+                ("y-axis", "keep") => self.flip_y = Some(false),
+
+                _ => return Err(err()),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Splits declarations into `(declaration, property, value)`.
+fn declarations(decls: &str) -> Result<Vec<(&str, &str, &str)>, ExportOptionsError> {
+    decls
+        .split(';')
+        .map(str::trim)
+        .filter(|decl| !decl.is_empty())
+        .map(|decl| {
+            let (property, value) = decl
+                .split_once(':')
+                .ok_or_else(|| ExportOptionsError(format!("bad declaration '{decl}'")))?;
+            Ok((decl, property.trim(), value.trim()))
+        })
+        .collect()
+}
+
+fn parse_size(value: &str) -> Option<f64> {
+    value.parse().ok().filter(|size: &f64| *size > 0.0)
+}
+
+/// Parses `x1 y1 x2 y2`.
+fn parse_viewport(value: &str) -> Option<Rect> {
+    let coords = value
+        .split_whitespace()
+        .map(|coord| coord.parse().ok().filter(|coord: &f64| coord.is_finite()))
+        .collect::<Option<Vec<f64>>>()?;
+    let &[x1, y1, x2, y2] = coords.as_slice() else {
+        return None;
+    };
+    let viewport = Rect::new(Pt::new(x1, y1), Pt::new(x2, y2));
+    (viewport.width() != 0.0 && viewport.height() != 0.0).then_some(viewport)
+}
+
+/// An axis-aligned rectangle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Rect {
+    pub min: Pt,
+    pub max: Pt,
+}
+
+impl Rect {
+    /// Creates a rectangle with the given opposite corners.
+    pub fn new(p1: Pt, p2: Pt) -> Self {
+        let (x1, y1, x2, y2) = (p1.x.to_f64(), p1.y.to_f64(), p2.x.to_f64(), p2.y.to_f64());
+        Rect {
+            min: Pt::new(x1.min(x2), y1.min(y2)),
+            max: Pt::new(x1.max(x2), y1.max(y2)),
+        }
+    }
+
+    pub fn width(&self) -> f64 {
+        self.max.x.to_f64() - self.min.x.to_f64()
+    }
+
+    pub fn height(&self) -> f64 {
+        self.max.y.to_f64() - self.min.y.to_f64()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportOptionsError(pub String);
+
+impl Display for ExportOptionsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for ExportOptionsError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn apply() {
+        let mut options = ExportOptions::default();
+        options
+            .apply(
+                "viewport: 5 5 -1 -1; width: 800; dpi: 300; y-axis: down; background: white; lines: segments",
+            )
+            .unwrap();
+        assert_eq!(
+            options,
+            ExportOptions {
+                viewport: Some(Rect {
+                    min: Pt::new(-1.0, -1.0),
+                    max: Pt::new(5.0, 5.0),
+                }),
+                width: Some(800.0),
+                height: None,
+                dpi: 300.0,
+                y_up: false,
+                background: Some(Color::rgb(255, 255, 255)),
+                clip_lines: false,
+            }
+        );
+
+        options
+            .apply("viewport: auto; width: auto; height: 600; dpi: auto; background: none; lines: auto")
+            .unwrap();
+        assert_eq!(
+            options,
+            ExportOptions {
+                height: Some(600.0),
+                y_up: false,
+                ..Default::default()
+            }
+        );
+
+        for decls in [
+            "viewport: 0 0 1",
+            "viewport: 0 0 0 1",
+            "width: 0",
+            "height: -5",
+            "dpi: 0",
+            "y-axis: left",
+            "background: reddish",
+            "lines",
+            "scale: 2",
+        ] {
+            assert!(ExportOptions::default().apply(decls).is_err(), "{decls}");
+        }
+    }
+
+    #[test]
+    fn apply_tikz() {
+        let mut options = TikzOptions::default();
+        options
+            .apply("scale: 0.5; viewport: 0 0 2 1; lines: segments")
+            .unwrap();
+        assert_eq!(
+            options,
+            TikzOptions {
+                scale: 0.5,
+                viewport: Some(Rect {
+                    min: Pt::new(0.0, 0.0),
+                    max: Pt::new(2.0, 1.0),
+                }),
+                clip_lines: false,
+            }
+        );
+
+        options
+            .apply("scale: auto; viewport: auto; lines: auto")
+            .unwrap();
+        assert_eq!(options, TikzOptions::default());
+
+        for decls in ["scale: 0", "scale", "width: 100", "viewport: 0 0 0 0"] {
+            assert!(TikzOptions::default().apply(decls).is_err(), "{decls}");
+        }
+    }
+
+    #[test]
+    fn apply_import() {
+        let mut options = ImportOptions::default();
+        options.apply("scale: 100; y-axis: keep").unwrap();
+        assert_eq!(
+            options,
+            ImportOptions {
+                scale: Some(100.0),
+                flip_y: Some(false),
+            }
+        );
+
+        options.apply("scale: auto; y-axis: auto").unwrap();
+        assert_eq!(options, ImportOptions::default());
+
+        for decls in ["scale: 0", "y-axis: up", "width: 1"] {
+            assert!(ImportOptions::default().apply(decls).is_err(), "{decls}");
+        }
+    }
+
+    #[test]
+    fn apply_pdf() {
+        let mut options = PdfOptions::default();
+        options
+            .apply("page: a4 landscape; margin: 0; fit: none; width: 400")
+            .unwrap();
+        assert_eq!(
+            options,
+            PdfOptions {
+                export: ExportOptions {
+                    width: Some(400.0),
+                    ..Default::default()
+                },
+                page: PageSize::new(297.0, 210.0),
+                margin: 0.0,
+                fit: false,
+            }
+        );
+
+        options.apply("page: 100 50 portrait").unwrap();
+        assert_eq!(options.page, PageSize::new(50.0, 100.0));
+
+        options
+            .apply("page: auto; margin: auto; fit: auto; width: auto")
+            .unwrap();
+        assert_eq!(options, PdfOptions::default());
+
+        for decls in [
+            "page: a6",
+            "page: 100",
+            "page: a4 sideways",
+            "margin: -1",
+            "margin: 200",
+            "fit: width",
+            "dpi: 0",
+        ] {
+            assert!(PdfOptions::default().apply(decls).is_err(), "{decls}");
+        }
+    }
+
+    #[test]
+    fn apply_animation() {
+        let mut options = AnimationOptions::default();
+        options
+            .apply("duration: 5; output: frames; width: 400")
+            .unwrap();
+        assert_eq!(
+            options,
+            AnimationOptions {
+                export: ExportOptions {
+                    width: Some(400.0),
+                    ..Default::default()
+                },
+                duration: 5.0,
+                animated: false,
+            }
+        );
+
+        options
+            .apply("duration: auto; output: auto; width: auto")
+            .unwrap();
+        assert_eq!(options, AnimationOptions::default());
+
+        for decls in ["duration: 0", "output: gif", "dpi: 0"] {
+            assert!(AnimationOptions::default().apply(decls).is_err(), "{decls}");
+        }
+    }
+
+    #[test]
+    fn sweep() {
+        let sweep = Sweep {
+            from: 1.0,
+            to: 2.0,
+            frames: 5,
+        };
+        assert_eq!(
+            (0..5).map(|frame| sweep.value(frame)).collect::<Vec<_>>(),
+            [1.0, 1.25, 1.5, 1.75, 2.0]
+        );
+
+        let sweep = Sweep { frames: 1, ..sweep };
+        assert_eq!(sweep.value(0), 1.0);
+    }
+}
@@ -203,3 +203,13 @@ pub struct DotExpr {
     pub name: Ident,
     pub body: Box<Expr>,
 }
+
+/// Non-fatal problem found while compiling definitions.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Warning {
+    UnusedArgument { func: FunctionSignature, arg: Ident },
+    ShadowedLetBinding(Ident),
+    ConstantIfCondition(bool),
+    UnreachableIfCase,
+}
@@ -0,0 +1,36 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::core::Ident;
+
+/// A named group of items, that are hidden, locked and exported together.
+///
+/// An item belongs to at most one layer.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Layer {
+    pub name: Ident,
+
+    /// Items in order of addition.
+    pub items: Vec<Ident>,
+
+    pub hidden: bool,
+
+    /// Locked items can not be `set!` or removed.
+    pub locked: bool,
+}
+
+impl Layer {
+    pub fn new(name: Ident) -> Self {
+        Layer {
+            name,
+            items: Vec::new(),
+            hidden: false,
+            locked: false,
+        }
+    }
+
+    pub fn contains(&self, item: &Ident) -> bool {
+        self.items.contains(item)
+    }
+}
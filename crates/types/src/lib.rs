@@ -1,5 +1,10 @@
+pub mod check;
 pub mod core;
+pub mod export;
 pub mod lang;
+pub mod layer;
+pub mod slider;
+pub mod style;
 
 #[cfg(feature = "serde")]
 pub mod api;
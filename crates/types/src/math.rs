@@ -1,6 +1,9 @@
-use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::{
+    cmp::Ordering,
+    ops::{Add, Div, Mul, Neg, Rem, Sub},
+};
 
-use crate::core::{Circ, Line, Pt};
+use crate::core::{Circ, Line, Polyline, Pt, PtOn, Rational, Real, Transform, Value};
 
 impl Add for Pt {
     type Output = Pt;
@@ -30,10 +33,10 @@ impl Sub for Pt {
     }
 }
 
-impl Mul<f64> for Pt {
+impl Mul<Real> for Pt {
     type Output = Pt;
 
-    fn mul(self, rhs: f64) -> Self {
+    fn mul(self, rhs: Real) -> Self {
         let Pt { x, y } = self;
 
         Pt {
@@ -43,6 +46,22 @@ impl Mul<f64> for Pt {
     }
 }
 
+impl Mul<Pt> for Real {
+    type Output = Pt;
+
+    fn mul(self, rhs: Pt) -> Self::Output {
+        rhs * self
+    }
+}
+
+impl Mul<f64> for Pt {
+    type Output = Pt;
+
+    fn mul(self, rhs: f64) -> Self {
+        self * Real::Float(rhs)
+    }
+}
+
 impl Mul<Pt> for f64 {
     type Output = Pt;
 
@@ -51,10 +70,10 @@ impl Mul<Pt> for f64 {
     }
 }
 
-impl Div<f64> for Pt {
+impl Div<Real> for Pt {
     type Output = Pt;
 
-    fn div(self, rhs: f64) -> Self {
+    fn div(self, rhs: Real) -> Self {
         let Pt { x, y } = self;
 
         Pt {
@@ -64,33 +83,94 @@ impl Div<f64> for Pt {
     }
 }
 
+impl Div<f64> for Pt {
+    type Output = Pt;
+
+    fn div(self, rhs: f64) -> Self {
+        self / Real::Float(rhs)
+    }
+}
+
 impl Neg for Pt {
     type Output = Pt;
 
     fn neg(self) -> Self {
         let Pt { x, y } = self;
 
-        Pt { x: -x, y: -y }
+        Pt::new(-x, -y)
     }
 }
 
 impl Pt {
     pub fn len(self) -> f64 {
-        let Pt { x, y } = self;
-
-        (x * x + y * y).sqrt()
+        self.dot(self).to_f64().sqrt()
     }
 
     pub fn dist(self, rhs: Pt) -> f64 {
         (self - rhs).len()
     }
 
-    pub fn cross(self, rhs: Pt) -> f64 {
+    pub fn cross(self, rhs: Pt) -> Real {
         let Pt { x: x1, y: y1 } = self;
         let Pt { x: x2, y: y2 } = rhs;
 
         x1 * y2 - y1 * x2
     }
+
+    pub fn dot(self, rhs: Pt) -> Real {
+        self.x * rhs.x + self.y * rhs.y
+    }
+
+    pub fn is_exact(self) -> bool {
+        self.x.is_exact() && self.y.is_exact()
+    }
+
+    /// Whether the points are at most `eps` apart; exact points are compared exactly.
+    pub fn approx_eq(self, rhs: Pt, eps: f64) -> bool {
+        if self.is_exact() && rhs.is_exact() {
+            self == rhs
+        } else {
+            self.dist(rhs) <= eps
+        }
+    }
+
+    /// Whether the points are on a line, i.e. the shortest height
+    /// of the triangle is at most `eps`.
+    ///
+    /// Coinciding points are collinear.
+    pub fn collinear(a: Pt, b: Pt, c: Pt, eps: f64) -> bool {
+        let cross = (b - a).cross(c - a);
+        if cross.is_exact() {
+            return cross == Real::from(0);
+        }
+
+        let longest = a.dist(b).max(b.dist(c)).max(c.dist(a));
+        longest == 0.0 || cross.to_f64().abs() / longest <= eps
+    }
+
+    /// Whether the points are on a circle, i.e. the last one is at most `eps`
+    /// away from the circle through the first three.
+    ///
+    /// Points on a line are treated as if they were on a circle of infinite radius.
+    pub fn concyclic(a: Pt, b: Pt, c: Pt, d: Pt, eps: f64) -> bool {
+        if Pt::collinear(a, b, c, eps) {
+            return Pt::collinear(a, b, d, eps) && Pt::collinear(a, c, d, eps);
+        }
+
+        let (ab, ac) = (b - a, c - a);
+        let denom = Real::from(2) * ab.cross(ac);
+        let (ab2, ac2) = (ab.dot(ab), ac.dot(ac));
+        let o = a + Pt {
+            x: ac.y * ab2 - ab.y * ac2,
+            y: ab.x * ac2 - ac.x * ab2,
+        } / denom;
+
+        let (od, oa) = (d - o, a - o);
+        if o.is_exact() && d.is_exact() {
+            return od.dot(od) == oa.dot(oa);
+        }
+        (od.len() - oa.len()).abs() <= eps
+    }
 }
 
 impl Line {
@@ -99,7 +179,123 @@ impl Line {
         let p1p2 = p2 - p1;
         let p1p = p - p1;
 
-        p1p2.cross(p1p).abs() / p1p2.len()
+        p1p2.cross(p1p).to_f64().abs() / p1p2.len()
+    }
+
+    pub fn is_exact(self) -> bool {
+        self.p1.is_exact() && self.p2.is_exact()
+    }
+
+    /// Whether the point is at most `eps` away from the line;
+    /// it's checked exactly for exact ones.
+    pub fn contains(self, p: Pt, eps: f64) -> bool {
+        if self.is_exact() && p.is_exact() {
+            (self.p2 - self.p1).cross(p - self.p1) == Real::from(0)
+        } else {
+            self.dist(p) <= eps
+        }
+    }
+
+    /// Whether the lines are the same, i.e. points of each of them
+    /// are at most `eps` away from the other one, or on it for exact lines.
+    ///
+    /// Lines, whose points coincide, are equal if their points are.
+    pub fn approx_eq(self, rhs: Line, eps: f64) -> bool {
+        match (self.p1 == self.p2, rhs.p1 == rhs.p2) {
+            (true, true) => self.p1.approx_eq(rhs.p1, eps),
+            (false, false) => {
+                self.contains(rhs.p1, eps)
+                    && self.contains(rhs.p2, eps)
+                    && rhs.contains(self.p1, eps)
+                    && rhs.contains(self.p2, eps)
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the lines are parallel up to `eps` in the sine of the angle between them.
+    ///
+    /// Returns `None` if either of them has `p1 == p2`.
+    pub fn parallel(self, rhs: Line, eps: f64) -> Option<bool> {
+        let (d1, d2) = (self.p2 - self.p1, rhs.p2 - rhs.p1);
+        Line::angle_check(d1, d2, d1.cross(d2), eps)
+    }
+
+    /// Whether the lines are perpendicular up to `eps` in the cosine of the angle between them.
+    ///
+    /// Returns `None` if either of them has `p1 == p2`.
+    pub fn perpendicular(self, rhs: Line, eps: f64) -> Option<bool> {
+        let (d1, d2) = (self.p2 - self.p1, rhs.p2 - rhs.p1);
+        Line::angle_check(d1, d2, d1.dot(d2), eps)
+    }
+
+    /// Whether `product` of the directions is zero,
+    /// exactly for exact ones and up to `eps` times their lengths otherwise.
+    fn angle_check(d1: Pt, d2: Pt, product: Real, eps: f64) -> Option<bool> {
+        let zero = Pt::new(0, 0);
+        if d1 == zero || d2 == zero {
+            return None;
+        }
+
+        Some(if product.is_exact() {
+            product == Real::from(0)
+        } else {
+            product.to_f64().abs() / (d1.len() * d2.len()) <= eps
+        })
+    }
+
+    /// The point `p1 + t * (p2 - p1)`.
+    pub fn at(self, t: Real) -> Pt {
+        let Line { p1, p2 } = self;
+        p1 + (p2 - p1) * t
+    }
+
+    /// Parameter of the line's point, that is the closest to `p`, as in [Line::at].
+    ///
+    /// Returns `None` if `p1 == p2`.
+    pub fn project(self, p: Pt) -> Option<Real> {
+        let Line { p1, p2 } = self;
+        let dir = p2 - p1;
+        let len2 = dir.dot(dir);
+        if len2 == Real::from(0) {
+            return None;
+        }
+
+        Some((p - p1).dot(dir) / len2)
+    }
+
+    /// Clips the infinite line, that goes through `p1` and `p2`, to the rectangle.
+    ///
+    /// Returns `None` if the line misses the rectangle or `p1 == p2`.
+    pub fn clip(self, min: Pt, max: Pt) -> Option<Line> {
+        let Line { p1, p2 } = self;
+        let dir = p2 - p1;
+        if dir.len() == 0.0 {
+            return None;
+        }
+
+        // Parameters of the line's points inside of the rectangle: p1 + t * dir
+        let mut t_min = f64::NEG_INFINITY;
+        let mut t_max = f64::INFINITY;
+
+        for (p, d, lo, hi) in [(p1.x, dir.x, min.x, max.x), (p1.y, dir.y, min.y, max.y)] {
+            let (p, d, lo, hi) = (p.to_f64(), d.to_f64(), lo.to_f64(), hi.to_f64());
+            if d == 0.0 {
+                if p < lo || p > hi {
+                    return None;
+                }
+                continue;
+            }
+
+            let (t1, t2) = ((lo - p) / d, (hi - p) / d);
+            t_min = t_min.max(t1.min(t2));
+            t_max = t_max.min(t1.max(t2));
+        }
+
+        (t_min <= t_max).then(|| Line {
+            p1: p1 + dir * t_min,
+            p2: p1 + dir * t_max,
+        })
     }
 }
 
@@ -107,7 +303,384 @@ impl Circ {
     pub fn dist(self, p: Pt) -> f64 {
         let Circ { o, r } = self;
 
-        (o.dist(p) - r).abs()
+        (o.dist(p) - r.to_f64()).abs()
+    }
+
+    pub fn is_exact(self) -> bool {
+        self.o.is_exact() && self.r.is_exact()
+    }
+
+    /// Whether the point is at most `eps` away from the circle;
+    /// it's checked exactly for exact ones.
+    pub fn contains(self, p: Pt, eps: f64) -> bool {
+        if self.is_exact() && p.is_exact() {
+            let op = p - self.o;
+            op.dot(op) == self.r * self.r
+        } else {
+            self.dist(p) <= eps
+        }
+    }
+
+    /// Whether the centers and radii are at most `eps` apart;
+    /// exact circles are compared exactly.
+    pub fn approx_eq(self, rhs: Circ, eps: f64) -> bool {
+        if self.is_exact() && rhs.is_exact() {
+            self == rhs
+        } else {
+            self.o.approx_eq(rhs.o, eps) && (self.r.to_f64() - rhs.r.to_f64()).abs() <= eps
+        }
+    }
+
+    /// The point at the angle (in radians, counterclockwise from the x axis).
+    pub fn at(self, angle: f64) -> Pt {
+        let Circ { o, r } = self;
+        o + Pt::new(angle.cos(), angle.sin()) * r
+    }
+
+    /// Angle of the circle's point, that is the closest to `p`.
+    pub fn project(self, p: Pt) -> f64 {
+        let Pt { x, y } = p - self.o;
+        y.to_f64().atan2(x.to_f64())
+    }
+}
+
+impl Polyline {
+    pub fn dist(&self, p: Pt) -> f64 {
+        if let [pt] = self.pts.as_slice() {
+            return pt.dist(p);
+        }
+
+        self.pts
+            .windows(2)
+            .map(|seg| {
+                let (a, b) = (seg[0], seg[1]);
+                let ab = b - a;
+                let len2 = ab.dot(ab).to_f64();
+                if len2 == 0.0 {
+                    return a.dist(p);
+                }
+
+                let t = ((p - a).dot(ab).to_f64() / len2).clamp(0.0, 1.0);
+                (a + ab * t).dist(p)
+            })
+            .fold(f64::INFINITY, f64::min)
+    }
+}
+
+impl PtOn {
+    /// Value of the parameter, that puts the point the closest to `p`.
+    pub fn project(&self, p: Pt) -> Option<Real> {
+        match self.object {
+            Value::Line(Some(line)) => line.project(p),
+            Value::Circ(Some(circ)) => Some(circ.project(p).into()),
+            _ => None,
+        }
+    }
+}
+
+impl Transform {
+    pub fn identity() -> Self {
+        Transform::linear_about(1.into(), 0.into(), 0.into(), 1.into(), Pt::new(0, 0))
+    }
+
+    pub fn translation(v: Pt) -> Self {
+        Transform {
+            e: v.x,
+            f: v.y,
+            ..Transform::identity()
+        }
+    }
+
+    /// Counterclockwise rotation by the angle (in radians).
+    pub fn rotation(center: Pt, angle: f64) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Transform::linear_about(cos.into(), sin.into(), (-sin).into(), cos.into(), center)
+    }
+
+    pub fn homothety(center: Pt, k: Real) -> Self {
+        Transform::linear_about(k, 0.into(), 0.into(), k, center)
+    }
+
+    /// Returns `None` if the line is degenerate.
+    ///
+    /// The direction is not normalized, so reflections in lines through exact points are exact.
+    pub fn reflection(l: Line) -> Option<Self> {
+        let dir = l.p2 - l.p1;
+        let len2 = dir.dot(dir);
+        if len2 == 0.into() {
+            return None;
+        }
+
+        let Pt { x, y } = dir;
+        let (cos2, sin2) = ((x * x - y * y) / len2, Real::from(2) * x * y / len2);
+        Some(Transform::linear_about(cos2, sin2, sin2, -cos2, l.p1))
+    }
+
+    /// Transformation with the given linear part, that keeps `center` in place.
+    fn linear_about(a: Real, b: Real, c: Real, d: Real, center: Pt) -> Self {
+        let linear = Transform {
+            a,
+            b,
+            c,
+            d,
+            e: 0.into(),
+            f: 0.into(),
+        };
+        let Pt { x: e, y: f } = center - linear.apply_pt(center);
+        Transform { e, f, ..linear }
+    }
+
+    /// Returns `None` if the transformation is degenerate.
+    pub fn inverse(self) -> Option<Self> {
+        let Transform { a, b, c, d, e, f } = self;
+        let det = a * d - b * c;
+        if det == 0.into() {
+            return None;
+        }
+
+        let linear = Transform {
+            a: d / det,
+            b: -b / det,
+            c: -c / det,
+            d: a / det,
+            e: 0.into(),
+            f: 0.into(),
+        };
+        let Pt { x: e, y: f } = -linear.apply_pt(Pt::new(e, f));
+        Some(Transform { e, f, ..linear })
+    }
+
+    pub fn apply_pt(self, p: Pt) -> Pt {
+        let Transform { a, b, c, d, e, f } = self;
+        let Pt { x, y } = p;
+        Pt::new(a * x + c * y + e, b * x + d * y + f)
+    }
+
+    pub fn apply_line(self, l: Line) -> Line {
+        Line {
+            p1: self.apply_pt(l.p1),
+            p2: self.apply_pt(l.p2),
+        }
+    }
+
+    /// Circles are mapped to circles by similarities only,
+    /// which all translations, rotations, homotheties, reflections
+    /// and their compositions are.
+    pub fn apply_circ(self, c: Circ) -> Circ {
+        let det = self.a * self.d - self.b * self.c;
+        Circ {
+            o: self.apply_pt(c.o),
+            r: c.r * Real::Float(det.to_f64().abs().sqrt()),
+        }
+    }
+}
+
+/// Composition: `(t1 * t2)(p) = t1(t2(p))`.
+impl Mul for Transform {
+    type Output = Transform;
+
+    fn mul(self, rhs: Self) -> Self {
+        let Transform { a, b, c, d, .. } = self;
+        let Pt { x: e, y: f } = self.apply_pt(Pt::new(rhs.e, rhs.f));
+        Transform {
+            a: a * rhs.a + c * rhs.b,
+            b: b * rhs.a + d * rhs.b,
+            c: a * rhs.c + c * rhs.d,
+            d: b * rhs.c + d * rhs.d,
+            e,
+            f,
+        }
+    }
+}
+
+impl Rational {
+    pub fn checked_add(self, rhs: Rational) -> Option<Rational> {
+        let (a, b, c, d) = self.widen(rhs);
+        Rational::from_i128(a * d + c * b, b * d)
+    }
+
+    pub fn checked_sub(self, rhs: Rational) -> Option<Rational> {
+        let (a, b, c, d) = self.widen(rhs);
+        Rational::from_i128(a * d - c * b, b * d)
+    }
+
+    pub fn checked_mul(self, rhs: Rational) -> Option<Rational> {
+        let (a, b, c, d) = self.widen(rhs);
+        Rational::from_i128(a * c, b * d)
+    }
+
+    pub fn checked_div(self, rhs: Rational) -> Option<Rational> {
+        let (a, b, c, d) = self.widen(rhs);
+        Rational::from_i128(a * d, b * c)
+    }
+
+    /// Remainder of the truncated division, same as `%` for f64.
+    pub fn checked_rem(self, rhs: Rational) -> Option<Rational> {
+        let (a, b, c, d) = self.widen(rhs);
+        let quot = (a * d).checked_div(b * c)?;
+        Rational::from_i128(a * d - quot.checked_mul(c)?.checked_mul(b)?, b * d)
+    }
+
+    pub fn checked_neg(self) -> Option<Rational> {
+        Rational::from_i128(-(self.num() as i128), self.den().into())
+    }
+
+    pub fn checked_pow(self, exp: i64) -> Option<Rational> {
+        let exp_abs = exp.unsigned_abs().try_into().ok()?;
+        let num = (self.num() as i128).checked_pow(exp_abs)?;
+        let den = (self.den() as i128).checked_pow(exp_abs)?;
+        if exp >= 0 {
+            Rational::from_i128(num, den)
+        } else {
+            Rational::from_i128(den, num)
+        }
+    }
+
+    fn widen(self, rhs: Rational) -> (i128, i128, i128, i128) {
+        (
+            self.num().into(),
+            self.den().into(),
+            rhs.num().into(),
+            rhs.den().into(),
+        )
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (a, b, c, d) = self.widen(*other);
+        (a * d).cmp(&(c * b))
+    }
+}
+
+// Implements an operator for reals, that is exact if both operands are exact
+// and the result fits into Rational.
+macro_rules! real_op {
+    ($trait:ident, $method:ident, $checked_method:ident) => {
+        impl $trait for Real {
+            type Output = Real;
+
+            fn $method(self, rhs: Self) -> Self {
+                match (self, rhs) {
+                    (Real::Exact(a), Real::Exact(b)) => match a.$checked_method(b) {
+                        Some(v) => Real::Exact(v),
+                        None => Real::Float($trait::$method(a.to_f64(), b.to_f64())),
+                    },
+                    _ => Real::Float($trait::$method(self.to_f64(), rhs.to_f64())),
+                }
+            }
+        }
+    };
+}
+
+real_op!(Add, add, checked_add);
+real_op!(Sub, sub, checked_sub);
+real_op!(Mul, mul, checked_mul);
+real_op!(Div, div, checked_div);
+real_op!(Rem, rem, checked_rem);
+
+impl Neg for Real {
+    type Output = Real;
+
+    fn neg(self) -> Self {
+        match self {
+            Real::Exact(v) => match v.checked_neg() {
+                Some(v) => Real::Exact(v),
+                None => Real::Float(-v.to_f64()),
+            },
+            Real::Float(v) => Real::Float(-v),
+        }
+    }
+}
+
+impl PartialOrd for Real {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Real::Exact(a), Real::Exact(b)) => Some(a.cmp(b)),
+            _ => self.to_f64().partial_cmp(&other.to_f64()),
+        }
+    }
+}
+
+impl Real {
+    /// Power; it's exact only for exact base and exact integer exponent.
+    pub fn powf(self, exp: Real) -> Real {
+        match (self, exp) {
+            (Real::Exact(base), Real::Exact(exp)) if exp.is_integer() => {
+                match base.checked_pow(exp.num()) {
+                    Some(v) => Real::Exact(v),
+                    None => Real::Float(base.to_f64().powf(exp.to_f64())),
+                }
+            }
+            _ => Real::Float(self.to_f64().powf(exp.to_f64())),
+        }
+    }
+}
+
+impl Value {
+    /// Whether values are equal up to `eps`, e.g. points are at most `eps` apart.
+    ///
+    /// Objects are compared geometrically, see [Line::approx_eq].
+    /// Exact reals are compared exactly; ints, bools and strs are compared exactly.
+    /// Values of different types are never equal, e.g. `2` and `2.0`.
+    pub fn approx_eq(&self, other: &Value, eps: f64) -> bool {
+        match (self, other) {
+            (Value::Real(Some(a)), Value::Real(Some(b))) => match (a, b) {
+                (Real::Exact(_), Real::Exact(_)) => a == b,
+                _ => (a.to_f64() - b.to_f64()).abs() <= eps,
+            },
+            (Value::Pt(Some(a)), Value::Pt(Some(b))) => a.approx_eq(*b, eps),
+            (Value::Line(Some(a)), Value::Line(Some(b))) => a.approx_eq(*b, eps),
+            (Value::Circ(Some(a)), Value::Circ(Some(b))) => a.approx_eq(*b, eps),
+            (Value::Polyline(Some(a)), Value::Polyline(Some(b))) => {
+                a.pts.len() == b.pts.len()
+                    && a.pts.iter().zip(&b.pts).all(|(a, b)| a.approx_eq(*b, eps))
+            }
+            (a, b) => a == b,
+        }
+    }
+
+    /// Returns a point of the object, where it's label is attached,
+    /// and a unit vector, pointing away from the object.
+    ///
+    /// Y axis is expected to point down, so labels are put above and to the right.
+    pub fn label_anchor(&self) -> Option<(Pt, Pt)> {
+        let up_right = Pt::new(1.0, -1.0) / 2f64.sqrt();
+
+        match self {
+            Value::Pt(Some(pt)) => Some((*pt, up_right)),
+            Value::Line(Some(Line { p1, p2 })) => {
+                let mid = (*p1 + *p2) / 2.0;
+                let dir = *p2 - *p1;
+                if dir.len() == 0.0 {
+                    return Some((mid, up_right));
+                }
+
+                let normal = Pt {
+                    x: dir.y,
+                    y: -dir.x,
+                } / dir.len();
+                let (x, y) = (normal.x.to_f64(), normal.y.to_f64());
+                let normal = if y > 0.0 || (y == 0.0 && x < 0.0) {
+                    -normal
+                } else {
+                    normal
+                };
+
+                Some((mid, normal))
+            }
+            Value::Circ(Some(Circ { o, r })) => Some((*o + up_right * *r, up_right)),
+            Value::Polyline(Some(Polyline { pts })) => {
+                pts.get(pts.len() / 2).map(|pt| (*pt, up_right))
+            }
+            _ => None,
+        }
     }
 }
 
@@ -117,58 +690,55 @@ mod test {
 
     #[test]
     fn add() {
-        assert_eq!(
-            Pt { x: 1.0, y: 2.0 } + Pt { x: 5.0, y: 6.0 },
-            Pt { x: 6.0, y: 8.0 }
-        );
+        assert_eq!(Pt::new(1.0, 2.0) + Pt::new(5.0, 6.0), Pt::new(6.0, 8.0));
     }
 
     #[test]
     fn sub() {
-        assert_eq!(
-            Pt { x: 1.0, y: 2.0 } - Pt { x: 5.0, y: 3.0 },
-            Pt { x: -4.0, y: -1.0 }
-        );
+        assert_eq!(Pt::new(1.0, 2.0) - Pt::new(5.0, 3.0), Pt::new(-4.0, -1.0));
     }
 
     #[test]
     fn mul() {
-        assert_eq!(Pt { x: 3.0, y: 4.0 } * 5.0, Pt { x: 15.0, y: 20.0 });
-        assert_eq!(5.0 * Pt { x: 3.0, y: 4.0 }, Pt { x: 15.0, y: 20.0 });
+        assert_eq!(Pt::new(3.0, 4.0) * 5.0, Pt::new(15.0, 20.0));
+        assert_eq!(5.0 * Pt::new(3.0, 4.0), Pt::new(15.0, 20.0));
     }
 
     #[test]
     fn div() {
-        assert_eq!(Pt { x: 15.0, y: 20.0 } / 5.0, Pt { x: 3.0, y: 4.0 });
+        assert_eq!(Pt::new(15.0, 20.0) / 5.0, Pt::new(3.0, 4.0));
     }
 
     #[test]
     fn neg() {
-        assert_eq!(-Pt { x: 3.0, y: 1.0 }, Pt { x: -3.0, y: -1.0 });
+        assert_eq!(-Pt::new(3.0, 1.0), Pt::new(-3.0, -1.0));
     }
 
     #[test]
     fn len() {
-        assert_eq!(Pt { x: 3.0, y: 4.0 }.len(), 5.0);
+        assert_eq!(Pt::new(3.0, 4.0).len(), 5.0);
     }
 
     #[test]
     fn cross() {
-        assert_eq!(Pt { x: 2.0, y: 3.0 }.cross(Pt { x: 4.0, y: 5.0 }), -2.0);
+        assert_eq!(
+            Pt::new(2.0, 3.0).cross(Pt::new(4.0, 5.0)),
+            Real::Float(-2.0)
+        );
     }
 
     #[test]
     fn dist_pt_pt() {
-        assert_eq!(Pt { x: 7.0, y: 9.0 }.dist(Pt { x: 12.0, y: 21.0 }), 13.0);
+        assert_eq!(Pt::new(7.0, 9.0).dist(Pt::new(12.0, 21.0)), 13.0);
     }
 
     #[test]
     fn dist_line_pt() {
         let actual = Line {
-            p1: Pt { x: 1.0, y: 0.0 },
-            p2: Pt { x: 0.0, y: 1.0 },
+            p1: Pt::new(1.0, 0.0),
+            p2: Pt::new(0.0, 1.0),
         }
-        .dist(Pt { x: 1.0, y: 1.0 });
+        .dist(Pt::new(1.0, 1.0));
 
         let expected = 2f64.sqrt() / 2.0;
 
@@ -179,11 +749,375 @@ mod test {
     fn dist_circ_pt() {
         assert_eq!(
             Circ {
-                o: Pt { x: 1.0, y: 1.0 },
-                r: 1.0
+                o: Pt::new(1.0, 1.0),
+                r: 1.0.into()
             }
-            .dist(Pt { x: 2.0, y: 2.0 }),
+            .dist(Pt::new(2.0, 2.0)),
             2f64.sqrt() - 1.0
         )
     }
+
+    #[test]
+    fn clip() {
+        let pt = |x: f64, y: f64| Pt::new(x, y);
+        let (min, max) = (pt(0.0, 0.0), pt(4.0, 2.0));
+        let line = |x1, y1, x2, y2| Line {
+            p1: pt(x1, y1),
+            p2: pt(x2, y2),
+        };
+
+        assert_eq!(
+            line(1.0, 1.0, 2.0, 1.0).clip(min, max),
+            Some(line(0.0, 1.0, 4.0, 1.0))
+        );
+        assert_eq!(
+            line(1.0, 1.0, 1.0, 5.0).clip(min, max),
+            Some(line(1.0, 0.0, 1.0, 2.0))
+        );
+        // Points outside of the rectangle are fine
+        assert_eq!(
+            line(-2.0, -2.0, -1.0, -1.0).clip(min, max),
+            Some(line(0.0, 0.0, 2.0, 2.0))
+        );
+
+        assert_eq!(line(0.0, 3.0, 1.0, 3.0).clip(min, max), None);
+        assert_eq!(line(5.0, 0.0, 6.0, 1.0).clip(min, max), None);
+        assert_eq!(line(1.0, 1.0, 1.0, 1.0).clip(min, max), None);
+    }
+
+    #[test]
+    fn label_anchor() {
+        let pt = |x: f64, y: f64| Pt::new(x, y);
+        let d = 1.0 / 2f64.sqrt();
+
+        assert_eq!(
+            Value::from(pt(1.0, 2.0)).label_anchor(),
+            Some((pt(1.0, 2.0), pt(d, -d)))
+        );
+
+        let (anchor, dir) = Value::from(Line {
+            p1: pt(0.0, 0.0),
+            p2: pt(2.0, 0.0),
+        })
+        .label_anchor()
+        .unwrap();
+        assert_eq!(anchor, pt(1.0, 0.0));
+        assert!(dir.dist(pt(0.0, -1.0)) < 1e-9);
+
+        let (_, dir) = Value::from(Line {
+            p1: pt(0.0, 2.0),
+            p2: pt(0.0, 0.0),
+        })
+        .label_anchor()
+        .unwrap();
+        assert!(dir.dist(pt(1.0, 0.0)) < 1e-9);
+
+        let (anchor, _) = Value::from(Circ {
+            o: pt(0.0, 0.0),
+            r: 2.0.into(),
+        })
+        .label_anchor()
+        .unwrap();
+        assert!(anchor.dist(pt(2.0 * d, -2.0 * d)) < 1e-9);
+
+        assert_eq!(Value::from(1).label_anchor(), None);
+    }
+
+    #[test]
+    fn project() {
+        use std::f64::consts::{PI, SQRT_2};
+
+        let pt = |x: f64, y: f64| Pt::new(x, y);
+        let line = Line {
+            p1: pt(1.0, 1.0),
+            p2: pt(3.0, 1.0),
+        };
+        assert_eq!(line.at(0.5.into()), pt(2.0, 1.0));
+        assert_eq!(line.project(pt(4.0, 5.0)), Some(1.5.into()));
+        assert_eq!(line.at(line.project(pt(2.0, -1.0)).unwrap()), pt(2.0, 1.0));
+        assert_eq!(
+            Line {
+                p1: pt(1.0, 1.0),
+                p2: pt(1.0, 1.0)
+            }
+            .project(pt(0.0, 0.0)),
+            None
+        );
+
+        let circ = Circ {
+            o: pt(1.0, 1.0),
+            r: 2.0.into(),
+        };
+        assert!(circ.at(PI / 2.0).dist(pt(1.0, 3.0)) < 1e-9);
+        assert!((circ.project(pt(-5.0, 1.0)) - PI).abs() < 1e-9);
+        assert!(
+            circ.at(circ.project(pt(4.0, 4.0)))
+                .dist(pt(1.0 + SQRT_2, 1.0 + SQRT_2))
+                < 1e-9
+        );
+
+        let pt_on = PtOn {
+            object: circ.into(),
+            param: "t".into(),
+        };
+        assert!((pt_on.project(pt(1.0, 5.0)).unwrap().to_f64() - PI / 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn approx_eq() {
+        let pt = |x: f64, y: f64| Pt::new(x, y);
+        let line = |x1, y1, x2, y2| Line {
+            p1: pt(x1, y1),
+            p2: pt(x2, y2),
+        };
+        let eps = 1e-9;
+
+        assert!(pt(0.1 + 0.2, 0.0).approx_eq(pt(0.3, 0.0), eps));
+        assert!(!pt(0.0, 0.0).approx_eq(pt(0.0, 1e-6), eps));
+
+        assert!(line(0.0, 0.0, 1.0, 1.0).approx_eq(line(1.0, 1.0, 0.0, 0.0), eps));
+        assert!(line(0.0, 0.0, 1.0, 1.0).approx_eq(line(-2.0, -2.0, 5.0, 5.0), eps));
+        assert!(!line(0.0, 0.0, 1.0, 1.0).approx_eq(line(0.0, 1.0, 1.0, 2.0), eps));
+        assert!(!line(0.0, 0.0, 1.0, 1.0).approx_eq(line(0.0, 0.0, 0.0, 0.0), eps));
+        assert!(line(1.0, 1.0, 1.0, 1.0).approx_eq(line(1.0, 1.0, 1.0, 1.0), eps));
+
+        let circ = Circ {
+            o: pt(1.0, 1.0),
+            r: 2.0.into(),
+        };
+        assert!(circ.approx_eq(
+            Circ {
+                r: (2.0 + 1e-12).into(),
+                ..circ
+            },
+            eps
+        ));
+        assert!(!circ.approx_eq(
+            Circ {
+                r: 2.1.into(),
+                ..circ
+            },
+            eps
+        ));
+    }
+
+    #[test]
+    fn predicates() {
+        let pt = |x: f64, y: f64| Pt::new(x, y);
+        let line = |x1, y1, x2, y2| Line {
+            p1: pt(x1, y1),
+            p2: pt(x2, y2),
+        };
+        let eps = 1e-9;
+
+        assert_eq!(
+            line(0.0, 0.0, 1.0, 1.0).parallel(line(0.0, 1.0, -2.0, -1.0), eps),
+            Some(true)
+        );
+        assert_eq!(
+            line(0.0, 0.0, 1.0, 1.0).parallel(line(0.0, 1.0, 1.0, 0.0), eps),
+            Some(false)
+        );
+        assert_eq!(
+            line(0.0, 0.0, 1.0, 1.0).parallel(line(1.0, 1.0, 1.0, 1.0), eps),
+            None
+        );
+        assert_eq!(
+            line(0.0, 0.0, 1.0, 1.0).perpendicular(line(0.0, 1.0, 1.0, 0.0), eps),
+            Some(true)
+        );
+        assert_eq!(
+            line(0.0, 0.0, 1.0, 1.0).perpendicular(line(0.0, 1.0, 1.0, 1.0), eps),
+            Some(false)
+        );
+
+        assert!(Pt::collinear(pt(0.0, 0.0), pt(1.0, 1.0), pt(0.3, 0.3), eps));
+        assert!(Pt::collinear(pt(0.0, 0.0), pt(0.0, 0.0), pt(5.0, 3.0), eps));
+        assert!(!Pt::collinear(
+            pt(0.0, 0.0),
+            pt(1.0, 1.0),
+            pt(0.3, 0.4),
+            eps
+        ));
+
+        let on_circle = |angle: f64| pt(1.0 + 2.0 * angle.cos(), -1.0 + 2.0 * angle.sin());
+        assert!(Pt::concyclic(
+            on_circle(0.1),
+            on_circle(1.0),
+            on_circle(2.0),
+            on_circle(4.0),
+            eps
+        ));
+        assert!(!Pt::concyclic(
+            on_circle(0.1),
+            on_circle(1.0),
+            on_circle(2.0),
+            pt(1.0, 1.1),
+            eps
+        ));
+        assert!(Pt::concyclic(
+            pt(0.0, 0.0),
+            pt(1.0, 0.0),
+            pt(2.0, 0.0),
+            pt(3.0, 0.0),
+            eps
+        ));
+        assert!(!Pt::concyclic(
+            pt(0.0, 0.0),
+            pt(1.0, 0.0),
+            pt(2.0, 0.0),
+            pt(3.0, 1.0),
+            eps
+        ));
+    }
+
+    #[test]
+    fn value_approx_eq() {
+        let eps = 1e-9;
+        assert!(Value::from(0.1 + 0.2).approx_eq(&Value::from(0.3), eps));
+        assert!(!Value::from(2).approx_eq(&Value::from(2.0), eps));
+        assert!(!Value::from(1.0).approx_eq(&Value::from(1.1), eps));
+        assert!(Value::from(Pt::new(1.0, 1e-12)).approx_eq(&Value::from(Pt::new(1.0, 0.0)), eps));
+        assert!(!Value::from(Pt::new(1.0, 0.0)).approx_eq(&Value::from(Pt::new(0.0, 1.0)), eps));
+        assert!(Value::Pt(None).approx_eq(&Value::Pt(None), eps));
+        assert!(!Value::Pt(None).approx_eq(&Value::from(Pt::new(0.0, 0.0)), eps));
+        assert!(!Value::from(1.0).approx_eq(&Value::from(Pt::new(1.0, 1.0)), eps));
+
+        // Lines are the same regardless of their points
+        let line = |x1, y1, x2, y2| Value::from(Line::new(Pt::new(x1, y1), Pt::new(x2, y2)));
+        assert!(line(0.0, 0.0, 1.0, 1.0).approx_eq(&line(3.0, 3.0, -1.0, -1.0), eps));
+        assert!(!line(0.0, 0.0, 1.0, 1.0).approx_eq(&line(0.0, 0.0, 1.0, 2.0), eps));
+
+        // Exact reals are compared exactly
+        let exact = |num, den| Value::from(Real::Exact(Rational::new(num, den).unwrap()));
+        assert!(exact(1, 3).approx_eq(&exact(2, 6), eps));
+        assert!(!exact(1, 1_000_000_000_000).approx_eq(&exact(0, 1), eps));
+        assert!(exact(1, 1_000_000_000_000).approx_eq(&Value::from(0.0), eps));
+    }
+
+    #[test]
+    fn exact_pts() {
+        let frac = |num, den| Real::Exact(Rational::new(num, den).unwrap());
+        let pt = |x: i64, y: i64| Pt::new(x, y);
+        let eps = 1e-9;
+
+        // Nearly equal exact points differ
+        let tiny = Pt::new(frac(1, 1_000_000_000_000), 0);
+        let tiny_up = Pt::new(0, frac(1, 1_000_000_000_000));
+        assert!(tiny.is_exact());
+        assert!(!tiny.approx_eq(pt(0, 0), eps));
+        assert!(!Pt::collinear(pt(0, 0), pt(1, 0), pt(2, 0) + tiny_up, eps));
+
+        let line = Line::new(pt(0, 0), pt(3, 1));
+        assert_eq!(line.project(pt(1, 3)), Some(frac(3, 5)));
+        assert_eq!(line.at(frac(3, 5)), Pt::new(frac(9, 5), frac(3, 5)));
+        assert!(line.contains(Pt::new(frac(1, 2), frac(1, 6)), eps));
+        assert!(!line.contains(Pt::new(frac(1, 2), frac(1, 6)) + tiny_up, eps));
+
+        let circ = Circ::new(pt(0, 0), 5);
+        assert!(circ.contains(pt(3, 4), eps));
+        assert!(!circ.contains(pt(3, 4) + tiny, eps));
+    }
+
+    #[test]
+    fn transform() {
+        use std::f64::consts::FRAC_PI_2;
+
+        let pt = |x: f64, y: f64| Pt::new(x, y);
+        let eps = 1e-9;
+
+        let t = Transform::translation(pt(1.0, 2.0));
+        assert_eq!(t.apply_pt(pt(3.0, 4.0)), pt(4.0, 6.0));
+
+        let r = Transform::rotation(pt(1.0, 1.0), FRAC_PI_2);
+        assert!(r.apply_pt(pt(2.0, 1.0)).approx_eq(pt(1.0, 2.0), eps));
+        assert!(r.apply_pt(pt(1.0, 1.0)).approx_eq(pt(1.0, 1.0), eps));
+
+        let h = Transform::homothety(pt(1.0, 0.0), 3.0.into());
+        assert_eq!(h.apply_pt(pt(2.0, 1.0)), pt(4.0, 3.0));
+        let c = h.apply_circ(Circ {
+            o: pt(1.0, 1.0),
+            r: 2.0.into(),
+        });
+        assert!(c.approx_eq(
+            Circ {
+                o: pt(1.0, 3.0),
+                r: 6.0.into()
+            },
+            eps
+        ));
+
+        let s = Transform::reflection(Line::new(pt(0.0, 1.0), pt(1.0, 2.0))).unwrap();
+        assert!(s.apply_pt(pt(1.0, 0.0)).approx_eq(pt(-1.0, 2.0), eps));
+        assert!((s * s).apply_pt(pt(3.0, 5.0)).approx_eq(pt(3.0, 5.0), eps));
+        assert!(Transform::reflection(Line::new(pt(1.0, 1.0), pt(1.0, 1.0))).is_none());
+
+        // Composition applies the right transform first
+        assert!((t * r).apply_pt(pt(2.0, 1.0)).approx_eq(pt(2.0, 4.0), eps));
+        assert!((r * t).apply_pt(pt(1.0, -1.0)).approx_eq(pt(1.0, 2.0), eps));
+
+        let composed = t * r * h * s;
+        let p = pt(0.3, -1.7);
+        let back = composed.inverse().unwrap().apply_pt(composed.apply_pt(p));
+        assert!(back.approx_eq(p, eps));
+        assert!(Transform::homothety(p, 0.0.into()).inverse().is_none());
+        assert_eq!(Transform::identity().apply_pt(p), p);
+    }
+
+    #[test]
+    fn exact_transform() {
+        let frac = |num, den| Real::Exact(Rational::new(num, den).unwrap());
+        let is_exact = |t: Transform| [t.a, t.b, t.c, t.d, t.e, t.f].iter().all(|v| v.is_exact());
+
+        let t = Transform::translation(Pt::new(1, 2))
+            * Transform::homothety(Pt::new(1, 0), frac(1, 3))
+            * Transform::reflection(Line::new(Pt::new(0, 1), Pt::new(1, 3))).unwrap();
+        assert!(is_exact(t));
+        assert!(is_exact(t.inverse().unwrap()));
+
+        let p = t.apply_pt(Pt::new(2, 0));
+        assert!(p.x.is_exact() && p.y.is_exact());
+        assert_eq!(p, Pt::new(1, frac(8, 3)));
+        assert_eq!(t.inverse().unwrap().apply_pt(p), Pt::new(2, 0));
+
+        // Rotations are not
+        assert!(!is_exact(Transform::rotation(Pt::new(0, 0), 1.0)));
+    }
+
+    #[test]
+    fn real_ops() {
+        let exact = |num, den| Real::Exact(Rational::new(num, den).unwrap());
+
+        assert_eq!(exact(1, 10) + exact(2, 10), exact(3, 10));
+        assert!((exact(1, 10) + exact(2, 10)).is_exact());
+        assert_eq!(exact(1, 3) - exact(1, 2), exact(-1, 6));
+        assert_eq!(exact(2, 3) * exact(3, 4), exact(1, 2));
+        assert_eq!(exact(2, 3) / exact(4, 3), exact(1, 2));
+        assert_eq!(exact(7, 2) % exact(3, 2), exact(1, 2));
+        assert_eq!(exact(-7, 2) % exact(3, 2), exact(-1, 2));
+        assert_eq!(-exact(1, 2), exact(-1, 2));
+        assert!(exact(1, 3) < exact(1, 2));
+
+        assert_eq!(exact(2, 3).powf(exact(-2, 1)), exact(9, 4));
+        assert!(exact(2, 3).powf(exact(-2, 1)).is_exact());
+        assert!(!exact(2, 1).powf(exact(1, 2)).is_exact());
+
+        // Mixed operands and overflows fall back to floats
+        assert!(!(exact(1, 2) + Real::Float(0.5)).is_exact());
+        assert_eq!(exact(1, 2) + Real::Float(0.5), Real::Float(1.0));
+        assert!(!(exact(1, i64::MAX) * exact(1, 3)).is_exact());
+        assert!(!(exact(1, 1) / exact(0, 1)).is_exact());
+    }
+
+    #[test]
+    fn polyline_dist() {
+        let pt = |x: f64, y: f64| Pt::new(x, y);
+        let polyline = Polyline::new(vec![pt(0.0, 0.0), pt(2.0, 0.0), pt(2.0, 2.0)]);
+
+        assert_eq!(polyline.dist(pt(1.0, 1.0)), 1.0);
+        assert_eq!(polyline.dist(pt(3.0, 3.0)), 2f64.sqrt());
+        assert_eq!(polyline.dist(pt(-3.0, 4.0)), 5.0);
+        assert_eq!(Polyline::new(vec![pt(1.0, 1.0)]).dist(pt(1.0, 2.0)), 1.0);
+        assert_eq!(Polyline::new(vec![]).dist(pt(1.0, 2.0)), f64::INFINITY);
+    }
 }
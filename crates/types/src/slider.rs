@@ -0,0 +1,232 @@
+use std::fmt::{self, Display, Formatter};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::export::{Sweep, MAX_SWEEP_FRAMES};
+
+/// Frames of a continuous slider's sweep.
+const CONTINUOUS_FRAMES: u32 = 100;
+
+/// Range of a free int or real item, that is changed with a slider in the gui.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Slider {
+    pub min: f64,
+    pub max: f64,
+
+    /// Distance between values, that the slider snaps to;
+    /// `None` means continuous for real items and 1 for int items.
+    pub step: Option<f64>,
+}
+
+impl Default for Slider {
+    fn default() -> Self {
+        Slider {
+            min: 0.0,
+            max: 100.0,
+            step: None,
+        }
+    }
+}
+
+impl Slider {
+    /// Updates the slider with css-like declarations, e.g. `min: -1; max: 1; step: 0.1`.
+    ///
+    /// `step: none` makes the slider continuous.
+    pub fn apply(&mut self, decls: &str) -> Result<(), SliderError> {
+        for decl in decls
+            .split(';')
+            .map(str::trim)
+            .filter(|decl| !decl.is_empty())
+        {
+            let err = || SliderError(format!("bad declaration '{decl}'"));
+            let (property, value) = decl.split_once(':').ok_or_else(err)?;
+            let (property, value) = (property.trim(), value.trim());
+            let number = || value.parse::<f64>().ok().filter(|v| v.is_finite());
+
+            match property {
+                "min" => self.min = number().ok_or_else(err)?,
+                "max" => self.max = number().ok_or_else(err)?,
+                "step" if value == "none" => self.step = None,
+                "step" => self.step = Some(number().filter(|v| *v > 0.0).ok_or_else(err)?),
+                _ => return Err(err()),
+            }
+        }
+
+        if self.min >= self.max {
+            return Err(SliderError(format!(
+                "min ({}) should be less than max ({})",
+                self.min, self.max
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Clamps the value to the range and rounds it to the nearest step from `min`.
+    pub fn snap(&self, value: f64) -> f64 {
+        let value = value.clamp(self.min, self.max);
+        match self.step {
+            Some(step) => {
+                let snapped = self.min + ((value - self.min) / step).round() * step;
+                // The last step may be shorter
+                if snapped > self.max {
+                    snapped - step
+                } else {
+                    snapped
+                }
+            }
+            None => value,
+        }
+    }
+
+    /// Sweep from `min` to the last step, that stops at every step,
+    /// e.g. to play the slider in the gui.
+    ///
+    /// Sliders with more than [MAX_SWEEP_FRAMES] steps are swept like continuous ones,
+    /// but with [MAX_SWEEP_FRAMES] frames.
+    ///
+    /// `whole` means, that the item is an int one, so there is a step of 1 by default.
+    pub fn sweep(&self, whole: bool) -> Sweep {
+        let step = self.step.or(whole.then_some(1.0));
+        let Some(step) = step else {
+            return Sweep {
+                from: self.min,
+                to: self.max,
+                frames: CONTINUOUS_FRAMES,
+            };
+        };
+
+        let steps = ((self.max - self.min) / step + 1e-9).floor();
+        if steps >= MAX_SWEEP_FRAMES as f64 {
+            return Sweep {
+                from: self.min,
+                to: self.max,
+                frames: MAX_SWEEP_FRAMES,
+            };
+        }
+
+        Sweep {
+            from: self.min,
+            to: self.min + steps * step,
+            frames: steps as u32 + 1,
+        }
+    }
+}
+
+/// Same format as [Slider::apply] accepts.
+impl Display for Slider {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "min: {}; max: {}", self.min, self.max)?;
+        if let Some(step) = self.step {
+            write!(f, "; step: {step}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SliderError(pub String);
+
+impl Display for SliderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for SliderError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn apply() {
+        let mut slider = Slider::default();
+        slider.apply("min: -1; max: 1.5; step: 0.5").unwrap();
+        assert_eq!(
+            slider,
+            Slider {
+                min: -1.0,
+                max: 1.5,
+                step: Some(0.5)
+            }
+        );
+        assert_eq!(slider.to_string(), "min: -1; max: 1.5; step: 0.5");
+
+        slider.apply("step: none").unwrap();
+        assert_eq!(slider.step, None);
+
+        for bad in [
+            "min: 200", "step: 0", "step: -1", "max: x", "width: 2", "min",
+        ] {
+            assert!(Slider::default().apply(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn snap() {
+        let slider = Slider {
+            min: 1.0,
+            max: 2.0,
+            step: Some(0.3),
+        };
+        assert_eq!(slider.snap(0.0), 1.0);
+        assert!((slider.snap(1.35) - 1.3).abs() < 1e-9);
+        assert!((slider.snap(2.0) - 1.9).abs() < 1e-9);
+
+        let slider = Slider {
+            step: None,
+            ..slider
+        };
+        assert_eq!(slider.snap(1.35), 1.35);
+        assert_eq!(slider.snap(3.0), 2.0);
+    }
+
+    #[test]
+    fn sweep() {
+        let slider = Slider {
+            min: 1.0,
+            max: 2.0,
+            step: Some(0.3),
+        };
+        let sweep = slider.sweep(false);
+        assert_eq!(sweep.frames, 4);
+        assert!((sweep.to - 1.9).abs() < 1e-9);
+
+        let slider = Slider {
+            min: 0.0,
+            max: 5.0,
+            step: None,
+        };
+        assert_eq!(
+            slider.sweep(true),
+            Sweep {
+                from: 0.0,
+                to: 5.0,
+                frames: 6,
+            }
+        );
+        assert_eq!(slider.sweep(false).frames, CONTINUOUS_FRAMES);
+
+        let slider = Slider {
+            min: 0.0,
+            max: 1e10,
+            step: None,
+        };
+        assert_eq!(
+            slider.sweep(true),
+            Sweep {
+                from: 0.0,
+                to: 1e10,
+                frames: MAX_SWEEP_FRAMES,
+            }
+        );
+        let slider = Slider {
+            step: Some(f64::MIN_POSITIVE),
+            ..slider
+        };
+        assert_eq!(slider.sweep(false).frames, MAX_SWEEP_FRAMES);
+    }
+}
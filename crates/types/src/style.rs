@@ -0,0 +1,329 @@
+use std::fmt::{self, Display, Formatter};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// How an item is drawn on the canvas and in exported images.
+///
+/// `None` fields are drawn in the default way.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Style {
+    /// Stroke color; points are filled with it.
+    pub color: Option<Color>,
+
+    /// Stroke width; radius for points.
+    pub width: Option<f64>,
+
+    /// Lengths of alternating dashes and gaps.
+    pub dash: Option<Vec<f64>>,
+
+    /// Fill color of circles.
+    pub fill: Option<Color>,
+
+    pub hidden: bool,
+
+    /// Text of the label; item's name by default.
+    pub label: Option<String>,
+
+    /// Overrides whether labels are shown for all items.
+    pub show_label: Option<bool>,
+}
+
+impl Style {
+    pub fn is_default(&self) -> bool {
+        self == &Style::default()
+    }
+
+    /// Updates the style with css-like declarations, e.g. `color: gray; dash: 4 2; hidden`.
+    ///
+    /// Labels are set with `label: show | hide | auto` and `label-text: <text>`.
+    ///
+    /// `default` resets the whole style, `<property>: default` resets one property.
+    pub fn apply(&mut self, decls: &str) -> Result<(), StyleError> {
+        for decl in decls
+            .split(';')
+            .map(str::trim)
+            .filter(|decl| !decl.is_empty())
+        {
+            let (property, value) = match decl.split_once(':') {
+                Some((property, value)) => (property.trim(), Some(value.trim())),
+                None => (decl, None),
+            };
+
+            let err = || StyleError(format!("bad declaration '{decl}'"));
+            let color = |value: &str| Color::parse(value).ok_or_else(err);
+
+            match (property, value) {
+                ("default", None) => *self = Style::default(),
+                ("hidden", None) => self.hidden = true,
+                ("visible", None) => self.hidden = false,
+
+                ("color", Some("default")) => self.color = None,
+                ("color", Some(value)) => self.color = Some(color(value)?),
+
+                ("width", Some("default")) => self.width = None,
+                ("width", Some(value)) => {
+                    self.width = Some(
+                        value
+                            .parse()
+                            .ok()
+                            .filter(|w: &f64| w.is_finite() && *w >= 0.0)
+                            .ok_or_else(err)?,
+                    )
+                }
+
+                ("dash", Some("default" | "none")) => self.dash = None,
+                ("dash", Some(value)) => {
+                    let dash = value
+                        .split_whitespace()
+                        .map(|len| {
+                            len.parse()
+                                .ok()
+                                .filter(|len: &f64| len.is_finite() && *len >= 0.0)
+                        })
+                        .collect::<Option<Vec<f64>>>()
+                        .filter(|dash| !dash.is_empty())
+                        .ok_or_else(err)?;
+                    self.dash = Some(dash);
+                }
+
+                ("fill", Some("default" | "none")) => self.fill = None,
+                ("fill", Some(value)) => self.fill = Some(color(value)?),
+
+                ("label", Some("show")) => self.show_label = Some(true),
+                ("label", Some("hide")) => self.show_label = Some(false),
+                ("label", Some("auto" | "default")) => self.show_label = None,
+
+                ("label-text", Some("default")) => self.label = None,
+                ("label-text", Some(value)) if !value.is_empty() => {
+                    self.label = Some(value.to_string())
+                }
+
+                _ => return Err(err()),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Same format as [Style::apply] accepts.
+impl Display for Style {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut decls = Vec::new();
+
+        if let Some(color) = self.color {
+            decls.push(format!("color: {color}"));
+        }
+        if let Some(width) = self.width {
+            decls.push(format!("width: {width}"));
+        }
+        if let Some(dash) = &self.dash {
+            let dash: Vec<_> = dash.iter().map(f64::to_string).collect();
+            decls.push(format!("dash: {}", dash.join(" ")));
+        }
+        if let Some(fill) = self.fill {
+            decls.push(format!("fill: {fill}"));
+        }
+        if self.hidden {
+            decls.push("hidden".to_string());
+        }
+        match self.show_label {
+            Some(true) => decls.push("label: show".to_string()),
+            Some(false) => decls.push("label: hide".to_string()),
+            None => {}
+        }
+        if let Some(label) = &self.label {
+            decls.push(format!("label-text: {label}"));
+        }
+
+        if decls.is_empty() {
+            write!(f, "default")
+        } else {
+            write!(f, "{}", decls.join("; "))
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StyleError(pub String);
+
+impl Display for StyleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for StyleError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Color { r, g, b, a: 255 }
+    }
+
+    /// Parses `#rgb`, `#rrggbb`, `#rrggbbaa` or one of a few color names.
+    pub fn parse(s: &str) -> Option<Self> {
+        let named = match s {
+            "black" => Some(Color::BLACK),
+            "white" => Some(Color::rgb(255, 255, 255)),
+            "gray" | "grey" => Some(Color::rgb(128, 128, 128)),
+            "red" => Some(Color::rgb(255, 0, 0)),
+            "green" => Some(Color::rgb(0, 128, 0)),
+            "blue" => Some(Color::rgb(0, 0, 255)),
+            "yellow" => Some(Color::rgb(255, 255, 0)),
+            "orange" => Some(Color::rgb(255, 165, 0)),
+            "purple" => Some(Color::rgb(128, 0, 128)),
+            "transparent" => Some(Color {
+                a: 0,
+                ..Color::BLACK
+            }),
+            _ => None,
+        };
+        if named.is_some() {
+            return named;
+        }
+
+        let hex = s.strip_prefix('#')?;
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let digits: Vec<u8> = match hex.len() {
+            // Short form: each digit is doubled
+            3 => hex
+                .chars()
+                .map(|c| c.to_digit(16).unwrap() as u8 * 17)
+                .collect(),
+            6 | 8 => (0..hex.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+                .collect(),
+            _ => return None,
+        };
+
+        Some(Color {
+            r: digits[0],
+            g: digits[1],
+            b: digits[2],
+            a: digits.get(3).copied().unwrap_or(255),
+        })
+    }
+}
+
+impl Display for Color {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let Color { r, g, b, a } = self;
+        write!(f, "#{r:02x}{g:02x}{b:02x}")?;
+        if *a != 255 {
+            write!(f, "{a:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn color() {
+        assert_eq!(Color::parse("red"), Some(Color::rgb(255, 0, 0)));
+        assert_eq!(Color::parse("#0a0"), Some(Color::rgb(0, 170, 0)));
+        assert_eq!(Color::parse("#12ab34"), Some(Color::rgb(0x12, 0xab, 0x34)));
+        assert_eq!(
+            Color::parse("#12ab3480"),
+            Some(Color {
+                r: 0x12,
+                g: 0xab,
+                b: 0x34,
+                a: 0x80
+            })
+        );
+
+        assert_eq!(Color::parse("#12ab3"), None);
+        assert_eq!(Color::parse("#zzz"), None);
+        assert_eq!(Color::parse("reddish"), None);
+
+        for s in ["#12ab34", "#12ab3480"] {
+            assert_eq!(Color::parse(s).unwrap().to_string(), s);
+        }
+    }
+
+    #[test]
+    fn apply() {
+        let mut style = Style::default();
+        style
+            .apply("color: gray; width: 2; dash: 4 2.5; fill: #ff000080; hidden;")
+            .unwrap();
+        assert_eq!(
+            style,
+            Style {
+                color: Some(Color::rgb(128, 128, 128)),
+                width: Some(2.0),
+                dash: Some(vec![4.0, 2.5]),
+                fill: Some(Color {
+                    r: 255,
+                    g: 0,
+                    b: 0,
+                    a: 128
+                }),
+                hidden: true,
+                label: None,
+                show_label: None,
+            }
+        );
+        assert_eq!(
+            style.to_string(),
+            "color: #808080; width: 2; dash: 4 2.5; fill: #ff000080; hidden"
+        );
+
+        let mut parsed = Style::default();
+        parsed.apply(&style.to_string()).unwrap();
+        assert_eq!(parsed, style);
+
+        style.apply("visible; dash: none; color: default").unwrap();
+        assert_eq!(style.to_string(), "width: 2; fill: #ff000080");
+
+        style.apply("label: show; label-text: A'").unwrap();
+        assert_eq!(style.show_label, Some(true));
+        assert_eq!(style.label.as_deref(), Some("A'"));
+        assert_eq!(
+            style.to_string(),
+            "width: 2; fill: #ff000080; label: show; label-text: A'"
+        );
+        style.apply("label: auto; label-text: default").unwrap();
+        assert_eq!(style.to_string(), "width: 2; fill: #ff000080");
+
+        style.apply("default").unwrap();
+        assert!(style.is_default());
+        assert_eq!(style.to_string(), "default");
+
+        for decls in [
+            "width: -1",
+            "width: inf",
+            "width: NaN",
+            "dash:",
+            "dash: 4 inf",
+            "color: reddish",
+            "size: 2",
+            "hidden: 1",
+            "label: maybe",
+            "label-text:",
+        ] {
+            assert!(Style::default().apply(decls).is_err(), "{decls}");
+        }
+    }
+}
@@ -143,6 +143,7 @@
             cratePath = ./crates/client;
             isBinary = false;
             localDeps = [
+              ./crates/executor
               ./crates/parser
               ./crates/types
             ];
@@ -157,6 +158,7 @@
             isBinary = true;
             localDeps = [
               ./crates/client
+              ./crates/executor
               ./crates/parser
               ./crates/types
             ];
@@ -229,6 +231,19 @@
             testRuntimeDeps = [(buildCrate server).packages.server];
           };
 
+          lsp = {
+            cratePath = ./crates/lsp;
+            isBinary = true;
+            localDeps = [
+              ./crates/executor
+              ./crates/parser
+              ./crates/types
+            ];
+
+            buildInputs = with pkgs; [openssl];
+            nativeBuildInputs = with pkgs; [pkg-config];
+          };
+
           executor = {
             cratePath = ./crates/executor;
             isBinary = false;